@@ -0,0 +1,96 @@
+use anyhow::Result;
+use axum::{
+    http::{header, HeaderName, HeaderValue, Method},
+    routing::{get, post},
+    Router,
+};
+use clap::Parser;
+use tower_http::{
+    cors::{Any, CorsLayer},
+    set_header::SetResponseHeaderLayer,
+};
+
+use client::load_cfg;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+mod metadata;
+mod routes;
+mod spec;
+use routes::*;
+use spec::ACTION_VERSION;
+
+const X_ACTION_VERSION: HeaderName = HeaderName::from_static("x-action-version");
+const X_BLOCKCHAIN_IDS: HeaderName = HeaderName::from_static("x-blockchain-ids");
+
+/// Solana Actions server answering and claiming blinks from shared links.
+#[derive(Debug, Parser)]
+pub struct Opts {
+    /// Same config the cli uses, for the rpc url and program id.
+    #[clap(long, default_value = "client_config.ini")]
+    pub config: String,
+    #[clap(long, default_value = "127.0.0.1:8080")]
+    pub bind: SocketAddr,
+    /// Icon url for blinks without a picture.
+    #[clap(long, default_value = "")]
+    pub icon: String,
+    /// Fetch blink metadata over plain `http` too, not only `https`. Private
+    /// and loopback hosts stay refused either way.
+    #[clap(long)]
+    pub allow_http_metadata: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let opts = Opts::parse();
+    let state = Arc::new(AppState {
+        config: load_cfg(&opts.config)?,
+        icon: opts.icon,
+        allow_http_metadata: opts.allow_http_metadata,
+    });
+
+    // CAIP-2 id of whichever cluster the config points at
+    let blockchain_id = {
+        let state = state.clone();
+        tokio::task::spawn_blocking(move || -> Result<String> {
+            let genesis_hash = state.rpc_client().get_genesis_hash()?.to_string();
+            Ok(format!("solana:{}", &genesis_hash[..32]))
+        })
+        .await??
+    };
+
+    let cors = CorsLayer::new()
+        .allow_origin(Any)
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::OPTIONS])
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::AUTHORIZATION,
+            header::CONTENT_ENCODING,
+            header::ACCEPT_ENCODING,
+            X_ACTION_VERSION,
+            X_BLOCKCHAIN_IDS,
+        ])
+        .expose_headers([X_ACTION_VERSION, X_BLOCKCHAIN_IDS]);
+
+    let app = Router::new()
+        .route("/actions.json", get(actions_json))
+        .route("/api/blink/:index", get(get_blink))
+        .route("/api/blink/:index/submit", post(post_submit))
+        .route("/api/blink/:index/claim", post(post_claim))
+        .route("/api/blink/:index/results", post(post_results))
+        .layer(SetResponseHeaderLayer::overriding(
+            X_ACTION_VERSION,
+            HeaderValue::from_static(ACTION_VERSION),
+        ))
+        .layer(SetResponseHeaderLayer::overriding(
+            X_BLOCKCHAIN_IDS,
+            HeaderValue::from_str(&blockchain_id)?,
+        ))
+        .layer(cors)
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(opts.bind).await?;
+    println!("blink actions on http://{}", opts.bind);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
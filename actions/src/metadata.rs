@@ -0,0 +1,147 @@
+//! Fetching the metadata documents of blinks for the server. Their `uri` is
+//! set by whoever creates a blink, so unlike the cli this never reads local
+//! files and only reaches public hosts.
+use anyhow::{format_err, Result};
+use reqwest::{blocking::Client, redirect, Url};
+use std::io::Read;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+use std::time::Duration;
+
+/// Largest document accepted, well above what a question with four options
+/// and a few translations needs.
+pub const MAX_METADATA_LEN: u64 = 64 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Download the document at `uri`, which has to be `https`, or `http` with
+/// `allow_http`, on a host that resolves to public addresses only. Redirects
+/// are not followed, they could lead anywhere.
+pub fn fetch_metadata(uri: &str, allow_http: bool) -> Result<Vec<u8>> {
+    let url = Url::parse(uri).map_err(|e| format_err!("invalid metadata uri {}: {}", uri, e))?;
+    match url.scheme() {
+        "https" => {}
+        "http" if allow_http => {}
+        scheme => return Err(format_err!("metadata uri scheme {} is not allowed", scheme)),
+    }
+    let host = url
+        .host_str()
+        .ok_or_else(|| format_err!("metadata uri {} has no host", uri))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| format_err!("metadata uri {} has no port", uri))?;
+
+    // connect to the address checked here, a second lookup could answer
+    // differently
+    let addr = public_addr(host, port)?;
+    let client = Client::builder()
+        .resolve(host, addr)
+        .redirect(redirect::Policy::none())
+        .timeout(FETCH_TIMEOUT)
+        .build()?;
+    let response = client.get(url).send()?.error_for_status()?;
+    if response.content_length().unwrap_or(0) > MAX_METADATA_LEN {
+        return Err(format_err!("metadata at {} is too large", uri));
+    }
+    let mut data = Vec::new();
+    response.take(MAX_METADATA_LEN + 1).read_to_end(&mut data)?;
+    if data.len() as u64 > MAX_METADATA_LEN {
+        return Err(format_err!("metadata at {} is too large", uri));
+    }
+    Ok(data)
+}
+
+/// The first address `host` resolves to, when none of them is private.
+fn public_addr(host: &str, port: u16) -> Result<SocketAddr> {
+    // `Url` keeps the brackets of an ipv6 host
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs: Vec<SocketAddr> = (host, port).to_socket_addrs()?.collect();
+    if let Some(addr) = addrs.iter().find(|addr| !is_public(addr.ip())) {
+        return Err(format_err!(
+            "metadata host {} resolves to non-public address {}",
+            host,
+            addr.ip()
+        ));
+    }
+    addrs
+        .into_iter()
+        .next()
+        .ok_or_else(|| format_err!("metadata host {} does not resolve", host))
+}
+
+/// Whether `ip` is routable on the internet, as opposed to loopback,
+/// link-local, private or otherwise reserved.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_v4(ip),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8 and the carrier-grade NAT range 100.64.0.0/10
+        || a == 0
+        || (a == 100 && b & 0xc0 == 64))
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // unique local fc00::/7 and link-local fe80::/10
+        || first & 0xfe00 == 0xfc00
+        || first & 0xffc0 == 0xfe80)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_internal_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in ["1.1.1.1", "8.8.8.8", "2606:4700:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn rejects_other_schemes_and_hosts() {
+        for uri in [
+            "file:///etc/passwd",
+            "/etc/passwd",
+            "ftp://example.com/blink.json",
+            "http://example.com/blink.json",
+            "https://127.0.0.1/blink.json",
+            "https://[::1]/blink.json",
+            "https://localhost/blink.json",
+        ] {
+            assert!(fetch_metadata(uri, false).is_err(), "{}", uri);
+        }
+        assert!(fetch_metadata("http://169.254.169.254/latest", true).is_err());
+    }
+}
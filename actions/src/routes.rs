@@ -0,0 +1,435 @@
+use anyhow::{format_err, Result};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig, instruction::Instruction, pubkey::Pubkey,
+    signature::Signature, transaction::Transaction,
+};
+
+use blink::state::*;
+use blink_sdk::{accounts as blink_accounts, rewards};
+use client::instructions::blink_instructions::{claim_instr_for, submit_instr_for};
+use client::instructions::clock::clock_now;
+use client::instructions::metadata::{metadata_hash, parse_metadata};
+use client::ClientConfig;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::metadata::fetch_metadata;
+use crate::spec::*;
+
+pub struct AppState {
+    pub config: ClientConfig,
+    /// Shown for blinks without a picture of their own.
+    pub icon: String,
+    /// Also fetch metadata over plain `http`, for local testing.
+    pub allow_http_metadata: bool,
+}
+
+impl AppState {
+    /// The builders only read accounts; the posting wallet signs and pays
+    /// for what they return.
+    pub fn rpc_client(&self) -> RpcClient {
+        RpcClient::new_with_commitment(self.config.http_url.clone(), CommitmentConfig::confirmed())
+    }
+}
+
+/// Any failure, answered the way the spec expects.
+pub struct ActionFailure(anyhow::Error);
+
+impl IntoResponse for ActionFailure {
+    fn into_response(self) -> Response {
+        let error = ActionError {
+            message: format!("{:#}", self.0),
+        };
+        (StatusCode::BAD_REQUEST, Json(error)).into_response()
+    }
+}
+
+impl<E: Into<anyhow::Error>> From<E> for ActionFailure {
+    fn from(err: E) -> Self {
+        ActionFailure(err.into())
+    }
+}
+
+/// The rpc client blocks, keep it off the async workers.
+async fn blocking<T, F>(f: F) -> Result<T, ActionFailure>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    Ok(tokio::task::spawn_blocking(f).await??)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlinkQuery {
+    /// Wallet to tailor the action to, when the client passes one.
+    pub account: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubmitQuery {
+    pub answer: u8,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClaimQuery {
+    pub series: Option<u64>,
+}
+
+pub async fn actions_json() -> Json<ActionsJson> {
+    Json(ActionsJson {
+        rules: vec![ActionRule {
+            path_pattern: "/blink/*".to_string(),
+            api_path: "/api/blink/*".to_string(),
+        }],
+    })
+}
+
+pub async fn get_blink(
+    State(state): State<Arc<AppState>>,
+    Path(index): Path<u64>,
+    Query(query): Query<BlinkQuery>,
+) -> Result<Json<ActionGetResponse>, ActionFailure> {
+    let account = query.account.as_deref().map(parse_account).transpose()?;
+    Ok(Json(
+        blocking(move || blink_action(&state, index, account)).await?,
+    ))
+}
+
+/// Next-action callback once a submit or claim has landed: the blink as the
+/// posting wallet now sees it, with the tallies so far.
+pub async fn post_results(
+    State(state): State<Arc<AppState>>,
+    Path(index): Path<u64>,
+    Json(request): Json<NextActionPostRequest>,
+) -> Result<Json<ActionGetResponse>, ActionFailure> {
+    let account = parse_account(&request.account)?;
+    let signature = Signature::from_str(&request.signature)
+        .map_err(|_| format_err!("invalid signature: {}", request.signature))?;
+    Ok(Json(
+        blocking(move || {
+            // read state only once our rpc node has seen the transaction
+            state.rpc_client().poll_for_signature(&signature)?;
+            blink_action(&state, index, Some(account))
+        })
+        .await?,
+    ))
+}
+
+pub async fn post_submit(
+    State(state): State<Arc<AppState>>,
+    Path(index): Path<u64>,
+    Query(query): Query<SubmitQuery>,
+    Json(request): Json<ActionPostRequest>,
+) -> Result<Json<ActionPostResponse>, ActionFailure> {
+    let account = parse_account(&request.account)?;
+    if !(1..=4).contains(&query.answer) {
+        return Err(format_err!("answer must be 1 to 4").into());
+    }
+    let response = blocking(move || {
+        let rpc_client = state.rpc_client();
+        let program_id = state.config.blink_program;
+        let instructions = submit_instr_for(
+            &rpc_client,
+            &program_id,
+            &account,
+            &account,
+            index,
+            query.answer,
+        )?;
+        Ok(ActionPostResponse {
+            transaction: unsigned_transaction(&rpc_client, &instructions, &account)?,
+            message: Some(format!("Answer {} to blink {}", query.answer, index)),
+            links: Some(results_link(index)),
+        })
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+pub async fn post_claim(
+    State(state): State<Arc<AppState>>,
+    Path(index): Path<u64>,
+    Query(query): Query<ClaimQuery>,
+    Json(request): Json<ActionPostRequest>,
+) -> Result<Json<ActionPostResponse>, ActionFailure> {
+    let account = parse_account(&request.account)?;
+    let response = blocking(move || {
+        let rpc_client = state.rpc_client();
+        let program_id = state.config.blink_program;
+        let blink = fetch_blink(
+            &rpc_client,
+            &program_id,
+            index,
+            Some(account),
+            state.allow_http_metadata,
+        )?;
+        if !blink.claimable() {
+            return Err(format_err!("no reward to claim on blink {}", index));
+        }
+        let instructions = claim_instr_for(
+            &rpc_client,
+            &program_id,
+            &account,
+            &account,
+            index,
+            query.series,
+        )?;
+        Ok(ActionPostResponse {
+            transaction: unsigned_transaction(&rpc_client, &instructions, &account)?,
+            message: Some(format!("Claim reward of blink {}", index)),
+            links: Some(results_link(index)),
+        })
+    })
+    .await?;
+    Ok(Json(response))
+}
+
+/// On-chain state of a blink, and of `account`'s submission when given.
+struct BlinkView {
+    pic: String,
+    content: String,
+    options: Vec<String>,
+    state: BlinkState,
+    submit: Option<SubmitState>,
+    now: u64,
+    /// Whether `now` and the blink's times are slots.
+    slot_clock: bool,
+    /// Whether the whole program is paused.
+    paused: bool,
+}
+
+impl BlinkView {
+    fn tallies(&self) -> [u32; 4] {
+        [
+            self.state.right1,
+            self.state.right2,
+            self.state.right3,
+            self.state.right4,
+        ]
+    }
+
+    fn claimable(&self) -> bool {
+        match &self.submit {
+            Some(submit) => rewards::claimable(&self.state, submit) > 0,
+            None => false,
+        }
+    }
+
+    fn option(&self, answer: u8) -> &str {
+        self.options
+            .get((answer as usize).wrapping_sub(1))
+            .map(String::as_str)
+            .unwrap_or("?")
+    }
+
+    /// Question followed by the tallies, marking the answer once known.
+    fn results(&self) -> String {
+        let mut lines = vec![self.content.clone()];
+        for (i, (option, tally)) in self.options.iter().zip(self.tallies()).enumerate() {
+            let answer = self.state.closed && self.state.answer as usize == i + 1;
+            lines.push(format!(
+                "{}{}: {}",
+                option,
+                if answer { " (answer)" } else { "" },
+                tally
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+fn fetch_blink(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    index: u64,
+    account: Option<Pubkey>,
+    allow_http_metadata: bool,
+) -> Result<BlinkView> {
+    let blink_config = blink_accounts::fetch_blink_config(rpc_client, program_id, index)?;
+    let state = blink_accounts::fetch_blink_state(rpc_client, program_id, index)?;
+
+    let (pic, content, options) = if blink_config.is_metadata() {
+        let data = fetch_metadata(&blink_config.uri, allow_http_metadata)?;
+        if metadata_hash(&data) != blink_config.metadata_hash {
+            return Err(format_err!(
+                "metadata of blink {} does not match its hash",
+                index
+            ));
+        }
+        let metadata = parse_metadata(&data)?;
+        (
+            metadata.images.into_iter().next().unwrap_or_default(),
+            metadata.question,
+            metadata.options,
+        )
+    } else {
+        (
+            blink_config.pic,
+            blink_config.content,
+            vec![
+                blink_config.option1,
+                blink_config.option2,
+                blink_config.option3,
+                blink_config.option4,
+            ],
+        )
+    };
+
+    let submit = match account {
+        Some(account) => {
+            blink_accounts::fetch_submit_state(rpc_client, program_id, index, &account)?
+        }
+        None => None,
+    };
+
+    let global_config = blink_accounts::fetch_global_config(rpc_client, program_id)?;
+    Ok(BlinkView {
+        pic,
+        content,
+        options,
+        state,
+        submit,
+        now: clock_now(rpc_client, global_config.slot_clock)?,
+        slot_clock: global_config.slot_clock,
+        paused: global_config.paused,
+    })
+}
+
+/// The action matching where the blink is in its lifecycle: paused, not open
+/// yet, open for answers, waiting for the answer, or resolved. Claiming is
+/// only offered to a wallet known to have a reward.
+fn blink_action(
+    state: &AppState,
+    index: u64,
+    account: Option<Pubkey>,
+) -> Result<ActionGetResponse> {
+    let blink = fetch_blink(
+        &state.rpc_client(),
+        &state.config.blink_program,
+        index,
+        account,
+        state.allow_http_metadata,
+    )?;
+
+    let mut response = ActionGetResponse {
+        kind: "action".to_string(),
+        icon: if blink.pic.is_empty() {
+            state.icon.clone()
+        } else {
+            blink.pic.clone()
+        },
+        title: format!("Blink #{}", index),
+        description: blink.content.clone(),
+        label: "Answer".to_string(),
+        disabled: false,
+        links: None,
+    };
+    let option_links = || {
+        let actions = blink
+            .options
+            .iter()
+            .enumerate()
+            .map(|(i, label)| LinkedAction {
+                kind: "transaction".to_string(),
+                href: format!("/api/blink/{}/submit?answer={}", index, i + 1),
+                label: label.clone(),
+            })
+            .collect();
+        Some(ActionLinks { actions })
+    };
+
+    if blink.paused || blink.state.frozen {
+        response.label = "Paused".to_string();
+        response.disabled = true;
+    } else if blink.now < blink.state.open_time {
+        response.description = format!(
+            "{}\nOpens at {}",
+            blink.content,
+            clock_label(blink.slot_clock, blink.state.open_time)
+        );
+        response.label = "Not open yet".to_string();
+        response.disabled = true;
+        response.links = option_links();
+    } else if !blink.state.closed {
+        if let Some(submit) = &blink.submit {
+            response.kind = "completed".to_string();
+            response.description = blink.results();
+            response.label = format!("You answered {}", blink.option(submit.answer));
+        } else if blink.now >= blink.state.close_time {
+            response.description = format!("{}\nWaiting for the answer", blink.content);
+            response.label = "Closed".to_string();
+            response.disabled = true;
+            response.links = option_links();
+        } else {
+            response.links = option_links();
+        }
+    } else {
+        response.description = blink.results();
+        match &blink.submit {
+            _ if blink.claimable() => {
+                response.label = "Claim reward".to_string();
+                response.links = Some(ActionLinks {
+                    actions: vec![LinkedAction {
+                        kind: "transaction".to_string(),
+                        href: format!("/api/blink/{}/claim", index),
+                        label: "Claim reward".to_string(),
+                    }],
+                });
+            }
+            Some(submit) => {
+                response.kind = "completed".to_string();
+                response.label = if submit.claim {
+                    "Reward claimed".to_string()
+                } else {
+                    format!("You answered {}", blink.option(submit.answer))
+                };
+            }
+            // also without a wallet to check
+            None => {
+                response.kind = "completed".to_string();
+                response.label = "Closed".to_string();
+            }
+        }
+    }
+    Ok(response)
+}
+
+fn clock_label(slot_clock: bool, time: u64) -> String {
+    if slot_clock {
+        format!("slot {}", time)
+    } else {
+        format!("unix time {}", time)
+    }
+}
+
+fn results_link(index: u64) -> PostLinks {
+    PostLinks {
+        next: NextActionLink::Post {
+            href: format!("/api/blink/{}/results", index),
+        },
+    }
+}
+
+fn parse_account(account: &str) -> Result<Pubkey> {
+    Pubkey::from_str(account).map_err(|_| format_err!("invalid account: {}", account))
+}
+
+/// Serialize `instructions` for `payer` to sign, as the spec expects.
+fn unsigned_transaction(
+    rpc_client: &RpcClient,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+) -> Result<String> {
+    let mut txn = Transaction::new_with_payer(instructions, Some(payer));
+    txn.message.recent_blockhash = rpc_client.get_latest_blockhash()?;
+    Ok(STANDARD.encode(bincode::serialize(&txn)?))
+}
@@ -0,0 +1,84 @@
+//! Request and response bodies of the Solana Actions spec.
+use serde::{Deserialize, Serialize};
+
+pub const ACTION_VERSION: &str = "2.1.3";
+
+/// Served at `/actions.json`, mapping website paths to action endpoints.
+#[derive(Debug, Serialize)]
+pub struct ActionsJson {
+    pub rules: Vec<ActionRule>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionRule {
+    pub path_pattern: String,
+    pub api_path: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActionGetResponse {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub icon: String,
+    pub title: String,
+    pub description: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<ActionLinks>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActionLinks {
+    pub actions: Vec<LinkedAction>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LinkedAction {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub href: String,
+    pub label: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActionPostRequest {
+    /// Wallet that signs and pays for the returned transaction.
+    pub account: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActionPostResponse {
+    /// Base64 bincode of the unsigned transaction.
+    pub transaction: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<PostLinks>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PostLinks {
+    /// Fetched once the transaction is confirmed.
+    pub next: NextActionLink,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NextActionLink {
+    Post { href: String },
+}
+
+/// Posted to a `NextActionLink::Post` href after the transaction lands.
+#[derive(Debug, Deserialize)]
+pub struct NextActionPostRequest {
+    pub account: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActionError {
+    pub message: String,
+}
@@ -1,235 +1,809 @@
-use anchor_client::{Client, Cluster};
-use anyhow::Result;
-use solana_sdk::{instruction::Instruction, pubkey::Pubkey, signature::Signer, system_program};
+use anyhow::{format_err, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    transaction::TransactionError,
+};
 use spl_associated_token_account as spl_associated;
 
-use blink::accounts as blink_accounts;
-use blink::instruction as blink_instructions;
-use blink::{state::*, AUTH_SEED};
-use std::rc::Rc;
+use blink::state::*;
+use blink_sdk::{
+    accounts as blink_accounts, instructions as blink_instructions, pda,
+    rewards::{streak_blinks, streak_bonus},
+};
 
-use crate::{read_keypair_file, ClientConfig};
+use crate::instructions::clock::{clock_now, fetch_slot_clock};
+use crate::instructions::query::LegacyAccount;
+use crate::instructions::rpc::transaction_error;
+use crate::instructions::token::{
+    add_transfer_hook_accounts, fetch_mint_info, unwrap_sol_instr, wrap_sol_instrs,
+};
+use crate::{BlinkContent, ClientConfig, TranslationContent};
+
+fn rpc_client(config: &ClientConfig) -> RpcClient {
+    RpcClient::new_with_commitment(config.http_url.clone(), CommitmentConfig::confirmed())
+}
+
+pub fn create_global_instr(config: &ClientConfig, admin: &Pubkey) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::create_global(
+        &config.blink_program,
+        admin,
+    )])
+}
+
+pub fn accept_admin_instr(
+    config: &ClientConfig,
+    pending_admin: Pubkey,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::accept_admin(
+        &config.blink_program,
+        &pending_admin,
+    )])
+}
+
+/// For `Role::Admin` the key is only proposed and has to accept the transfer.
+pub fn set_role_instr(
+    config: &ClientConfig,
+    admin: &Pubkey,
+    role: Role,
+    key: Pubkey,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::set_role(
+        &config.blink_program,
+        admin,
+        role,
+        key,
+    )])
+}
+
+pub fn pause_instr(
+    config: &ClientConfig,
+    pause_guardian: &Pubkey,
+    paused: bool,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::pause(
+        &config.blink_program,
+        pause_guardian,
+        paused,
+    )])
+}
+
+pub fn freeze_blink_instr(
+    config: &ClientConfig,
+    pause_guardian: &Pubkey,
+    index: u64,
+    frozen: bool,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::freeze_blink(
+        &config.blink_program,
+        pause_guardian,
+        index,
+        frozen,
+    )])
+}
 
 pub fn create_config_instr(
     config: &ClientConfig,
+    time_manager: &Pubkey,
     open_time: u64,
     period: u64,
 ) -> Result<Vec<Instruction>> {
-    let payer = read_keypair_file(&config.owner_path)?;
-    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
-    // Client.
-    let client = Client::new(url, Rc::new(payer));
-    let program = client.program(config.blink_program)?;
-
-    let (time_config_key, _bump) =
-        Pubkey::find_program_address(&[TIME_SEED.as_bytes()], &program.id());
-    let instructions = program
-        .request()
-        .accounts(blink_accounts::CreateTimeConfig {
-            owner: program.payer(),
-            time_config: time_config_key,
-            system_program: system_program::id(),
-        })
-        .args(blink_instructions::CreateTime { open_time, period })
-        .instructions()?;
-    Ok(instructions)
+    Ok(vec![blink_instructions::create_time(
+        &config.blink_program,
+        time_manager,
+        open_time,
+        period,
+    )])
 }
 
 pub fn update_config_instr(
     config: &ClientConfig,
+    time_manager: &Pubkey,
     open_time: u64,
     period: u64,
 ) -> Result<Vec<Instruction>> {
-    let payer = read_keypair_file(&config.owner_path)?;
-    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
-    // Client.
-    let client = Client::new(url, Rc::new(payer));
-    let program = client.program(config.blink_program)?;
-
-    let (time_config_key, _bump) =
-        Pubkey::find_program_address(&[TIME_SEED.as_bytes()], &program.id());
-    let instructions = program
-        .request()
-        .accounts(blink_accounts::UpdateTimeConfig {
-            owner: program.payer(),
-            time_config: time_config_key,
-        })
-        .args(blink_instructions::UpdateTime { open_time, period })
-        .instructions()?;
-    Ok(instructions)
+    Ok(vec![blink_instructions::update_time(
+        &config.blink_program,
+        time_manager,
+        open_time,
+        period,
+    )])
 }
 
-#[allow(clippy::too_many_arguments)]
+/// Where a new blink keeps its question and options.
+#[derive(Clone)]
+pub enum BlinkSource {
+    Inline(BlinkContent),
+    /// JSON document served at `uri`, see `metadata::BlinkMetadata`.
+    Metadata {
+        uri: String,
+        metadata_hash: [u8; 32],
+    },
+}
+
+/// Returns the instructions together with the blink index they allocate.
+///
+/// The index is the counter's next one when built, so the instructions only
+/// land while no other blink is created first; see `is_index_taken`. A
+/// sign-only initialize must therefore be broadcast before any other create.
 pub fn initialize_instr(
     config: &ClientConfig,
-    index: u16,
+    creator: &Pubkey,
     token_mint: Pubkey,
     amount: u64,
-    pic: String,
-    content: String,
-    option1: String,
-    option2: String,
-    option3: String,
-    option4: String,
-) -> Result<Vec<Instruction>> {
-    let creator = read_keypair_file(&config.creator_path)?;
-    let payer = read_keypair_file(&config.creator_path)?;
-    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
-    // Client.
-    let client = Client::new(url, Rc::new(payer));
-    let program = client.program(config.blink_program)?;
-
-    let (time_config_key, _bump) =
-        Pubkey::find_program_address(&[TIME_SEED.as_bytes()], &program.id());
-    let (blink_config_key, _bump1) = Pubkey::find_program_address(
-        &[CONFIG_SEED.as_bytes(), &index.to_le_bytes()[..]],
-        &program.id(),
-    );
+    source: BlinkSource,
+) -> Result<(Vec<Instruction>, u64)> {
+    let rpc_client = rpc_client(config);
+    let program_id = config.blink_program;
 
-    let (authority, _bump2) = Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &program.id());
-
-    let (blink_state_key, _bump) = Pubkey::find_program_address(
-        &[BLINK_SEED.as_bytes(), &index.to_le_bytes()[..]],
-        &program.id(),
+    let index = blink_accounts::fetch_blink_counter(&rpc_client, &program_id)?.next_index;
+    let mint_info = fetch_mint_info(&rpc_client, &token_mint)?;
+    let creator_token = spl_associated::get_associated_token_address_with_program_id(
+        creator,
+        &token_mint,
+        &mint_info.token_program,
     );
+    let vault = pda::vault(&program_id, &token_mint, &mint_info.token_program);
 
-    let creator_token =
-        spl_associated::get_associated_token_address(&creator.pubkey(), &token_mint);
-    let vault = spl_associated::get_associated_token_address(&authority, &token_mint);
-
-    let instructions = program
-        .request()
-        .accounts(blink_accounts::Initialize {
-            creator: creator.pubkey(),
-            authority,
-            time_config: time_config_key,
-            blink_config: blink_config_key,
-            blink_state: blink_state_key,
-            token_mint,
-            creator_token,
-            vault,
-            token_program: spl_token::id(),
-            associated_token_program: spl_associated::id(),
-            system_program: system_program::id(),
-        })
-        .args(blink_instructions::Initialize {
+    let mut instruction = match source {
+        BlinkSource::Inline(blink_content) => blink_instructions::initialize(
+            &program_id,
+            creator,
             index,
+            &token_mint,
+            &mint_info.token_program,
             amount,
-            pic,
-            content,
-            option1,
-            option2,
-            option3,
-            option4,
-        })
-        .instructions()?;
-    Ok(instructions)
+            blink_content.into(),
+        ),
+        BlinkSource::Metadata { uri, metadata_hash } => {
+            blink_instructions::initialize_with_metadata(
+                &program_id,
+                creator,
+                index,
+                &token_mint,
+                &mint_info.token_program,
+                amount,
+                uri,
+                metadata_hash,
+            )
+        }
+    };
+    if let Some(hook_program) = mint_info.transfer_hook {
+        add_transfer_hook_accounts(
+            &rpc_client,
+            &mut instruction,
+            &hook_program,
+            &creator_token,
+            &token_mint,
+            &vault,
+            creator,
+            amount,
+        )?;
+    }
+    let mut instructions = vec![instruction];
+    // pay a SOL pot from the creator's lamports
+    if token_mint == spl_token::native_mint::id() {
+        instructions.splice(0..0, wrap_sol_instrs(creator, amount)?);
+    }
+    Ok((instructions, index))
+}
+
+/// Whether an initialize failed because another creator took its index
+/// first, the blink accounts then no longer match the counter's seeds.
+/// Sending it again cannot help, it has to be built on the new index.
+pub fn is_index_taken(err: &anyhow::Error) -> bool {
+    matches!(
+        transaction_error(err),
+        Some(TransactionError::InstructionError(_, InstructionError::Custom(code)))
+            if code == u32::from(anchor_lang::error::ErrorCode::ConstraintSeeds)
+    )
+}
+
+pub fn update_blink_config_instr(
+    config: &ClientConfig,
+    creator: &Pubkey,
+    index: u64,
+    blink_content: BlinkContent,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::update_config(
+        &config.blink_program,
+        creator,
+        index,
+        blink_content.into(),
+    )])
+}
+
+pub fn update_metadata_instr(
+    config: &ClientConfig,
+    creator: &Pubkey,
+    index: u64,
+    uri: String,
+    metadata_hash: [u8; 32],
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::update_metadata(
+        &config.blink_program,
+        creator,
+        index,
+        uri,
+        metadata_hash,
+    )])
+}
+
+pub fn add_translation_instr(
+    config: &ClientConfig,
+    creator: &Pubkey,
+    index: u64,
+    lang: String,
+    translation: TranslationContent,
+) -> Result<Vec<Instruction>> {
+    // a code over 32 bytes cannot even derive the translation address
+    if !BlinkTranslation::check_lang(&lang) {
+        return Err(format_err!(
+            "invalid language code {:?}, at most {} letters, digits or `-`",
+            lang,
+            MAX_LANG_LEN
+        ));
+    }
+    Ok(vec![blink_instructions::add_translation(
+        &config.blink_program,
+        creator,
+        index,
+        lang,
+        translation.into(),
+    )])
+}
+
+pub fn submit_instr(
+    config: &ClientConfig,
+    user: &Pubkey,
+    payer: &Pubkey,
+    index: u64,
+    answer: u8,
+) -> Result<Vec<Instruction>> {
+    submit_instr_for(
+        &rpc_client(config),
+        &config.blink_program,
+        user,
+        payer,
+        index,
+        answer,
+    )
 }
 
-pub fn submit_instr(config: &ClientConfig, index: u16, answer: u8) -> Result<Vec<Instruction>> {
-    let user = read_keypair_file(&config.user_path)?;
-    let payer = read_keypair_file(&config.creator_path)?;
-    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
-    // Client.
-    let client = Client::new(url, Rc::new(payer));
-    let program = client.program(config.blink_program)?;
+/// Submit instructions signed by `user`, with `payer`, the user or a
+/// sponsor, paying the rent of the accounts they create.
+pub fn submit_instr_for(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    user: &Pubkey,
+    payer: &Pubkey,
+    index: u64,
+    answer: u8,
+) -> Result<Vec<Instruction>> {
+    // counted in the user's profile only once they have created one
+    let with_profile =
+        blink_accounts::account_exists(rpc_client, &pda::user_profile(program_id, user))?;
+    Ok(vec![blink_instructions::submit(
+        program_id,
+        user,
+        payer,
+        index,
+        answer,
+        with_profile,
+    )])
+}
 
-    let (blink_state_key, _bump) = Pubkey::find_program_address(
-        &[BLINK_SEED.as_bytes(), &index.to_le_bytes()[..]],
-        &program.id(),
+/// Start tracking `user`'s submits and claims, `payer` covering the rent.
+pub fn create_profile_instr(
+    config: &ClientConfig,
+    user: &Pubkey,
+    payer: &Pubkey,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::create_profile(
+        &config.blink_program,
+        user,
+        payer,
+    )])
+}
+
+pub fn close_instr(
+    config: &ClientConfig,
+    creator: &Pubkey,
+    index: u64,
+    answer: u8,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::close(
+        &config.blink_program,
+        creator,
+        index,
+        answer,
+    )])
+}
+
+pub fn extend_deadline_instr(
+    config: &ClientConfig,
+    creator: &Pubkey,
+    index: u64,
+    close_time: u64,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::extend_deadline(
+        &config.blink_program,
+        creator,
+        index,
+        close_time,
+    )])
+}
+
+pub fn end_early_instr(
+    config: &ClientConfig,
+    creator: &Pubkey,
+    index: u64,
+    close_time: u64,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::end_early(
+        &config.blink_program,
+        creator,
+        index,
+        close_time,
+    )])
+}
+
+/// Claim a reward; with `series` the claim also counts towards the user's
+/// streak in that series.
+pub fn claim_instr(
+    config: &ClientConfig,
+    user: &Pubkey,
+    payer: &Pubkey,
+    index: u64,
+    series: Option<u64>,
+) -> Result<Vec<Instruction>> {
+    claim_instr_for(
+        &rpc_client(config),
+        &config.blink_program,
+        user,
+        payer,
+        index,
+        series,
+    )
+}
+
+/// Claim instructions signed by `user`, with `payer`, the user or a
+/// sponsor, paying the rent of the accounts they create.
+pub fn claim_instr_for(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    user: &Pubkey,
+    payer: &Pubkey,
+    index: u64,
+    series: Option<u64>,
+) -> Result<Vec<Instruction>> {
+    let blink_state = blink_accounts::fetch_blink_state(rpc_client, program_id, index)?;
+    let authority = pda::authority(program_id);
+    let mint_info = fetch_mint_info(rpc_client, &blink_state.token_mint)?;
+    let user_token = spl_associated::get_associated_token_address_with_program_id(
+        user,
+        &blink_state.token_mint,
+        &mint_info.token_program,
     );
 
-    let (submit_state_key, _bump) = Pubkey::find_program_address(
-        &[
-            SUBMIT_SEED.as_bytes(),
-            &index.to_le_bytes()[..],
-            user.pubkey().to_bytes().as_ref(),
-        ],
-        &program.id(),
+    // only unwrap a wrapped SOL account the claim itself creates, so SOL the
+    // user already held wrapped stays wrapped
+    let unwrap_sol = blink_state.token_mint == spl_token::native_mint::id()
+        && !blink_accounts::account_exists(rpc_client, &user_token)?;
+
+    let mut instructions = Vec::new();
+    // the first claim in a series starts the user's streak
+    if let Some(id) = series {
+        if !blink_accounts::account_exists(rpc_client, &pda::streak(program_id, id, user))? {
+            instructions.push(blink_instructions::create_streak(
+                program_id, user, payer, id,
+            ));
+        }
+    }
+
+    // the streak is read from the user's answers on the blinks before this one
+    let (streak_blinks, bonus) = match series {
+        Some(id) => {
+            let series = blink_accounts::fetch_series(rpc_client, program_id, id)?;
+            let blinks = streak_blinks(&series, index, |index| {
+                answered_right(rpc_client, program_id, user, index)
+            })?;
+            let now = clock_now(rpc_client, fetch_slot_clock(rpc_client, program_id)?)?;
+            let bonus = streak_bonus(&series, index, blinks.len() as u32, blink_state.reward, now);
+            (blinks, bonus)
+        }
+        None => (Vec::new(), 0),
+    };
+
+    let mut claim = blink_instructions::claim(
+        program_id,
+        user,
+        payer,
+        index,
+        &blink_state.token_mint,
+        &mint_info.token_program,
+        blink_accounts::account_exists(rpc_client, &pda::user_profile(program_id, user))?,
+        series,
+        &streak_blinks,
     );
+    if let Some(hook_program) = mint_info.transfer_hook {
+        add_transfer_hook_accounts(
+            rpc_client,
+            &mut claim,
+            &hook_program,
+            &blink_state.vault,
+            &blink_state.token_mint,
+            &user_token,
+            &authority,
+            blink_state.reward,
+        )?;
+        // the bonus is a second transfer, out of the series vault
+        if let Some(id) = series.filter(|_| bonus > 0) {
+            add_transfer_hook_accounts(
+                rpc_client,
+                &mut claim,
+                &hook_program,
+                &pda::series_vault(
+                    program_id,
+                    id,
+                    &blink_state.token_mint,
+                    &mint_info.token_program,
+                ),
+                &blink_state.token_mint,
+                &user_token,
+                &pda::series(program_id, id),
+                bonus,
+            )?;
+        }
+    }
+    instructions.push(claim);
+    // hand a SOL reward back as lamports
+    if unwrap_sol {
+        instructions.push(unwrap_sol_instr(user)?);
+    }
+    Ok(instructions)
+}
 
-    let instructions = program
-        .request()
-        .accounts(blink_accounts::Submit {
-            user: user.pubkey(),
-            submit_state: submit_state_key,
-            blink_state: blink_state_key,
-            system_program: system_program::id(),
-        })
-        .args(blink_instructions::Submit { index, answer })
-        .instructions()?;
+/// Whether `user` answered blink `index` and it closed on their answer.
+fn answered_right(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    user: &Pubkey,
+    index: u64,
+) -> Result<bool> {
+    let submit_state = match blink_accounts::fetch_optional::<SubmitState>(
+        rpc_client,
+        &pda::submit_state(program_id, index, user),
+    )? {
+        Some(submit_state) => submit_state,
+        None => return Ok(false),
+    };
+    let blink_state = blink_accounts::fetch_blink_state(rpc_client, program_id, index)?;
+    Ok(blink_state.closed && blink_state.answer == submit_state.answer)
+}
+
+pub fn create_season_instr(
+    config: &ClientConfig,
+    owner: &Pubkey,
+    id: u64,
+    token_mint: Pubkey,
+    prize: u64,
+    end_time: u64,
+    top_n: u8,
+) -> Result<Vec<Instruction>> {
+    let rpc_client = rpc_client(config);
+    let program_id = config.blink_program;
+
+    let mint_info = fetch_mint_info(&rpc_client, &token_mint)?;
+    let owner_token = spl_associated::get_associated_token_address_with_program_id(
+        owner,
+        &token_mint,
+        &mint_info.token_program,
+    );
+    let vault = pda::vault(&program_id, &token_mint, &mint_info.token_program);
+
+    let mut instruction = blink_instructions::create_season(
+        &program_id,
+        owner,
+        id,
+        &token_mint,
+        &mint_info.token_program,
+        prize,
+        end_time,
+        top_n,
+    );
+    if let Some(hook_program) = mint_info.transfer_hook {
+        add_transfer_hook_accounts(
+            &rpc_client,
+            &mut instruction,
+            &hook_program,
+            &owner_token,
+            &token_mint,
+            &vault,
+            owner,
+            prize,
+        )?;
+    }
+    let mut instructions = vec![instruction];
+    // pay a SOL prize from the owner's lamports
+    if token_mint == spl_token::native_mint::id() {
+        instructions.splice(0..0, wrap_sol_instrs(owner, prize)?);
+    }
     Ok(instructions)
 }
 
-pub fn close_instr(config: &ClientConfig, index: u16, answer: u8) -> Result<Vec<Instruction>> {
-    let creator = read_keypair_file(&config.creator_path)?;
-    let payer = read_keypair_file(&config.creator_path)?;
-    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
-    // Client.
-    let client = Client::new(url, Rc::new(payer));
-    let program = client.program(config.blink_program)?;
+pub fn add_season_blink_instr(
+    config: &ClientConfig,
+    owner: &Pubkey,
+    id: u64,
+    index: u64,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::add_season_blink(
+        &config.blink_program,
+        owner,
+        id,
+        index,
+    )])
+}
 
-    let (blink_state_key, _bump) = Pubkey::find_program_address(
-        &[BLINK_SEED.as_bytes(), &index.to_le_bytes()[..]],
-        &program.id(),
+pub fn score_instr(
+    config: &ClientConfig,
+    payer: &Pubkey,
+    id: u64,
+    index: u64,
+    user: Pubkey,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::score(
+        &config.blink_program,
+        payer,
+        id,
+        index,
+        user,
+    )])
+}
+
+pub fn payout_season_instr(
+    config: &ClientConfig,
+    payer: &Pubkey,
+    id: u64,
+    rank: u8,
+) -> Result<Vec<Instruction>> {
+    let rpc_client = rpc_client(config);
+    let program_id = config.blink_program;
+
+    let season = blink_accounts::fetch_season(&rpc_client, &program_id, id)?;
+    let winner = season
+        .leaderboard
+        .get(rank as usize)
+        .ok_or_else(|| format_err!("season {} has no rank {}", id, rank))?
+        .user;
+
+    let mint_info = fetch_mint_info(&rpc_client, &season.token_mint)?;
+    let winner_token = spl_associated::get_associated_token_address_with_program_id(
+        &winner,
+        &season.token_mint,
+        &mint_info.token_program,
     );
 
-    //let (event_key, _bump) = Pubkey::find_program_address(&[b"__event_authority"], &program.id());
+    let mut instruction = blink_instructions::payout_season(
+        &program_id,
+        payer,
+        id,
+        rank,
+        &winner,
+        &season.token_mint,
+        &mint_info.token_program,
+    );
+    if let Some(hook_program) = mint_info.transfer_hook {
+        add_transfer_hook_accounts(
+            &rpc_client,
+            &mut instruction,
+            &hook_program,
+            &season.vault,
+            &season.token_mint,
+            &winner_token,
+            &pda::authority(&program_id),
+            blink_sdk::rewards::season_share(&season),
+        )?;
+    }
+    Ok(vec![instruction])
+}
 
-    let instructions = program
-        .request()
-        .accounts(blink_accounts::Close {
-            payer: creator.pubkey(),
-            blink_state: blink_state_key,
-            //event_authority: event_key,
-            //program: program.id(),
-        })
-        .args(blink_instructions::Close { index, answer })
-        .instructions()?;
+#[allow(clippy::too_many_arguments)]
+pub fn create_series_instr(
+    config: &ClientConfig,
+    owner: &Pubkey,
+    id: u64,
+    token_mint: Pubkey,
+    bonus: u64,
+    end_time: u64,
+    bonus_bps: u16,
+    max_streak: u32,
+) -> Result<Vec<Instruction>> {
+    let rpc_client = rpc_client(config);
+    let program_id = config.blink_program;
+
+    let mint_info = fetch_mint_info(&rpc_client, &token_mint)?;
+    let owner_token = spl_associated::get_associated_token_address_with_program_id(
+        owner,
+        &token_mint,
+        &mint_info.token_program,
+    );
+    let vault = pda::series_vault(&program_id, id, &token_mint, &mint_info.token_program);
+
+    let mut instruction = blink_instructions::create_series(
+        &program_id,
+        owner,
+        id,
+        &token_mint,
+        &mint_info.token_program,
+        bonus,
+        end_time,
+        bonus_bps,
+        max_streak,
+    );
+    if let Some(hook_program) = mint_info.transfer_hook {
+        add_transfer_hook_accounts(
+            &rpc_client,
+            &mut instruction,
+            &hook_program,
+            &owner_token,
+            &token_mint,
+            &vault,
+            owner,
+            bonus,
+        )?;
+    }
+    let mut instructions = vec![instruction];
+    // fund a SOL bonus from the owner's lamports
+    if token_mint == spl_token::native_mint::id() {
+        instructions.splice(0..0, wrap_sol_instrs(owner, bonus)?);
+    }
     Ok(instructions)
 }
 
-pub fn claim_instr(config: &ClientConfig, index: u16) -> Result<Vec<Instruction>> {
-    let user = read_keypair_file(&config.user_path)?;
-    let payer = read_keypair_file(&config.creator_path)?;
-    let url = Cluster::Custom(config.http_url.clone(), config.ws_url.clone());
-    // Client.
-    let client = Client::new(url, Rc::new(payer));
-    let program = client.program(config.blink_program)?;
-
-    let (submit_state_key, _bump) = Pubkey::find_program_address(
-        &[
-            SUBMIT_SEED.as_bytes(),
-            &index.to_le_bytes()[..],
-            user.pubkey().to_bytes().as_ref(),
-        ],
-        &program.id(),
+pub fn fund_series_instr(
+    config: &ClientConfig,
+    owner: &Pubkey,
+    id: u64,
+    amount: u64,
+) -> Result<Vec<Instruction>> {
+    let rpc_client = rpc_client(config);
+    let program_id = config.blink_program;
+
+    let series = blink_accounts::fetch_series(&rpc_client, &program_id, id)?;
+    let mint_info = fetch_mint_info(&rpc_client, &series.token_mint)?;
+    let owner_token = spl_associated::get_associated_token_address_with_program_id(
+        owner,
+        &series.token_mint,
+        &mint_info.token_program,
     );
 
-    let (blink_state_key, _bump) = Pubkey::find_program_address(
-        &[BLINK_SEED.as_bytes(), &index.to_le_bytes()[..]],
-        &program.id(),
+    let mut instruction = blink_instructions::fund_series(
+        &program_id,
+        owner,
+        id,
+        &series.token_mint,
+        &mint_info.token_program,
+        amount,
     );
-    let blink_state: blink::state::BlinkState = program.account(blink_state_key)?;
-    let (authority, __bump) = Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], &program.id());
-    let user_token =
-        spl_associated::get_associated_token_address(&user.pubkey(), &blink_state.token_mint);
-
-    let instructions = program
-        .request()
-        .accounts(blink_accounts::Claim {
-            user: user.pubkey(),
-            submit_state: submit_state_key,
-            blink_state: blink_state_key,
-            authority,
-            user_account: user_token,
-            vault: blink_state.vault,
-            token_mint: blink_state.token_mint,
-            token_program: spl_token::id(),
-            associated_token_program: spl_associated::id(),
-            system_program: system_program::id(),
+    if let Some(hook_program) = mint_info.transfer_hook {
+        add_transfer_hook_accounts(
+            &rpc_client,
+            &mut instruction,
+            &hook_program,
+            &owner_token,
+            &series.token_mint,
+            &series.vault,
+            owner,
+            amount,
+        )?;
+    }
+    let mut instructions = vec![instruction];
+    if series.token_mint == spl_token::native_mint::id() {
+        instructions.splice(0..0, wrap_sol_instrs(owner, amount)?);
+    }
+    Ok(instructions)
+}
+
+/// Return what is left of an ended series' bonus to `owner`, unwrapping a
+/// SOL bonus back into lamports.
+pub fn withdraw_series_instr(
+    config: &ClientConfig,
+    owner: &Pubkey,
+    id: u64,
+) -> Result<Vec<Instruction>> {
+    let rpc_client = rpc_client(config);
+    let program_id = config.blink_program;
+
+    let series = blink_accounts::fetch_series(&rpc_client, &program_id, id)?;
+    let mint_info = fetch_mint_info(&rpc_client, &series.token_mint)?;
+    let owner_token = spl_associated::get_associated_token_address_with_program_id(
+        owner,
+        &series.token_mint,
+        &mint_info.token_program,
+    );
+    let owner_token_exists = blink_accounts::account_exists(&rpc_client, &owner_token)?;
+    // as for a claim, only unwrap a wrapped SOL account created here
+    let unwrap_sol = series.token_mint == spl_token::native_mint::id() && !owner_token_exists;
+
+    let mut instructions = Vec::new();
+    // the owner's token account may be gone since funding, a wrapped SOL one
+    // most likely
+    if !owner_token_exists {
+        instructions.push(
+            spl_associated::instruction::create_associated_token_account_idempotent(
+                owner,
+                owner,
+                &series.token_mint,
+                &mint_info.token_program,
+            ),
+        );
+    }
+    let mut instruction = blink_instructions::withdraw_series(
+        &program_id,
+        owner,
+        id,
+        &series.token_mint,
+        &mint_info.token_program,
+    );
+    if let Some(hook_program) = mint_info.transfer_hook {
+        let amount = rpc_client
+            .get_token_account_balance(&series.vault)?
+            .amount
+            .parse()?;
+        add_transfer_hook_accounts(
+            &rpc_client,
+            &mut instruction,
+            &hook_program,
+            &series.vault,
+            &series.token_mint,
+            &owner_token,
+            &pda::series(&program_id, id),
+            amount,
+        )?;
+    }
+    instructions.push(instruction);
+    if unwrap_sol {
+        instructions.push(unwrap_sol_instr(owner)?);
+    }
+    Ok(instructions)
+}
+
+pub fn add_series_blink_instr(
+    config: &ClientConfig,
+    owner: &Pubkey,
+    id: u64,
+    index: u64,
+) -> Result<Vec<Instruction>> {
+    Ok(vec![blink_instructions::add_series_blink(
+        &config.blink_program,
+        owner,
+        id,
+        index,
+    )])
+}
+
+pub fn migrate_instr(
+    config: &ClientConfig,
+    payer: &Pubkey,
+    legacy: &[LegacyAccount],
+) -> Result<Vec<Instruction>> {
+    let instructions = legacy
+        .iter()
+        .map(|account| match *account {
+            LegacyAccount::TimeConfig => {
+                blink_instructions::migrate_time_config(&config.blink_program, payer)
+            }
+            LegacyAccount::BlinkState { index } => {
+                blink_instructions::migrate_blink_state(&config.blink_program, payer, index)
+            }
+            LegacyAccount::SubmitState { index, user } => {
+                blink_instructions::migrate_submit_state(&config.blink_program, payer, index, user)
+            }
         })
-        .args(blink_instructions::Claim { index })
-        .instructions()?;
+        .collect();
     Ok(instructions)
 }
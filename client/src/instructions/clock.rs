@@ -0,0 +1,85 @@
+use anyhow::{format_err, Result};
+use blink::utils::{SLOTS_PER_SECOND_DEN, SLOTS_PER_SECOND_NUM};
+use blink_sdk::accounts as blink_accounts;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const PERFORMANCE_SAMPLES: usize = 30;
+
+/// Parse a human duration such as `90s`, `15m`, `2h`, `1d` or `1w` into seconds.
+pub fn parse_duration(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value
+        .parse()
+        .map_err(|_| format_err!("invalid duration: {}", s))?;
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(format_err!("invalid duration unit: {}", s)),
+    };
+    value
+        .checked_mul(scale)
+        .ok_or_else(|| format_err!("duration overflow: {}", s))
+}
+
+/// Whether the deployed program measures blink windows in slots, as its
+/// global config records.
+pub fn fetch_slot_clock(rpc_client: &RpcClient, program_id: &Pubkey) -> Result<bool> {
+    Ok(blink_accounts::fetch_global_config(rpc_client, program_id)?.slot_clock)
+}
+
+/// Current point on the blink clock: the cluster slot with `slot_clock`,
+/// local unix time otherwise.
+pub fn clock_now(rpc_client: &RpcClient, slot_clock: bool) -> Result<u64> {
+    if slot_clock {
+        Ok(rpc_client.get_slot()?)
+    } else {
+        Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+    }
+}
+
+/// Convert seconds into blink clock units, estimating the slot rate from
+/// recent performance samples when running on slots.
+pub fn seconds_to_clock(rpc_client: &RpcClient, slot_clock: bool, secs: u64) -> Result<u64> {
+    if !slot_clock {
+        return Ok(secs);
+    }
+    let samples = rpc_client.get_recent_performance_samples(Some(PERFORMANCE_SAMPLES))?;
+    let slots: u64 = samples.iter().map(|s| s.num_slots).sum();
+    let period: u64 = samples.iter().map(|s| s.sample_period_secs as u64).sum();
+    if slots == 0 || period == 0 {
+        return Ok(secs * SLOTS_PER_SECOND_NUM / SLOTS_PER_SECOND_DEN);
+    }
+    Ok(secs * slots / period)
+}
+
+/// Resolve a period argument: a bare number is taken in clock units, a
+/// suffixed duration (`10m`, `2h`, ...) is converted.
+pub fn resolve_period(rpc_client: &RpcClient, slot_clock: bool, arg: &str) -> Result<u64> {
+    match arg.parse::<u64>() {
+        Ok(value) => Ok(value),
+        Err(_) => seconds_to_clock(rpc_client, slot_clock, parse_duration(arg)?),
+    }
+}
+
+/// Resolve a point in time: a bare number is an absolute clock value,
+/// `+<duration>` is relative to now.
+pub fn resolve_time(rpc_client: &RpcClient, slot_clock: bool, arg: &str) -> Result<u64> {
+    match arg.strip_prefix('+') {
+        Some(duration) => {
+            let offset = resolve_period(rpc_client, slot_clock, duration)?;
+            clock_now(rpc_client, slot_clock)?
+                .checked_add(offset)
+                .ok_or_else(|| format_err!("time overflow: {}", arg))
+        }
+        None => arg
+            .parse::<u64>()
+            .map_err(|_| format_err!("invalid time: {}", arg)),
+    }
+}
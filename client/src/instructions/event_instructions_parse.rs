@@ -1,13 +1,16 @@
 use anchor_client::ClientError;
-use anchor_lang::Discriminator;
+use anchor_lang::prelude::borsh;
+use anchor_lang::{event::EVENT_IX_TAG_LE, AnchorDeserialize, Discriminator};
 use base64::prelude::*;
 use blink::instruction;
 use blink::state::*;
 use colorful::Color;
 use colorful::Colorful;
 use regex::Regex;
+use solana_sdk::pubkey::Pubkey;
 use solana_transaction_status::{
-    option_serializer::OptionSerializer, EncodedTransaction, UiTransactionStatusMeta,
+    option_serializer::OptionSerializer, EncodedTransaction, UiInnerInstructions, UiInstruction,
+    UiTransactionStatusMeta,
 };
 
 const PROGRAM_LOG: &str = "Program log: ";
@@ -44,29 +47,11 @@ pub fn handle_program_log(
             }
         };
 
-        let mut slice: &[u8] = &borsh_bytes[..];
-        let disc: [u8; 8] = {
-            let mut disc = [0; 8];
-            disc.copy_from_slice(&borsh_bytes[..8]);
-            slice = &slice[8..];
-            disc
-        };
-        match disc {
-            InitializeEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<InitializeEvent>(&mut slice)?);
-            }
-            SubmitEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<SubmitEvent>(&mut slice)?);
-            }
-            CloseEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<CloseEvent>(&mut slice)?);
-            }
-            ClaimEvent::DISCRIMINATOR => {
-                println!("{:#?}", decode_event::<ClaimEvent>(&mut slice)?);
-            }
-            _ => {
-                println!("unknow event: {}", l);
-            }
+        // the program emitted through the logs before it moved to `emit_cpi!`,
+        // so what is found here can still be in an older layout
+        match decode_legacy_event(&borsh_bytes) {
+            Some(event) => println!("{:#?}", event),
+            None => handle_program_event(&borsh_bytes)?,
         }
         Ok((None, false))
     } else {
@@ -75,6 +60,199 @@ pub fn handle_program_log(
     }
 }
 
+/// Decode an event from its borsh bytes, as found in a `Program data:` log
+/// or in the data of an `emit_cpi!` self-CPI.
+pub fn handle_program_event(event_data: &[u8]) -> Result<(), ClientError> {
+    // events emitted through `emit_cpi!` are prefixed with the self-CPI tag
+    let event_data = event_data
+        .strip_prefix(&EVENT_IX_TAG_LE[..])
+        .unwrap_or(event_data);
+    if event_data.len() < 8 {
+        return Err(ClientError::LogParseError(format!(
+            "event data too short: {}",
+            BASE64_STANDARD.encode(event_data)
+        )));
+    }
+    let mut slice: &[u8] = event_data;
+    let disc: [u8; 8] = {
+        let mut disc = [0; 8];
+        disc.copy_from_slice(&event_data[..8]);
+        slice = &slice[8..];
+        disc
+    };
+    match disc {
+        AdminProposedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<AdminProposedEvent>(&mut slice)?);
+        }
+        RoleUpdatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<RoleUpdatedEvent>(&mut slice)?);
+        }
+        PauseEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<PauseEvent>(&mut slice)?);
+        }
+        FreezeEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<FreezeEvent>(&mut slice)?);
+        }
+        MigrateEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<MigrateEvent>(&mut slice)?);
+        }
+        TimeConfigCreatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<TimeConfigCreatedEvent>(&mut slice)?);
+        }
+        TimeConfigUpdatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<TimeConfigUpdatedEvent>(&mut slice)?);
+        }
+        InitializeEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<InitializeEvent>(&mut slice)?);
+        }
+        ConfigUpdatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<ConfigUpdatedEvent>(&mut slice)?);
+        }
+        TranslationAddedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<TranslationAddedEvent>(&mut slice)?);
+        }
+        SeasonCreatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<SeasonCreatedEvent>(&mut slice)?);
+        }
+        SeasonBlinkAddedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<SeasonBlinkAddedEvent>(&mut slice)?);
+        }
+        ScoreEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<ScoreEvent>(&mut slice)?);
+        }
+        SeasonPayoutEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<SeasonPayoutEvent>(&mut slice)?);
+        }
+        SeriesCreatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<SeriesCreatedEvent>(&mut slice)?);
+        }
+        SeriesBlinkAddedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<SeriesBlinkAddedEvent>(&mut slice)?);
+        }
+        SeriesFundedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<SeriesFundedEvent>(&mut slice)?);
+        }
+        SeriesWithdrawnEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<SeriesWithdrawnEvent>(&mut slice)?);
+        }
+        StreakCreatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<StreakCreatedEvent>(&mut slice)?);
+        }
+        StreakEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<StreakEvent>(&mut slice)?);
+        }
+        ProfileCreatedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<ProfileCreatedEvent>(&mut slice)?);
+        }
+        SubmitEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<SubmitEvent>(&mut slice)?);
+        }
+        CloseEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<CloseEvent>(&mut slice)?);
+        }
+        ClaimEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<ClaimEvent>(&mut slice)?);
+        }
+        DeadlineExtendedEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<DeadlineExtendedEvent>(&mut slice)?);
+        }
+        EndEarlyEvent::DISCRIMINATOR => {
+            println!("{:#?}", decode_event::<EndEarlyEvent>(&mut slice)?);
+        }
+        _ => {
+            println!("unknow event: {}", BASE64_STANDARD.encode(event_data));
+        }
+    }
+    Ok(())
+}
+
+/// Events as logged with `emit!` by program versions that indexed blinks with
+/// a `u16`. They share their names, and so their discriminators, with the
+/// current events; keep them as they are so old transactions still decode.
+#[allow(dead_code)]
+#[derive(Debug)]
+pub enum LegacyEvent {
+    Initialize(LegacyInitializeEvent),
+    Submit(LegacySubmitEvent),
+    Close(LegacyCloseEvent),
+    DeadlineExtended(LegacyDeadlineExtendedEvent),
+    EndEarly(LegacyEndEarlyEvent),
+    Claim(LegacyClaimEvent),
+}
+
+#[derive(Debug, AnchorDeserialize)]
+pub struct LegacyInitializeEvent {
+    pub index: u16,
+    pub creator: Pubkey,
+    pub valut: Pubkey,
+    pub token_mint: Pubkey,
+    pub config: Pubkey,
+    pub amount: u64,
+}
+
+#[derive(Debug, AnchorDeserialize)]
+pub struct LegacySubmitEvent {
+    pub index: u16,
+    pub user: Pubkey,
+    pub answer: u8,
+}
+
+#[derive(Debug, AnchorDeserialize)]
+pub struct LegacyCloseEvent {
+    pub index: u16,
+}
+
+#[derive(Debug, AnchorDeserialize)]
+pub struct LegacyDeadlineExtendedEvent {
+    pub index: u16,
+    pub old_close_time: u64,
+    pub close_time: u64,
+}
+
+#[derive(Debug, AnchorDeserialize)]
+pub struct LegacyEndEarlyEvent {
+    pub index: u16,
+    pub old_close_time: u64,
+    pub close_time: u64,
+}
+
+#[derive(Debug, AnchorDeserialize)]
+pub struct LegacyClaimEvent {
+    pub index: u16,
+    pub user: Pubkey,
+    pub reward: u64,
+}
+
+/// Decode `event_data` in one of the old layouts. The whole payload has to be
+/// consumed, a current event read as an old one would leave bytes over.
+pub fn decode_legacy_event(event_data: &[u8]) -> Option<LegacyEvent> {
+    if event_data.len() < 8 {
+        return None;
+    }
+    let (disc, data) = event_data.split_at(8);
+    match <[u8; 8]>::try_from(disc).ok()? {
+        InitializeEvent::DISCRIMINATOR => LegacyInitializeEvent::try_from_slice(data)
+            .ok()
+            .map(LegacyEvent::Initialize),
+        SubmitEvent::DISCRIMINATOR => LegacySubmitEvent::try_from_slice(data)
+            .ok()
+            .map(LegacyEvent::Submit),
+        CloseEvent::DISCRIMINATOR => LegacyCloseEvent::try_from_slice(data)
+            .ok()
+            .map(LegacyEvent::Close),
+        DeadlineExtendedEvent::DISCRIMINATOR => LegacyDeadlineExtendedEvent::try_from_slice(data)
+            .ok()
+            .map(LegacyEvent::DeadlineExtended),
+        EndEarlyEvent::DISCRIMINATOR => LegacyEndEarlyEvent::try_from_slice(data)
+            .ok()
+            .map(LegacyEvent::EndEarly),
+        ClaimEvent::DISCRIMINATOR => LegacyClaimEvent::try_from_slice(data)
+            .ok()
+            .map(LegacyEvent::Claim),
+        _ => None,
+    }
+}
+
 fn decode_event<T: anchor_lang::Event + anchor_lang::AnchorDeserialize>(
     slice: &mut &[u8],
 ) -> Result<T, ClientError> {
@@ -162,8 +340,17 @@ pub fn parse_program_instruction(
                     ) = instruction
                     {
                         if (ui_compiled_instruction.program_id_index as usize) == program_index {
-                            let out_put =
-                                format!("inner_instruction #{}.{}", inner.index + 1, i + 1);
+                            // self-CPIs carrying `emit_cpi!` events
+                            let is_event = bs58::decode(&ui_compiled_instruction.data)
+                                .into_vec()
+                                .map(|data| data.starts_with(&EVENT_IX_TAG_LE))
+                                .unwrap_or(false);
+                            let out_put = format!(
+                                "inner_{} #{}.{}",
+                                if is_event { "event" } else { "instruction" },
+                                inner.index + 1,
+                                i + 1
+                            );
                             println!("{}", out_put.gradient(Color::Green));
                             handle_program_instruction(
                                 &ui_compiled_instruction.data,
@@ -191,15 +378,21 @@ pub fn parse_program_event(
     } else {
         Vec::new()
     };
-    let mut logs = &logs[..];
+    parse_program_logs(self_program_str, &logs)
+}
+
+/// Decode the events the program logged, following CPIs through the logs.
+pub fn parse_program_logs(self_program_str: &str, logs: &[String]) -> Result<(), ClientError> {
+    let mut logs = logs;
     if !logs.is_empty() {
         if let Ok(mut execution) = Execution::new(&mut logs) {
             for l in logs {
                 let (new_program, did_pop) =
                     if !execution.is_empty() && self_program_str == execution.program() {
+                        // skip what does not decode and carry on with the rest
                         handle_program_log(self_program_str, l, true).unwrap_or_else(|e| {
                             println!("Unable to parse log: {e}");
-                            std::process::exit(1);
+                            (None, false)
                         })
                     } else {
                         let (program, did_pop) = handle_system_log(self_program_str, l);
@@ -221,6 +414,37 @@ pub fn parse_program_event(
     Ok(())
 }
 
+/// Decode the `emit_cpi!` events among a simulated transaction's inner
+/// instructions; `account_keys` are the keys of the simulated message.
+pub fn parse_inner_events(
+    self_program_str: &str,
+    account_keys: &[Pubkey],
+    inner_instructions: &[UiInnerInstructions],
+) -> Result<(), ClientError> {
+    let program_index = account_keys
+        .iter()
+        .position(|key| key.to_string() == self_program_str);
+    for inner in inner_instructions {
+        for instruction in inner.instructions.iter() {
+            if let UiInstruction::Compiled(ui_compiled_instruction) = instruction {
+                if Some(ui_compiled_instruction.program_id_index as usize) != program_index {
+                    continue;
+                }
+                let data = match bs58::decode(&ui_compiled_instruction.data).into_vec() {
+                    Ok(data) => data,
+                    _ => continue,
+                };
+                if data.starts_with(&EVENT_IX_TAG_LE) {
+                    if let Err(e) = handle_program_event(&data) {
+                        println!("Unable to parse event: {e}");
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 struct Execution {
     stack: Vec<String>,
 }
@@ -268,7 +492,13 @@ pub fn handle_program_instruction(
     decode_type: InstructionDecodeType,
 ) -> Result<(), ClientError> {
     let data = match decode_type {
-        InstructionDecodeType::BaseHex => hex::decode(instr_data).unwrap(),
+        InstructionDecodeType::BaseHex => match hex::decode(instr_data) {
+            Ok(borsh_bytes) => borsh_bytes,
+            _ => {
+                println!("Could not hex decode instruction: {}", instr_data);
+                return Ok(());
+            }
+        },
         InstructionDecodeType::Base64 => match BASE64_STANDARD.decode(instr_data) {
             Ok(borsh_bytes) => borsh_bytes,
             _ => {
@@ -285,6 +515,16 @@ pub fn handle_program_instruction(
         },
     };
 
+    if data.starts_with(&EVENT_IX_TAG_LE) {
+        return handle_program_event(&data);
+    }
+
+    if data.len() < 8 {
+        return Err(ClientError::LogParseError(format!(
+            "instruction data too short: {}",
+            instr_data
+        )));
+    }
     let mut ix_data: &[u8] = &data[..];
     let disc: [u8; 8] = {
         let mut disc = [0; 8];
@@ -295,12 +535,304 @@ pub fn handle_program_instruction(
     // println!("{:?}", disc);
 
     match disc {
+        instruction::CreateGlobal::DISCRIMINATOR => {
+            println!("CreateGlobal");
+        }
+        instruction::ProposeAdmin::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::ProposeAdmin>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct ProposeAdmin {
+                pub admin: Pubkey,
+            }
+            impl From<instruction::ProposeAdmin> for ProposeAdmin {
+                fn from(instr: instruction::ProposeAdmin) -> ProposeAdmin {
+                    ProposeAdmin { admin: instr.admin }
+                }
+            }
+            println!("{:#?}", ProposeAdmin::from(ix));
+        }
+        instruction::AcceptAdmin::DISCRIMINATOR => {
+            println!("AcceptAdmin");
+        }
+        instruction::SetTimeManager::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SetTimeManager>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct SetTimeManager {
+                pub time_manager: Pubkey,
+            }
+            impl From<instruction::SetTimeManager> for SetTimeManager {
+                fn from(instr: instruction::SetTimeManager) -> SetTimeManager {
+                    SetTimeManager {
+                        time_manager: instr.time_manager,
+                    }
+                }
+            }
+            println!("{:#?}", SetTimeManager::from(ix));
+        }
+        instruction::SetResolver::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SetResolver>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct SetResolver {
+                pub resolver: Pubkey,
+            }
+            impl From<instruction::SetResolver> for SetResolver {
+                fn from(instr: instruction::SetResolver) -> SetResolver {
+                    SetResolver {
+                        resolver: instr.resolver,
+                    }
+                }
+            }
+            println!("{:#?}", SetResolver::from(ix));
+        }
+        instruction::SetPauseGuardian::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::SetPauseGuardian>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct SetPauseGuardian {
+                pub pause_guardian: Pubkey,
+            }
+            impl From<instruction::SetPauseGuardian> for SetPauseGuardian {
+                fn from(instr: instruction::SetPauseGuardian) -> SetPauseGuardian {
+                    SetPauseGuardian {
+                        pause_guardian: instr.pause_guardian,
+                    }
+                }
+            }
+            println!("{:#?}", SetPauseGuardian::from(ix));
+        }
+        instruction::Pause::DISCRIMINATOR => {
+            println!("Pause");
+        }
+        instruction::Unpause::DISCRIMINATOR => {
+            println!("Unpause");
+        }
+        instruction::FreezeBlink::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::FreezeBlink>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct FreezeBlink {
+                pub index: u64,
+                pub frozen: bool,
+            }
+            impl From<instruction::FreezeBlink> for FreezeBlink {
+                fn from(instr: instruction::FreezeBlink) -> FreezeBlink {
+                    FreezeBlink {
+                        index: instr.index,
+                        frozen: instr.frozen,
+                    }
+                }
+            }
+            println!("{:#?}", FreezeBlink::from(ix));
+        }
+        instruction::CreateSeason::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CreateSeason>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct CreateSeason {
+                pub id: u64,
+                pub end_time: u64,
+                pub top_n: u8,
+                pub prize: u64,
+            }
+            impl From<instruction::CreateSeason> for CreateSeason {
+                fn from(instr: instruction::CreateSeason) -> CreateSeason {
+                    CreateSeason {
+                        id: instr.id,
+                        end_time: instr.end_time,
+                        top_n: instr.top_n,
+                        prize: instr.prize,
+                    }
+                }
+            }
+            println!("{:#?}", CreateSeason::from(ix));
+        }
+        instruction::AddSeasonBlink::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::AddSeasonBlink>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct AddSeasonBlink {
+                pub id: u64,
+                pub index: u64,
+            }
+            impl From<instruction::AddSeasonBlink> for AddSeasonBlink {
+                fn from(instr: instruction::AddSeasonBlink) -> AddSeasonBlink {
+                    AddSeasonBlink {
+                        id: instr.id,
+                        index: instr.index,
+                    }
+                }
+            }
+            println!("{:#?}", AddSeasonBlink::from(ix));
+        }
+        instruction::Score::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::Score>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct Score {
+                pub id: u64,
+                pub index: u64,
+                pub user: Pubkey,
+            }
+            impl From<instruction::Score> for Score {
+                fn from(instr: instruction::Score) -> Score {
+                    Score {
+                        id: instr.id,
+                        index: instr.index,
+                        user: instr.user,
+                    }
+                }
+            }
+            println!("{:#?}", Score::from(ix));
+        }
+        instruction::PayoutSeason::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::PayoutSeason>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct PayoutSeason {
+                pub id: u64,
+                pub rank: u8,
+            }
+            impl From<instruction::PayoutSeason> for PayoutSeason {
+                fn from(instr: instruction::PayoutSeason) -> PayoutSeason {
+                    PayoutSeason {
+                        id: instr.id,
+                        rank: instr.rank,
+                    }
+                }
+            }
+            println!("{:#?}", PayoutSeason::from(ix));
+        }
+        instruction::CreateSeries::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CreateSeries>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct CreateSeries {
+                pub id: u64,
+                pub end_time: u64,
+                pub bonus_bps: u16,
+                pub max_streak: u32,
+                pub bonus: u64,
+            }
+            impl From<instruction::CreateSeries> for CreateSeries {
+                fn from(instr: instruction::CreateSeries) -> CreateSeries {
+                    CreateSeries {
+                        id: instr.id,
+                        end_time: instr.end_time,
+                        bonus_bps: instr.bonus_bps,
+                        max_streak: instr.max_streak,
+                        bonus: instr.bonus,
+                    }
+                }
+            }
+            println!("{:#?}", CreateSeries::from(ix));
+        }
+        instruction::FundSeries::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::FundSeries>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct FundSeries {
+                pub id: u64,
+                pub amount: u64,
+            }
+            impl From<instruction::FundSeries> for FundSeries {
+                fn from(instr: instruction::FundSeries) -> FundSeries {
+                    FundSeries {
+                        id: instr.id,
+                        amount: instr.amount,
+                    }
+                }
+            }
+            println!("{:#?}", FundSeries::from(ix));
+        }
+        instruction::AddSeriesBlink::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::AddSeriesBlink>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct AddSeriesBlink {
+                pub id: u64,
+                pub index: u64,
+            }
+            impl From<instruction::AddSeriesBlink> for AddSeriesBlink {
+                fn from(instr: instruction::AddSeriesBlink) -> AddSeriesBlink {
+                    AddSeriesBlink {
+                        id: instr.id,
+                        index: instr.index,
+                    }
+                }
+            }
+            println!("{:#?}", AddSeriesBlink::from(ix));
+        }
+        instruction::WithdrawSeries::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::WithdrawSeries>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct WithdrawSeries {
+                pub id: u64,
+            }
+            impl From<instruction::WithdrawSeries> for WithdrawSeries {
+                fn from(instr: instruction::WithdrawSeries) -> WithdrawSeries {
+                    WithdrawSeries { id: instr.id }
+                }
+            }
+            println!("{:#?}", WithdrawSeries::from(ix));
+        }
+        instruction::CreateStreak::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::CreateStreak>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct CreateStreak {
+                pub id: u64,
+            }
+            impl From<instruction::CreateStreak> for CreateStreak {
+                fn from(instr: instruction::CreateStreak) -> CreateStreak {
+                    CreateStreak { id: instr.id }
+                }
+            }
+            println!("{:#?}", CreateStreak::from(ix));
+        }
+        instruction::MigrateTimeConfig::DISCRIMINATOR => {
+            println!("MigrateTimeConfig");
+        }
+        instruction::MigrateBlinkState::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::MigrateBlinkState>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct MigrateBlinkState {
+                pub index: u16,
+            }
+            impl From<instruction::MigrateBlinkState> for MigrateBlinkState {
+                fn from(instr: instruction::MigrateBlinkState) -> MigrateBlinkState {
+                    MigrateBlinkState { index: instr.index }
+                }
+            }
+            println!("{:#?}", MigrateBlinkState::from(ix));
+        }
+        instruction::MigrateSubmitState::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::MigrateSubmitState>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct MigrateSubmitState {
+                pub index: u16,
+                pub user: Pubkey,
+            }
+            impl From<instruction::MigrateSubmitState> for MigrateSubmitState {
+                fn from(instr: instruction::MigrateSubmitState) -> MigrateSubmitState {
+                    MigrateSubmitState {
+                        index: instr.index,
+                        user: instr.user,
+                    }
+                }
+            }
+            println!("{:#?}", MigrateSubmitState::from(ix));
+        }
         instruction::Initialize::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::Initialize>(&mut ix_data).unwrap();
+            let ix = decode_instruction::<instruction::Initialize>(&mut ix_data)?;
             #[allow(dead_code)]
             #[derive(Debug)]
             pub struct Initialize {
-                pub index: u16,
                 pub amount: u64,
                 pub pic: String,
                 pub content: String,
@@ -312,7 +844,6 @@ pub fn handle_program_instruction(
             impl From<instruction::Initialize> for Initialize {
                 fn from(instr: instruction::Initialize) -> Initialize {
                     Initialize {
-                        index: instr.index,
                         amount: instr.amount,
                         pic: instr.pic,
                         content: instr.content,
@@ -325,12 +856,111 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", Initialize::from(ix));
         }
+        instruction::InitializeWithMetadata::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::InitializeWithMetadata>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct InitializeWithMetadata {
+                pub amount: u64,
+                pub uri: String,
+                pub metadata_hash: String,
+            }
+            impl From<instruction::InitializeWithMetadata> for InitializeWithMetadata {
+                fn from(instr: instruction::InitializeWithMetadata) -> InitializeWithMetadata {
+                    InitializeWithMetadata {
+                        amount: instr.amount,
+                        uri: instr.uri,
+                        metadata_hash: hex::encode(instr.metadata_hash),
+                    }
+                }
+            }
+            println!("{:#?}", InitializeWithMetadata::from(ix));
+        }
+        instruction::UpdateConfig::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::UpdateConfig>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct UpdateConfig {
+                pub index: u64,
+                pub pic: String,
+                pub content: String,
+                pub option1: String,
+                pub option2: String,
+                pub option3: String,
+                pub option4: String,
+            }
+            impl From<instruction::UpdateConfig> for UpdateConfig {
+                fn from(instr: instruction::UpdateConfig) -> UpdateConfig {
+                    UpdateConfig {
+                        index: instr.index,
+                        pic: instr.pic,
+                        content: instr.content,
+                        option1: instr.option1,
+                        option2: instr.option2,
+                        option3: instr.option3,
+                        option4: instr.option4,
+                    }
+                }
+            }
+            println!("{:#?}", UpdateConfig::from(ix));
+        }
+        instruction::UpdateMetadata::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::UpdateMetadata>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct UpdateMetadata {
+                pub index: u64,
+                pub uri: String,
+                pub metadata_hash: String,
+            }
+            impl From<instruction::UpdateMetadata> for UpdateMetadata {
+                fn from(instr: instruction::UpdateMetadata) -> UpdateMetadata {
+                    UpdateMetadata {
+                        index: instr.index,
+                        uri: instr.uri,
+                        metadata_hash: hex::encode(instr.metadata_hash),
+                    }
+                }
+            }
+            println!("{:#?}", UpdateMetadata::from(ix));
+        }
+        instruction::AddTranslation::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::AddTranslation>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct AddTranslation {
+                pub index: u64,
+                pub lang: String,
+                pub content: String,
+                pub option1: String,
+                pub option2: String,
+                pub option3: String,
+                pub option4: String,
+            }
+            impl From<instruction::AddTranslation> for AddTranslation {
+                fn from(instr: instruction::AddTranslation) -> AddTranslation {
+                    AddTranslation {
+                        index: instr.index,
+                        lang: instr.lang,
+                        content: instr.content,
+                        option1: instr.option1,
+                        option2: instr.option2,
+                        option3: instr.option3,
+                        option4: instr.option4,
+                    }
+                }
+            }
+            println!("{:#?}", AddTranslation::from(ix));
+        }
+        instruction::CreateProfile::DISCRIMINATOR => {
+            println!("CreateProfile");
+        }
         instruction::Submit::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::Submit>(&mut ix_data).unwrap();
+            let ix = decode_instruction::<instruction::Submit>(&mut ix_data)?;
             #[allow(dead_code)]
             #[derive(Debug)]
             pub struct Submit {
-                pub index: u16,
+                pub index: u64,
                 pub answer: u8,
             }
             impl From<instruction::Submit> for Submit {
@@ -344,11 +974,11 @@ pub fn handle_program_instruction(
             println!("{:#?}", Submit::from(ix));
         }
         instruction::Close::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::Close>(&mut ix_data).unwrap();
+            let ix = decode_instruction::<instruction::Close>(&mut ix_data)?;
             #[allow(dead_code)]
             #[derive(Debug)]
             pub struct Close {
-                pub index: u16,
+                pub index: u64,
                 pub answer: u8,
             }
             impl From<instruction::Close> for Close {
@@ -362,11 +992,11 @@ pub fn handle_program_instruction(
             println!("{:#?}", Close::from(ix));
         }
         instruction::Claim::DISCRIMINATOR => {
-            let ix = decode_instruction::<instruction::Claim>(&mut ix_data).unwrap();
+            let ix = decode_instruction::<instruction::Claim>(&mut ix_data)?;
             #[allow(dead_code)]
             #[derive(Debug)]
             pub struct Claim {
-                pub index: u16,
+                pub index: u64,
             }
             impl From<instruction::Claim> for Claim {
                 fn from(instr: instruction::Claim) -> Claim {
@@ -375,6 +1005,42 @@ pub fn handle_program_instruction(
             }
             println!("{:#?}", Claim::from(ix));
         }
+        instruction::ExtendDeadline::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::ExtendDeadline>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct ExtendDeadline {
+                pub index: u64,
+                pub close_time: u64,
+            }
+            impl From<instruction::ExtendDeadline> for ExtendDeadline {
+                fn from(instr: instruction::ExtendDeadline) -> ExtendDeadline {
+                    ExtendDeadline {
+                        index: instr.index,
+                        close_time: instr.close_time,
+                    }
+                }
+            }
+            println!("{:#?}", ExtendDeadline::from(ix));
+        }
+        instruction::EndEarly::DISCRIMINATOR => {
+            let ix = decode_instruction::<instruction::EndEarly>(&mut ix_data)?;
+            #[allow(dead_code)]
+            #[derive(Debug)]
+            pub struct EndEarly {
+                pub index: u64,
+                pub close_time: u64,
+            }
+            impl From<instruction::EndEarly> for EndEarly {
+                fn from(instr: instruction::EndEarly) -> EndEarly {
+                    EndEarly {
+                        index: instr.index,
+                        close_time: instr.close_time,
+                    }
+                }
+            }
+            println!("{:#?}", EndEarly::from(ix));
+        }
 
         _ => {
             println!("unknow instruction: {}", instr_data);
@@ -385,8 +1051,59 @@ pub fn handle_program_instruction(
 
 fn decode_instruction<T: anchor_lang::AnchorDeserialize>(
     slice: &mut &[u8],
-) -> Result<T, anchor_lang::error::ErrorCode> {
+) -> Result<T, ClientError> {
     let instruction: T = anchor_lang::AnchorDeserialize::deserialize(slice)
-        .map_err(|_| anchor_lang::error::ErrorCode::InstructionDidNotDeserialize)?;
+        .map_err(|e| ClientError::LogParseError(e.to_string()))?;
     Ok(instruction)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::Event;
+
+    #[test]
+    fn decodes_legacy_logged_events() {
+        // `Program data:` of a claim logged by the u16-index program
+        let user = Pubkey::new_from_array([7; 32]);
+        let mut data = ClaimEvent::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&3u16.to_le_bytes());
+        data.extend_from_slice(user.as_ref());
+        data.extend_from_slice(&250u64.to_le_bytes());
+        let log = format!("{}{}", PROGRAM_DATA, BASE64_STANDARD.encode(&data));
+
+        let data = BASE64_STANDARD
+            .decode(log.strip_prefix(PROGRAM_DATA).unwrap())
+            .unwrap();
+        match decode_legacy_event(&data) {
+            Some(LegacyEvent::Claim(event)) => {
+                assert_eq!(event.index, 3);
+                assert_eq!(event.user, user);
+                assert_eq!(event.reward, 250);
+            }
+            event => panic!("unexpected {:?}", event),
+        }
+        assert!(handle_program_log("blink", &log, true).is_ok());
+
+        let mut data = CloseEvent::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&9u16.to_le_bytes());
+        assert!(matches!(
+            decode_legacy_event(&data),
+            Some(LegacyEvent::Close(LegacyCloseEvent { index: 9 }))
+        ));
+    }
+
+    #[test]
+    fn current_events_are_not_read_as_legacy() {
+        let event = CloseEvent {
+            index: 9,
+            answer: 1,
+            reward: 100,
+            rights: [1, 0, 0, 0],
+            timestamp: 1_700_000_000,
+        };
+        let data = event.data();
+        assert!(decode_legacy_event(&data).is_none());
+        assert!(handle_program_event(&data).is_ok());
+    }
+}
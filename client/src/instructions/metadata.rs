@@ -0,0 +1,123 @@
+use anchor_client::Program;
+use anyhow::{format_err, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{hash::hash, signature::Keypair};
+
+use blink_sdk::accounts as blink_accounts;
+use std::collections::BTreeMap;
+use std::fs;
+use std::rc::Rc;
+
+use crate::BlinkContent;
+
+/// Off-chain blink content, served as JSON at `BlinkConfig::uri`. The
+/// on-chain `metadata_hash` is the sha256 of the document bytes as served.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlinkMetadata {
+    pub question: String,
+    pub options: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<String>,
+    /// Translations keyed by language tag (`en`, `es`, ...).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub localized: BTreeMap<String, LocalizedText>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LocalizedText {
+    pub question: String,
+    pub options: Vec<String>,
+}
+
+/// Build a metadata document from the inline content fields. `localized`
+/// entries are `<lang>=<path>`, each path holding a `LocalizedText` JSON.
+pub fn build_metadata(content: BlinkContent, localized: &[String]) -> Result<Vec<u8>> {
+    let mut metadata = BlinkMetadata {
+        question: content.content,
+        options: vec![
+            content.option1,
+            content.option2,
+            content.option3,
+            content.option4,
+        ],
+        images: if content.pic.is_empty() {
+            Vec::new()
+        } else {
+            vec![content.pic]
+        },
+        localized: BTreeMap::new(),
+    };
+    for entry in localized {
+        let (lang, path) = entry
+            .split_once('=')
+            .ok_or_else(|| format_err!("invalid localized entry: {}", entry))?;
+        let text: LocalizedText = serde_json::from_slice(&fs::read(path)?)?;
+        metadata.localized.insert(lang.to_string(), text);
+    }
+    check_metadata(&metadata)?;
+    Ok(serde_json::to_vec_pretty(&metadata)?)
+}
+
+/// Read a metadata document from an http(s) uri, a `file://` uri or a
+/// local path.
+pub fn load_metadata(source: &str) -> Result<Vec<u8>> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let response = reqwest::blocking::get(source)?.error_for_status()?;
+        return Ok(response.bytes()?.to_vec());
+    }
+    let path = source.strip_prefix("file://").unwrap_or(source);
+    Ok(fs::read(path)?)
+}
+
+/// Parse and sanity check a metadata document.
+pub fn parse_metadata(data: &[u8]) -> Result<BlinkMetadata> {
+    let metadata: BlinkMetadata = serde_json::from_slice(data)?;
+    check_metadata(&metadata)?;
+    Ok(metadata)
+}
+
+pub fn metadata_hash(data: &[u8]) -> [u8; 32] {
+    hash(data).to_bytes()
+}
+
+fn check_metadata(metadata: &BlinkMetadata) -> Result<()> {
+    if metadata.options.len() != 4 {
+        return Err(format_err!(
+            "metadata must have 4 options, found {}",
+            metadata.options.len()
+        ));
+    }
+    for (lang, text) in metadata.localized.iter() {
+        if text.options.len() != metadata.options.len() {
+            return Err(format_err!("localized options mismatch for {}", lang));
+        }
+    }
+    Ok(())
+}
+
+/// Check the document at `source` (or the blink's own uri) against the hash
+/// stored on-chain.
+pub fn verify_metadata(
+    program: &Program<Rc<Keypair>>,
+    index: u64,
+    source: Option<String>,
+) -> Result<()> {
+    let blink_config = blink_accounts::fetch_blink_config(&program.rpc(), &program.id(), index)?;
+    if !blink_config.is_metadata() {
+        return Err(format_err!("blink {} keeps its content on-chain", index));
+    }
+    let source = source.unwrap_or(blink_config.uri);
+    let data = load_metadata(&source)?;
+    let hash = metadata_hash(&data);
+    if hash != blink_config.metadata_hash {
+        return Err(format_err!(
+            "metadata hash mismatch: expected {}, found {}",
+            hex::encode(blink_config.metadata_hash),
+            hex::encode(hash)
+        ));
+    }
+    let metadata = parse_metadata(&data)?;
+    println!("{:#?}", metadata);
+    println!("metadata ok: {}", hex::encode(hash));
+    Ok(())
+}
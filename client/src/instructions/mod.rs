@@ -1,3 +1,8 @@
 pub mod blink_instructions;
+pub mod clock;
 pub mod event_instructions_parse;
+pub mod metadata;
+pub mod query;
 pub mod rpc;
+pub mod signer;
+pub mod token;
@@ -0,0 +1,334 @@
+use anchor_client::Program;
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator, Space};
+use anyhow::{format_err, Result};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{
+    account::Account, native_token::lamports_to_sol, pubkey::Pubkey, signature::Keypair,
+};
+
+use blink::state::*;
+use blink_sdk::{accounts as blink_accounts, pda};
+use std::rc::Rc;
+
+use crate::instructions::blink_instructions::BlinkSource;
+
+fn fetch_global(program: &Program<Rc<Keypair>>) -> Result<GlobalConfig> {
+    blink_accounts::fetch_global_config(&program.rpc(), &program.id())
+}
+
+pub fn query_global(program: &Program<Rc<Keypair>>) -> Result<()> {
+    let global_config = fetch_global(program)?;
+    println!("global config");
+    println!("admin          : {}", global_config.admin);
+    println!("pending_admin  : {}", global_config.pending_admin);
+    println!("time_manager   : {}", global_config.time_manager);
+    println!("resolver       : {}", global_config.resolver);
+    println!("pause_guardian : {}", global_config.pause_guardian);
+    println!("paused         : {}", global_config.paused);
+    println!("slot_clock     : {}", global_config.slot_clock);
+
+    let blink_counter = blink_accounts::fetch_blink_counter(&program.rpc(), &program.id())?;
+    println!("next_index     : {}", blink_counter.next_index);
+    Ok(())
+}
+
+fn fetch_translation(
+    program: &Program<Rc<Keypair>>,
+    index: u64,
+    lang: &str,
+) -> Result<Option<BlinkTranslation>> {
+    blink_accounts::fetch_optional(
+        &program.rpc(),
+        &pda::blink_translation(&program.id(), index, lang),
+    )
+}
+
+pub fn query_blink(program: &Program<Rc<Keypair>>, index: u64, lang: Option<String>) -> Result<()> {
+    let global_config = fetch_global(program)?;
+
+    if let Ok(legacy_index) = u16::try_from(index) {
+        let legacy_key = pda::legacy_blink_state(&program.id(), legacy_index);
+        if blink_accounts::account_exists(&program.rpc(), &legacy_key)? {
+            println!("blink {} uses the u16 layout, run `migrate` first", index);
+            return Ok(());
+        }
+    }
+    let blink_config = blink_accounts::fetch_blink_config(&program.rpc(), &program.id(), index)?;
+    println!("config info");
+    println!("index      : {}", blink_config.index);
+    println!("creator    : {}", blink_config.creator);
+    println!("pic        : {}", blink_config.pic);
+    let translation = match &lang {
+        Some(lang) => fetch_translation(program, index, lang)?,
+        None => None,
+    };
+    match translation {
+        Some(translation) => {
+            println!("lang       : {}", translation.lang);
+            println!("content    : {}", translation.content);
+            println!("option1    : {}", translation.option1);
+            println!("option2    : {}", translation.option2);
+            println!("option3    : {}", translation.option3);
+            println!("option4    : {}", translation.option4);
+        }
+        None => {
+            if let Some(lang) = &lang {
+                println!("no {} translation, showing the default content", lang);
+            }
+            println!("content    : {}", blink_config.content);
+            println!("option1    : {}", blink_config.option1);
+            println!("option2    : {}", blink_config.option2);
+            println!("option3    : {}", blink_config.option3);
+            println!("option4    : {}", blink_config.option4);
+        }
+    }
+    if blink_config.is_metadata() {
+        println!("uri        : {}", blink_config.uri);
+        println!("metadata   : {}", hex::encode(blink_config.metadata_hash));
+    }
+
+    let blink_state = blink_accounts::fetch_blink_state(&program.rpc(), &program.id(), index)?;
+    println!("blink info");
+    println!("version    : {}", { blink_state.version });
+    println!("token_mint : {}", { blink_state.token_mint });
+    println!("vault      : {}", { blink_state.vault });
+    if blink_state.token_mint == spl_token::native_mint::id() {
+        println!("amount     : {} SOL", lamports_to_sol(blink_state.amount));
+    } else {
+        println!("amount     : {}", { blink_state.amount });
+    }
+    println!("open_time  : {}", { blink_state.open_time });
+    println!("close_time : {}", { blink_state.close_time });
+    println!("closed     : {}", { blink_state.closed });
+    println!("answer     : {}", { blink_state.answer });
+    println!("reward     : {}", { blink_state.reward });
+    println!("right1     : {}", { blink_state.right1 });
+    println!("right2     : {}", { blink_state.right2 });
+    println!("right3     : {}", { blink_state.right3 });
+    println!("right4     : {}", { blink_state.right4 });
+    println!("paused     : {}", global_config.paused);
+    println!("frozen     : {}", { blink_state.frozen });
+    Ok(())
+}
+
+pub fn query_season(program: &Program<Rc<Keypair>>, id: u64) -> Result<()> {
+    let global_config = fetch_global(program)?;
+    let season = blink_accounts::fetch_season(&program.rpc(), &program.id(), id)?;
+    println!("season info");
+    println!("id         : {}", season.id);
+    println!("owner      : {}", season.owner);
+    println!("token_mint : {}", season.token_mint);
+    println!("prize      : {}", season.prize);
+    println!("end_time   : {}", season.end_time);
+    println!("top_n      : {}", season.top_n);
+    println!("blinks     : {:?}", season.blinks);
+    println!("paused     : {}", global_config.paused);
+
+    println!("leaderboard");
+    for (rank, entry) in season.leaderboard.iter().enumerate() {
+        let paid = season.paid & (1 << rank) != 0;
+        println!(
+            "{:>3} {} {:>6}{}",
+            rank,
+            entry.user,
+            entry.points,
+            if paid { " paid" } else { "" }
+        );
+    }
+
+    // everyone else who scored, best first
+    let mut scores = Vec::new();
+    for (_key, account) in program_accounts_of_size(
+        &program.rpc(),
+        &program.id(),
+        &SeasonScore::DISCRIMINATOR,
+        ANCHOR_DISCRIMINATOR + SeasonScore::INIT_SPACE,
+    )? {
+        let season_score = SeasonScore::try_deserialize(&mut account.data.as_slice())?;
+        if season_score.season == id {
+            scores.push(season_score);
+        }
+    }
+    scores.sort_by_key(|season_score| std::cmp::Reverse(season_score.points));
+    println!("scores");
+    for season_score in scores.iter() {
+        println!("    {} {:>6}", season_score.user, season_score.points);
+    }
+    Ok(())
+}
+
+pub fn query_series(program: &Program<Rc<Keypair>>, id: u64, user: Option<Pubkey>) -> Result<()> {
+    let global_config = fetch_global(program)?;
+    let series = blink_accounts::fetch_series(&program.rpc(), &program.id(), id)?;
+    println!("series info");
+    println!("id         : {}", series.id);
+    println!("owner      : {}", series.owner);
+    println!("token_mint : {}", series.token_mint);
+    println!("bonus      : {}", series.bonus);
+    println!("bonus_bps  : {}", series.bonus_bps);
+    println!("max_streak : {}", series.max_streak);
+    println!("vault      : {}", series.vault);
+    println!("end_time   : {}", series.end_time);
+    println!("blinks     : {:?}", series.blinks);
+    println!("paused     : {}", global_config.paused);
+
+    if let Some(user) = user {
+        match blink_accounts::fetch_streak(&program.rpc(), &program.id(), id, &user)? {
+            Some(streak) => {
+                println!("streak     : {}", streak.streak);
+                if streak.streak > 0 {
+                    println!(
+                        "last_blink : {}",
+                        series.blinks[streak.last_position as usize]
+                    );
+                }
+            }
+            None => println!("no streak for {}", user),
+        }
+    }
+    Ok(())
+}
+
+pub fn query_profile(program: &Program<Rc<Keypair>>, wallet: Pubkey) -> Result<()> {
+    let global_config = fetch_global(program)?;
+    let user_profile = blink_accounts::fetch_user_profile(&program.rpc(), &program.id(), &wallet)?
+        .ok_or_else(|| format_err!("no profile for {}", wallet))?;
+    println!("profile info");
+    println!("user        : {}", user_profile.user);
+    println!("submissions : {}", user_profile.submissions);
+    println!("correct     : {}", user_profile.correct);
+    println!("rewards     : {}", user_profile.rewards);
+    println!("last_index  : {}", user_profile.last_index);
+    println!("paused      : {}", global_config.paused);
+    Ok(())
+}
+
+/// Print the rent a new blink locks up, failing early on content the
+/// program would reject.
+pub fn print_initialize_rent(rpc_client: &RpcClient, source: &BlinkSource) -> Result<()> {
+    let config_space = ANCHOR_DISCRIMINATOR
+        + match source {
+            BlinkSource::Inline(content) => {
+                let options = [
+                    content.option1.as_str(),
+                    content.option2.as_str(),
+                    content.option3.as_str(),
+                    content.option4.as_str(),
+                ];
+                if !BlinkConfig::check_len(&content.pic, &content.content, options) {
+                    return Err(format_err!(
+                        "content too long: pic up to {} bytes, content up to {}, options up to {}",
+                        MAX_PIC_LEN,
+                        MAX_CONTENT_LEN,
+                        MAX_OPTION_LEN
+                    ));
+                }
+                BlinkConfig::space(&content.pic, &content.content, options, "")
+            }
+            BlinkSource::Metadata { uri, .. } => {
+                if uri.len() > MAX_URI_LEN {
+                    return Err(format_err!("uri too long: up to {} bytes", MAX_URI_LEN));
+                }
+                BlinkConfig::space("", "", ["", "", "", ""], uri)
+            }
+        };
+    let state_space = ANCHOR_DISCRIMINATOR + BlinkState::INIT_SPACE;
+    let config_rent = rpc_client.get_minimum_balance_for_rent_exemption(config_space)?;
+    let state_rent = rpc_client.get_minimum_balance_for_rent_exemption(state_space)?;
+    println!(
+        "blink_config : {} bytes, {} SOL",
+        config_space,
+        lamports_to_sol(config_rent)
+    );
+    println!(
+        "blink_state  : {} bytes, {} SOL",
+        state_space,
+        lamports_to_sol(state_rent)
+    );
+    println!(
+        "rent         : {} SOL",
+        lamports_to_sol(config_rent + state_rent)
+    );
+    Ok(())
+}
+
+/// An account still stored with the layout from before accounts were
+/// versioned, when blinks had a `u16` index.
+#[derive(Clone, Copy, Debug)]
+pub enum LegacyAccount {
+    TimeConfig,
+    BlinkState { index: u16 },
+    SubmitState { index: u16, user: Pubkey },
+}
+
+/// Legacy accounts in the order they have to be migrated: a blink before
+/// its submissions.
+pub fn find_legacy_accounts(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+) -> Result<Vec<LegacyAccount>> {
+    let mut legacy = Vec::new();
+    if !program_accounts_of_size(
+        rpc_client,
+        program_id,
+        &TimeConfig::DISCRIMINATOR,
+        ANCHOR_DISCRIMINATOR + LegacyTimeConfig::INIT_SPACE,
+    )?
+    .is_empty()
+    {
+        legacy.push(LegacyAccount::TimeConfig);
+    }
+    for (_key, account) in program_accounts_of_size(
+        rpc_client,
+        program_id,
+        &BlinkState::DISCRIMINATOR,
+        ANCHOR_DISCRIMINATOR + LegacyBlinkState::INIT_SPACE,
+    )? {
+        let blink_state =
+            LegacyBlinkState::deserialize(&mut &account.data[ANCHOR_DISCRIMINATOR..])?;
+        legacy.push(LegacyAccount::BlinkState {
+            index: blink_state.index,
+        });
+    }
+    for (_key, account) in program_accounts_of_size(
+        rpc_client,
+        program_id,
+        &SubmitState::DISCRIMINATOR,
+        ANCHOR_DISCRIMINATOR + LegacySubmitState::INIT_SPACE,
+    )? {
+        let submit_state =
+            LegacySubmitState::deserialize(&mut &account.data[ANCHOR_DISCRIMINATOR..])?;
+        legacy.push(LegacyAccount::SubmitState {
+            index: submit_state.index,
+            user: submit_state.user,
+        });
+    }
+    Ok(legacy)
+}
+
+fn program_accounts_of_size(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    discriminator: &[u8],
+    size: usize,
+) -> Result<Vec<(Pubkey, Account)>> {
+    Ok(rpc_client.get_program_accounts_with_config(
+        program_id,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(size as u64),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, discriminator)),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        },
+    )?)
+}
@@ -1,20 +1,678 @@
-use anyhow::Result;
-use solana_client::{rpc_client::RpcClient, rpc_config::RpcSendTransactionConfig};
+use anyhow::{format_err, Result};
+use base64::prelude::*;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    nonblocking::rpc_client::RpcClient,
+    nonce_utils::nonblocking as nonce_utils,
+    rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig},
+    rpc_request::{RpcError, RpcResponseErrorData},
+    rpc_response::RpcSimulateTransactionResult,
+};
 use solana_sdk::{
-    commitment_config::CommitmentConfig, signature::Signature, transaction::Transaction,
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::SignerError,
+    signers::Signers,
+    system_instruction,
+    transaction::{uses_durable_nonce, Transaction, TransactionError},
 };
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// How often a sent transaction's status is checked.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Longest wait between two retries.
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+/// Most compute units a transaction may use.
+const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+/// Headroom over the simulated units, the accounts may change before landing.
+const COMPUTE_MARGIN_PERCENT: u64 = 10;
+/// Recent fees are ranked and this percentile is paid in `auto` mode.
+const AUTO_FEE_PERCENTILE: usize = 75;
+/// Most accounts `getRecentPrioritizationFees` accepts.
+const MAX_FEE_ACCOUNTS: usize = 128;
+
+/// Price of each compute unit, on top of the base fee.
+#[derive(Clone, Copy, Debug)]
+pub enum PriorityFee {
+    MicroLamports(u64),
+    /// Estimated from the fees recently paid to write the same accounts.
+    Auto,
+}
+
+impl FromStr for PriorityFee {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "auto" {
+            return Ok(PriorityFee::Auto);
+        }
+        s.parse()
+            .map(PriorityFee::MicroLamports)
+            .map_err(|_| format_err!("priority fee must be micro-lamports or `auto`: {}", s))
+    }
+}
+
+/// How `send_txn` retries and when it considers a transaction done.
+#[derive(Clone, Debug)]
+pub struct SendConfig {
+    /// Attempts after the first, on rpc errors and expired blockhashes.
+    pub retries: u32,
+    /// Wait before the first retry, doubled for each one after.
+    pub backoff: Duration,
+    /// Commitment the transaction has to reach.
+    pub commitment: CommitmentConfig,
+    /// Send without simulating first; a failing transaction then still
+    /// lands and pays its fee.
+    pub skip_preflight: bool,
+    /// Compute unit price, none pays only the base fee.
+    pub priority_fee: Option<PriorityFee>,
+    /// Compute unit limit, taken from a simulation when unset.
+    pub compute_limit: Option<u32>,
+    /// Nonce account whose durable nonce replaces the blockhash, so the
+    /// transaction stays valid until it lands.
+    pub nonce: Option<Pubkey>,
+    /// Signs the nonce advance, the payer when unset.
+    pub nonce_authority: Option<Pubkey>,
+}
+
+impl Default for SendConfig {
+    fn default() -> Self {
+        SendConfig {
+            retries: 5,
+            backoff: Duration::from_millis(500),
+            commitment: CommitmentConfig::confirmed(),
+            skip_preflight: false,
+            priority_fee: None,
+            compute_limit: None,
+            nonce: None,
+            nonce_authority: None,
+        }
+    }
+}
+
+/// The transaction made it into a block but the program rejected it. The
+/// fee is paid and sending it again fails the same way.
+#[derive(Debug)]
+pub struct TransactionFailed {
+    pub signature: Signature,
+    pub slot: u64,
+    pub err: TransactionError,
+}
+
+impl fmt::Display for TransactionFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "transaction {} landed in slot {} but failed: {}",
+            self.signature, self.slot, self.err
+        )
+    }
+}
+
+impl std::error::Error for TransactionFailed {}
+
+/// Nothing landed before the blockhash expired, so it is safe to sign again
+/// with a new one.
+#[derive(Debug)]
+struct BlockhashExpired(Signature);
+
+impl fmt::Display for BlockhashExpired {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "blockhash of transaction {} expired", self.0)
+    }
+}
+
+impl std::error::Error for BlockhashExpired {}
+
+/// Polling a sent transaction kept failing. It may still land, so it must
+/// not be signed again.
+#[derive(Debug)]
+struct LostTrack(Signature);
+
+impl fmt::Display for LostTrack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "lost track of transaction {}, check its status before sending again",
+            self.0
+        )
+    }
+}
+
+/// What keeps a signed transaction from landing once it is out.
+enum Expiry {
+    /// The cluster no longer accepts its blockhash.
+    Blockhash(Hash),
+    /// The nonce account no longer holds the durable nonce it was signed with.
+    Nonce(Pubkey, Hash),
+}
+
+impl Expiry {
+    /// Read from a transaction built elsewhere.
+    fn of(txn: &Transaction) -> Self {
+        let nonce = uses_durable_nonce(txn)
+            .and_then(|advance| advance.accounts.first())
+            .and_then(|index| txn.message.account_keys.get(*index as usize));
+        match nonce {
+            Some(nonce) => Expiry::Nonce(*nonce, txn.message.recent_blockhash),
+            None => Expiry::Blockhash(txn.message.recent_blockhash),
+        }
+    }
+
+    async fn passed(&self, client: &RpcClient) -> Result<bool> {
+        Ok(match self {
+            Expiry::Blockhash(hash) => {
+                !client.is_blockhash_valid(hash, client.commitment()).await?
+            }
+            Expiry::Nonce(nonce, hash) => durable_nonce(client, nonce).await? != *hash,
+        })
+    }
+}
+
+/// Sign `instructions` with a fresh blockhash, or the durable nonce of
+/// `config.nonce`, send them and wait for `config.commitment`. Failures before
+/// the transaction is out and expired blockhashes are retried with backoff;
+/// once sent, even when sending reported an error, the transaction is only
+/// ever signed again after its blockhash has expired, so it cannot land
+/// twice.
+pub async fn send_txn<T: Signers + ?Sized>(
+    client: &RpcClient,
+    config: &SendConfig,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &T,
+) -> Result<Signature> {
+    retrying(config, is_retryable, || {
+        try_send_txn(client, config, instructions, payer, signers)
+    })
+    .await
+}
+
+/// Run `attempt` until it succeeds, fails with an error `retryable` rejects,
+/// or `config.retries` run out.
+async fn retrying<F, Fut>(
+    config: &SendConfig,
+    retryable: impl Fn(&anyhow::Error) -> bool,
+    mut attempt_fn: F,
+) -> Result<Signature>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Signature>>,
+{
+    let mut attempt = 0;
+    loop {
+        match attempt_fn().await {
+            Ok(signature) => return Ok(signature),
+            Err(err) if attempt < config.retries && retryable(&err) => {
+                let backoff = config
+                    .backoff
+                    .saturating_mul(1 << attempt.min(16))
+                    .min(MAX_BACKOFF);
+                attempt += 1;
+                eprintln!(
+                    "{}, retry {}/{} in {:?}",
+                    err, attempt, config.retries, backoff
+                );
+                tokio::time::sleep(backoff).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+async fn try_send_txn<T: Signers + ?Sized>(
+    client: &RpcClient,
+    config: &SendConfig,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &T,
+) -> Result<Signature> {
+    // priced again on each attempt, congestion may have changed
+    let (mut txn, expiry) = prepare_txn(client, config, instructions, payer).await?;
+    txn.try_partial_sign(signers, txn.message.recent_blockhash)?;
+    let missing = missing_signers(&txn);
+    if !missing.is_empty() {
+        return Err(
+            SignerError::Custom(format!("{:?} sign elsewhere, use --sign-only", missing)).into(),
+        );
+    }
+    let signature = txn.signatures[0];
+    // a failed send may still have reached the leader, a timeout after the
+    // node took it for one, so only a rejection ends this attempt early
+    if let Err(err) = client
+        .send_transaction_with_config(&txn, send_config(client, config))
+        .await
+    {
+        let err = anyhow::Error::from(err);
+        if !is_retryable(&err) {
+            return Err(err);
+        }
+        eprintln!(
+            "{}, waiting for transaction {} to land or expire",
+            err, signature
+        );
+    }
+    await_txn(client, config, &txn, &signature, &expiry).await
+}
+
+fn send_config(client: &RpcClient, config: &SendConfig) -> RpcSendTransactionConfig {
+    RpcSendTransactionConfig {
+        skip_preflight: config.skip_preflight,
+        preflight_commitment: Some(client.commitment().commitment),
+        ..RpcSendTransactionConfig::default()
+    }
+}
+
+/// Wait for a sent transaction to reach `config.commitment`.
+async fn await_txn(
+    client: &RpcClient,
+    config: &SendConfig,
+    txn: &Transaction,
+    signature: &Signature,
+    expiry: &Expiry,
+) -> Result<Signature> {
+    // from here on the transaction may land, so rpc errors are retried in
+    // place rather than by signing again
+    let mut failures = 0;
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        match confirm_txn(client, config, txn, signature, expiry).await {
+            Ok(true) => return Ok(*signature),
+            Ok(false) => failures = 0,
+            Err(err) if err.is::<TransactionFailed>() || err.is::<BlockhashExpired>() => {
+                return Err(err)
+            }
+            Err(err) => {
+                failures += 1;
+                if failures > config.retries {
+                    return Err(err.context(LostTrack(*signature)));
+                }
+            }
+        }
+    }
+}
+
+/// Whether `signature` reached the commitment; rebroadcasts it while no
+/// node has seen it yet.
+async fn confirm_txn(
+    client: &RpcClient,
+    config: &SendConfig,
+    txn: &Transaction,
+    signature: &Signature,
+    expiry: &Expiry,
+) -> Result<bool> {
+    // checked first: expired and still unseen after means it cannot land
+    let expired = expiry.passed(client).await?;
+    let status = client
+        .get_signature_statuses(&[*signature])
+        .await?
+        .value
+        .pop()
+        .flatten();
+    if let Some(status) = status {
+        if let Some(err) = status.err {
+            return Err(TransactionFailed {
+                signature: *signature,
+                slot: status.slot,
+                err,
+            }
+            .into());
+        }
+        return Ok(status.satisfies_commitment(config.commitment));
+    }
+    if expired {
+        return Err(BlockhashExpired(*signature).into());
+    }
+    // the leader may have dropped it, preflight already ran on the first send
+    client
+        .send_transaction_with_config(
+            txn,
+            RpcSendTransactionConfig {
+                skip_preflight: true,
+                ..RpcSendTransactionConfig::default()
+            },
+        )
+        .await?;
+    Ok(false)
+}
+
+/// ComputeBudget instructions followed by `instructions`: the unit price from
+/// `config.priority_fee`, and the unit limit from `config.compute_limit` or
+/// else a simulation. A simulation that fails sets no limit, and leaves
+/// reporting the error to preflight.
+pub async fn with_compute_budget(
+    client: &RpcClient,
+    config: &SendConfig,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+) -> Result<Vec<Instruction>> {
+    let mut budget = Vec::new();
+    let price = match config.priority_fee {
+        Some(PriorityFee::MicroLamports(price)) => Some(price),
+        Some(PriorityFee::Auto) => Some(estimate_priority_fee(client, instructions).await?),
+        None => None,
+    };
+    if let Some(price) = price {
+        budget.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+    let limit = match config.compute_limit {
+        Some(limit) => Some(limit),
+        None => simulate_compute_units(client, &budget, instructions, payer).await?,
+    };
+    if let Some(limit) = limit {
+        budget.insert(0, ComputeBudgetInstruction::set_compute_unit_limit(limit));
+    }
+    budget.extend_from_slice(instructions);
+    Ok(budget)
+}
+
+/// Unsigned transaction for `instructions` with the compute budget in front,
+/// and before it the advance of `config.nonce` when set, whose durable nonce
+/// then stands in for the blockhash.
+async fn prepare_txn(
+    client: &RpcClient,
+    config: &SendConfig,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+) -> Result<(Transaction, Expiry)> {
+    let mut instructions = with_compute_budget(client, config, instructions, payer).await?;
+    let (blockhash, expiry) = match config.nonce {
+        Some(nonce) => {
+            let authority = config.nonce_authority.unwrap_or(*payer);
+            instructions.insert(
+                0,
+                system_instruction::advance_nonce_account(&nonce, &authority),
+            );
+            let hash = durable_nonce(client, &nonce).await?;
+            (hash, Expiry::Nonce(nonce, hash))
+        }
+        None => {
+            let (hash, _) = client
+                .get_latest_blockhash_with_commitment(client.commitment())
+                .await?;
+            (hash, Expiry::Blockhash(hash))
+        }
+    };
+    let mut txn = Transaction::new_with_payer(&instructions, Some(payer));
+    txn.message.recent_blockhash = blockhash;
+    Ok((txn, expiry))
+}
+
+/// Durable nonce currently held by the `nonce` account.
+async fn durable_nonce(client: &RpcClient, nonce: &Pubkey) -> Result<Hash> {
+    let account =
+        nonce_utils::get_account_with_commitment(client, nonce, client.commitment()).await?;
+    Ok(nonce_utils::data_from_account(&account)?.blockhash())
+}
+
+/// Fee recently paid per compute unit by transactions writing the same
+/// accounts, at `AUTO_FEE_PERCENTILE`.
+async fn estimate_priority_fee(client: &RpcClient, instructions: &[Instruction]) -> Result<u64> {
+    let mut accounts: Vec<Pubkey> = instructions
+        .iter()
+        .flat_map(|instruction| instruction.accounts.iter())
+        .filter(|meta| meta.is_writable)
+        .map(|meta| meta.pubkey)
+        .collect();
+    accounts.sort_unstable();
+    accounts.dedup();
+    accounts.truncate(MAX_FEE_ACCOUNTS);
+    let mut fees: Vec<u64> = client
+        .get_recent_prioritization_fees(&accounts)
+        .await?
+        .into_iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect();
+    if fees.is_empty() {
+        return Ok(0);
+    }
+    fees.sort_unstable();
+    Ok(fees[(fees.len() - 1) * AUTO_FEE_PERCENTILE / 100])
+}
+
+/// Units the transaction uses plus `COMPUTE_MARGIN_PERCENT`, or none when
+/// the simulation fails. Simulated unsigned, with the limit at its maximum.
+async fn simulate_compute_units(
+    client: &RpcClient,
+    budget: &[Instruction],
+    instructions: &[Instruction],
+    payer: &Pubkey,
+) -> Result<Option<u32>> {
+    let mut simulated = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+        MAX_COMPUTE_UNITS,
+    )];
+    simulated.extend_from_slice(budget);
+    simulated.extend_from_slice(instructions);
+    let txn = Transaction::new_with_payer(&simulated, Some(payer));
+    let result = client
+        .simulate_transaction_with_config(
+            &txn,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(client.commitment()),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .await?
+        .value;
+    if result.err.is_some() {
+        return Ok(None);
+    }
+    Ok(result.units_consumed.map(|units| {
+        let units = units + units * COMPUTE_MARGIN_PERCENT / 100;
+        units.min(MAX_COMPUTE_UNITS as u64) as u32
+    }))
+}
+
+/// Sign `instructions` with a fresh blockhash and only simulate them,
+/// collecting logs, inner instructions and compute units. Signatures are not
+/// checked, so keys that sign elsewhere can be left out. Returns the
+/// transaction alongside, as the inner instructions refer to its keys.
+pub async fn simulate_txn<T: Signers + ?Sized>(
+    client: &RpcClient,
+    config: &SendConfig,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &T,
+) -> Result<(Transaction, RpcSimulateTransactionResult)> {
+    let (mut txn, _) = prepare_txn(client, config, instructions, payer).await?;
+    txn.try_partial_sign(signers, txn.message.recent_blockhash)?;
+    let result = client
+        .simulate_transaction_with_config(
+            &txn,
+            RpcSimulateTransactionConfig {
+                commitment: Some(client.commitment()),
+                inner_instructions: true,
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .await?
+        .value;
+    Ok((txn, result))
+}
+
+/// Build the transaction as `send_txn` would, signed only by those of
+/// `signers` it needs, for the others to sign elsewhere.
+pub async fn sign_only_txn<T: Signers + ?Sized>(
+    client: &RpcClient,
+    config: &SendConfig,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &T,
+) -> Result<Transaction> {
+    let (mut txn, _) = prepare_txn(client, config, instructions, payer).await?;
+    txn.try_partial_sign(signers, txn.message.recent_blockhash)?;
+    Ok(txn)
+}
+
+/// Send a transaction signed elsewhere and wait for `config.commitment`. It
+/// cannot be signed again here, so once expired it fails for good.
+pub async fn broadcast_txn(
+    client: &RpcClient,
+    config: &SendConfig,
+    txn: &Transaction,
+) -> Result<Signature> {
+    let missing = missing_signers(txn);
+    if !missing.is_empty() {
+        return Err(format_err!(
+            "transaction is missing signatures from {:?}",
+            missing
+        ));
+    }
+    let expiry = Expiry::of(txn);
+    // sending the same signed transaction again is safe, an expired one
+    // is not worth it
+    let retryable = |err: &anyhow::Error| is_retryable(err) && !err.is::<BlockhashExpired>();
+    retrying(config, retryable, || async {
+        let signature = match client
+            .send_transaction_with_config(txn, send_config(client, config))
+            .await
+        {
+            Ok(signature) => signature,
+            // an earlier attempt got through after all, follow that one
+            Err(err)
+                if matches!(
+                    err.get_transaction_error(),
+                    Some(TransactionError::AlreadyProcessed)
+                ) =>
+            {
+                txn.signatures[0]
+            }
+            Err(err) => return Err(err.into()),
+        };
+        await_txn(client, config, txn, &signature, &expiry).await
+    })
+    .await
+}
+
+/// Required signers that have not signed `txn` yet.
+pub fn missing_signers(txn: &Transaction) -> Vec<Pubkey> {
+    txn.message
+        .account_keys
+        .iter()
+        .zip(txn.signatures.iter())
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(key, _)| *key)
+        .collect()
+}
+
+/// Base64 of the wire format, to carry a transaction between machines.
+pub fn encode_txn(txn: &Transaction) -> Result<String> {
+    Ok(BASE64_STANDARD.encode(bincode::serialize(txn)?))
+}
+
+pub fn decode_txn(data: &str) -> Result<Transaction> {
+    let bytes = BASE64_STANDARD
+        .decode(data.trim())
+        .map_err(|_| format_err!("transaction is not base64: {}", data))?;
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+/// Why the program rejected a transaction, whether it landed or failed
+/// preflight.
+pub fn transaction_error(err: &anyhow::Error) -> Option<TransactionError> {
+    if let Some(err) = err.downcast_ref::<TransactionError>() {
+        return Some(err.clone());
+    }
+    if let Some(failed) = err.downcast_ref::<TransactionFailed>() {
+        return Some(failed.err.clone());
+    }
+    err.downcast_ref::<ClientError>()
+        .and_then(ClientError::get_transaction_error)
+}
+
+/// Errors that sending the same instructions again cannot fix.
+fn is_retryable(err: &anyhow::Error) -> bool {
+    if err.is::<TransactionFailed>() || err.is::<SignerError>() || err.is::<LostTrack>() {
+        return false;
+    }
+    !matches!(
+        err.downcast_ref::<ClientError>().map(ClientError::kind),
+        Some(ClientErrorKind::TransactionError(_))
+            | Some(ClientErrorKind::SigningError(_))
+            | Some(ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                data: RpcResponseErrorData::SendTransactionPreflightFailure(_),
+                ..
+            }))
+    )
+}
+
+/// Blocking front for the cli, which builds its instructions with the
+/// blocking rpc client.
+pub struct TxnSender {
+    runtime: Runtime,
+    client: RpcClient,
+    pub config: SendConfig,
+}
+
+impl TxnSender {
+    pub fn new(http_url: String, config: SendConfig) -> Result<Self> {
+        let runtime = Runtime::new().map_err(|e| format_err!("failed to start runtime: {}", e))?;
+        let client = RpcClient::new_with_commitment(http_url, config.commitment);
+        Ok(TxnSender {
+            runtime,
+            client,
+            config,
+        })
+    }
+
+    /// Send `instructions` paid for by `payer`, who has to be among `signers`.
+    pub fn send<T: Signers + ?Sized>(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        signers: &T,
+    ) -> Result<Signature> {
+        self.runtime.block_on(send_txn(
+            &self.client,
+            &self.config,
+            instructions,
+            payer,
+            signers,
+        ))
+    }
+
+    pub fn simulate<T: Signers + ?Sized>(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        signers: &T,
+    ) -> Result<(Transaction, RpcSimulateTransactionResult)> {
+        self.runtime.block_on(simulate_txn(
+            &self.client,
+            &self.config,
+            instructions,
+            payer,
+            signers,
+        ))
+    }
+
+    pub fn sign_only<T: Signers + ?Sized>(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        signers: &T,
+    ) -> Result<Transaction> {
+        self.runtime.block_on(sign_only_txn(
+            &self.client,
+            &self.config,
+            instructions,
+            payer,
+            signers,
+        ))
+    }
 
-pub fn send_txn(client: &RpcClient, txn: &Transaction, wait_confirm: bool) -> Result<Signature> {
-    Ok(client.send_and_confirm_transaction_with_spinner_and_config(
-        txn,
-        if wait_confirm {
-            CommitmentConfig::confirmed()
-        } else {
-            CommitmentConfig::processed()
-        },
-        RpcSendTransactionConfig {
-            skip_preflight: true,
-            ..RpcSendTransactionConfig::default()
-        },
-    )?)
+    pub fn broadcast(&self, txn: &Transaction) -> Result<Signature> {
+        self.runtime
+            .block_on(broadcast_txn(&self.client, &self.config, txn))
+    }
 }
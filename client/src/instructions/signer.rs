@@ -0,0 +1,126 @@
+use anyhow::{format_err, Result};
+use bip39::{Language, Mnemonic, Seed};
+use solana_sdk::{
+    derivation_path::DerivationPath,
+    pubkey::Pubkey,
+    signature::{keypair_from_seed_and_derivation_path, read_keypair, Keypair},
+    signer::{null_signer::NullSigner, Signer},
+};
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::read_keypair_file;
+
+/// Solana CLI config file, under the home directory.
+const CLI_CONFIG: &str = ".config/solana/cli/config.yml";
+/// Keypair the Solana CLI falls back to when its config names none.
+const CLI_KEYPAIR: &str = ".config/solana/id.json";
+/// First account of the Solana BIP44 path, as wallets derive it.
+const DEFAULT_DERIVATION_PATH: &str = "m/44'/501'/0'/0'";
+
+/// Resolve where a role's key comes from:
+/// - a keypair file, as a path or `file:<path>`
+/// - `config`, the keypair of the Solana CLI default config
+/// - `prompt` or `prompt:<derivation path>`, a BIP39 seed phrase read from
+///   stdin, asking for the derivation path too when not given
+/// - `env:<VAR>`, a keypair in an environment variable, as JSON bytes like a
+///   keypair file or base58
+/// - a pubkey, for a key that signs elsewhere; transactions it has to sign
+///   only go out through `--sign-only`
+pub fn signer_from_uri(uri: &str) -> Result<Box<dyn Signer>> {
+    if let Ok(pubkey) = Pubkey::from_str(uri) {
+        return Ok(Box::new(NullSigner::new(&pubkey)));
+    }
+    if uri == "config" {
+        return Ok(Box::new(cli_config_keypair()?));
+    }
+    if uri == "prompt" {
+        return Ok(Box::new(seed_phrase_keypair(None)?));
+    }
+    if let Some(path) = uri.strip_prefix("prompt:") {
+        return Ok(Box::new(seed_phrase_keypair(Some(path))?));
+    }
+    if let Some(var) = uri.strip_prefix("env:") {
+        return Ok(Box::new(env_keypair(var)?));
+    }
+    let path = uri.strip_prefix("file:").unwrap_or(uri);
+    Ok(Box::new(read_keypair_file(path)?))
+}
+
+/// The `keypair_path` of the Solana CLI config, or the keypair the CLI
+/// defaults to.
+fn cli_config_keypair() -> Result<Keypair> {
+    let home = PathBuf::from(
+        std::env::var("HOME")
+            .map_err(|_| format_err!("HOME is not set, cannot find {}", CLI_CONFIG))?,
+    );
+    let keypair_path = std::fs::read_to_string(home.join(CLI_CONFIG))
+        .ok()
+        .and_then(|config| {
+            config.lines().find_map(|line| {
+                line.trim().strip_prefix("keypair_path:").map(|path| {
+                    path.trim()
+                        .trim_matches(|c| c == '"' || c == '\'')
+                        .to_string()
+                })
+            })
+        })
+        .unwrap_or_else(|| home.join(CLI_KEYPAIR).to_string_lossy().into_owned());
+    read_keypair_file(&keypair_path)
+}
+
+fn seed_phrase_keypair(derivation_path: Option<&str>) -> Result<Keypair> {
+    let phrase = prompt_line("seed phrase: ", true)?;
+    let mnemonic = Mnemonic::from_phrase(&phrase, Language::English)
+        .map_err(|err| format_err!("invalid seed phrase: {}", err))?;
+    let passphrase = prompt_line("passphrase (empty for none): ", true)?;
+    let derivation_path = match derivation_path {
+        Some(path) => path.to_string(),
+        None => {
+            let path = prompt_line(
+                &format!("derivation path [{}]: ", DEFAULT_DERIVATION_PATH),
+                false,
+            )?;
+            if path.is_empty() {
+                DEFAULT_DERIVATION_PATH.to_string()
+            } else {
+                path
+            }
+        }
+    };
+    let derivation_path = DerivationPath::from_absolute_path_str(&derivation_path)
+        .map_err(|err| format_err!("invalid derivation path {}: {}", derivation_path, err))?;
+    let seed = Seed::new(&mnemonic, &passphrase);
+    let keypair = keypair_from_seed_and_derivation_path(seed.as_bytes(), Some(derivation_path))
+        .map_err(|err| format_err!("failed to derive keypair: {}", err))?;
+    // nothing checks a phrase against the expected key, show which one it is
+    eprintln!("signing as {}", keypair.pubkey());
+    Ok(keypair)
+}
+
+fn env_keypair(var: &str) -> Result<Keypair> {
+    let value = std::env::var(var).map_err(|_| format_err!("{} is not set", var))?;
+    let value = value.trim();
+    let keypair = if value.starts_with('[') {
+        read_keypair(&mut value.as_bytes()).ok()
+    } else {
+        bs58::decode(value)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| Keypair::from_bytes(&bytes).ok())
+    };
+    keypair.ok_or_else(|| format_err!("{} does not hold a keypair", var))
+}
+
+/// Read a line from stdin after showing `prompt`, without echoing it when
+/// `hidden` and typed at a terminal.
+fn prompt_line(prompt: &str, hidden: bool) -> Result<String> {
+    if hidden && io::stdin().is_terminal() {
+        return Ok(rpassword::prompt_password(prompt)?.trim().to_string());
+    }
+    eprint!("{}", prompt);
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
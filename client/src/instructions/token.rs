@@ -0,0 +1,132 @@
+use anyhow::{format_err, Result};
+use futures::future;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction, native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, system_instruction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address, instruction::create_associated_token_account_idempotent,
+};
+use spl_token::native_mint;
+use spl_token_2022::{
+    extension::{transfer_hook, StateWithExtensions},
+    state::Mint,
+};
+use spl_transfer_hook_interface::offchain::add_extra_account_metas_for_execute;
+use std::str::FromStr;
+
+/// Programs a mint's transfers go through.
+pub struct MintInfo {
+    /// spl-token or Token-2022, whichever owns the mint.
+    pub token_program: Pubkey,
+    /// Token-2022 transfer hook program, if the mint has one.
+    pub transfer_hook: Option<Pubkey>,
+}
+
+pub fn fetch_mint_info(rpc_client: &RpcClient, mint: &Pubkey) -> Result<MintInfo> {
+    let account = rpc_client.get_account(mint)?;
+    if account.owner != spl_token::id() && account.owner != spl_token_2022::id() {
+        return Err(format_err!("{} is not a token mint", mint));
+    }
+    let state = StateWithExtensions::<Mint>::unpack(&account.data)?;
+    Ok(MintInfo {
+        token_program: account.owner,
+        transfer_hook: transfer_hook::get_program_id(&state),
+    })
+}
+
+/// Append the extra accounts `hook_program` needs for a transfer of `amount`,
+/// which the blink program hands on as remaining accounts.
+#[allow(clippy::too_many_arguments)]
+pub fn add_transfer_hook_accounts(
+    rpc_client: &RpcClient,
+    instruction: &mut Instruction,
+    hook_program: &Pubkey,
+    source: &Pubkey,
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    futures::executor::block_on(add_extra_account_metas_for_execute(
+        instruction,
+        hook_program,
+        source,
+        mint,
+        destination,
+        authority,
+        amount,
+        |address| {
+            let data = rpc_client
+                .get_account(&address)
+                .ok()
+                .map(|account| account.data);
+            future::ready(Ok(data))
+        },
+    ))
+    .map_err(|err| format_err!("failed to resolve transfer hook accounts: {}", err))
+}
+
+/// Parse a mint argument, `sol` standing for the wrapped SOL mint.
+pub fn parse_mint(s: &str) -> Result<Pubkey> {
+    if s.eq_ignore_ascii_case("sol") {
+        return Ok(native_mint::id());
+    }
+    Ok(Pubkey::from_str(s)?)
+}
+
+/// Parse a pot amount: SOL (`1.5`) for the wrapped SOL mint, base units
+/// otherwise.
+pub fn parse_amount(mint: &Pubkey, s: &str) -> Result<u64> {
+    if *mint == native_mint::id() {
+        return parse_sol(s).ok_or_else(|| format_err!("invalid SOL amount: {}", s));
+    }
+    s.parse().map_err(|_| format_err!("invalid amount: {}", s))
+}
+
+/// Lamports in the decimal SOL amount `s`, without going through a float.
+fn parse_sol(s: &str) -> Option<u64> {
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    let digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+    let decimals = native_mint::DECIMALS as usize;
+    if !digits(whole) || !digits(fraction) || fraction.len() > decimals {
+        return None;
+    }
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().ok()?
+    };
+    let fraction: u64 = format!("{:0<decimals$}", fraction).parse().ok()?;
+    whole.checked_mul(LAMPORTS_PER_SOL)?.checked_add(fraction)
+}
+
+/// Move `lamports` into `owner`'s wrapped SOL account, creating it if needed.
+pub fn wrap_sol_instrs(owner: &Pubkey, lamports: u64) -> Result<Vec<Instruction>> {
+    let wsol_account = get_associated_token_address(owner, &native_mint::id());
+    Ok(vec![
+        create_associated_token_account_idempotent(
+            owner,
+            owner,
+            &native_mint::id(),
+            &spl_token::id(),
+        ),
+        system_instruction::transfer(owner, &wsol_account, lamports),
+        spl_token::instruction::sync_native(&spl_token::id(), &wsol_account)?,
+    ])
+}
+
+/// Close `owner`'s wrapped SOL account, returning its balance as lamports.
+pub fn unwrap_sol_instr(owner: &Pubkey) -> Result<Instruction> {
+    let wsol_account = get_associated_token_address(owner, &native_mint::id());
+    Ok(spl_token::instruction::close_account(
+        &spl_token::id(),
+        &wsol_account,
+        owner,
+        owner,
+        &[],
+    )?)
+}
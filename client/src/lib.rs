@@ -0,0 +1,124 @@
+use anyhow::{format_err, Result};
+use clap::Args;
+use configparser::ini::Ini;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+
+use std::str::FromStr;
+
+pub mod instructions;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClientConfig {
+    pub http_url: String,
+    pub ws_url: String,
+    /// Signer URIs of each role, see `instructions::signer::signer_from_uri`.
+    /// Empty when the role is only given on the command line.
+    pub owner_path: Option<String>,
+    pub creator_path: Option<String>,
+    pub user_path: Option<String>,
+    /// Signer URIs of the global roles, the owner signs for any left empty.
+    pub time_manager_path: Option<String>,
+    pub resolver_path: Option<String>,
+    pub pause_guardian_path: Option<String>,
+    pub blink_program: Pubkey,
+}
+
+pub fn load_cfg(client_config: &String) -> Result<ClientConfig> {
+    let mut config = Ini::new();
+    let _map = config.load(client_config).unwrap();
+    let http_url = config.get("Global", "http_url").unwrap();
+    if http_url.is_empty() {
+        panic!("http_url must not be empty");
+    }
+    let ws_url = config.get("Global", "ws_url").unwrap();
+    if ws_url.is_empty() {
+        panic!("ws_url must not be empty");
+    }
+    let owner_path = config
+        .get("Global", "owner_path")
+        .filter(|path| !path.is_empty());
+    let creator_path = config
+        .get("Global", "creator_path")
+        .filter(|path| !path.is_empty());
+    let user_path = config
+        .get("Global", "user_path")
+        .filter(|path| !path.is_empty());
+    let time_manager_path = config
+        .get("Global", "time_manager_path")
+        .filter(|path| !path.is_empty());
+    let resolver_path = config
+        .get("Global", "resolver_path")
+        .filter(|path| !path.is_empty());
+    let pause_guardian_path = config
+        .get("Global", "pause_guardian_path")
+        .filter(|path| !path.is_empty());
+
+    let blink_program_str = config.get("Global", "blink_program").unwrap();
+    if blink_program_str.is_empty() {
+        panic!("blink_program must not be empty");
+    }
+    let blink_program = Pubkey::from_str(&blink_program_str).unwrap();
+
+    Ok(ClientConfig {
+        http_url,
+        ws_url,
+        owner_path,
+        creator_path,
+        user_path,
+        time_manager_path,
+        resolver_path,
+        pause_guardian_path,
+        blink_program,
+    })
+}
+
+pub fn read_keypair_file(s: &str) -> Result<Keypair> {
+    solana_sdk::signature::read_keypair_file(s)
+        .map_err(|_| format_err!("failed to read keypair from {}", s))
+}
+
+/// Question and answer labels shown for a blink.
+#[derive(Clone, Debug, Args)]
+pub struct BlinkContent {
+    pub pic: String,
+    pub content: String,
+    pub option1: String,
+    pub option2: String,
+    pub option3: String,
+    pub option4: String,
+}
+
+/// Question and answer labels in another language.
+#[derive(Debug, Args)]
+pub struct TranslationContent {
+    pub content: String,
+    pub option1: String,
+    pub option2: String,
+    pub option3: String,
+    pub option4: String,
+}
+
+impl From<BlinkContent> for blink_sdk::instructions::BlinkContent {
+    fn from(content: BlinkContent) -> Self {
+        Self {
+            pic: content.pic,
+            content: content.content,
+            option1: content.option1,
+            option2: content.option2,
+            option3: content.option3,
+            option4: content.option4,
+        }
+    }
+}
+
+impl From<TranslationContent> for blink_sdk::instructions::TranslationContent {
+    fn from(translation: TranslationContent) -> Self {
+        Self {
+            content: translation.content,
+            option1: translation.option1,
+            option2: translation.option2,
+            option3: translation.option3,
+            option4: translation.option4,
+        }
+    }
+}
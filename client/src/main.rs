@@ -1,116 +1,319 @@
 use anchor_client::{Client, Cluster};
 use anyhow::{format_err, Result};
 use clap::Parser;
-use configparser::ini::Ini;
 use solana_client::{rpc_client::RpcClient, rpc_config::RpcTransactionConfig};
 use solana_sdk::{
-    commitment_config::CommitmentConfig,
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    instruction::Instruction,
     pubkey::Pubkey,
     signature::{Keypair, Signature, Signer},
     transaction::Transaction,
 };
 use solana_transaction_status::UiTransactionEncoding;
 
+use blink::state::Role;
+use blink_sdk::{accounts as blink_accounts, errors};
+
 use std::rc::Rc;
 use std::str::FromStr;
+use std::time::Duration;
 
-mod instructions;
-use instructions::blink_instructions::*;
-use instructions::event_instructions_parse::*;
-use instructions::rpc::*;
-
-#[derive(Clone, Debug, PartialEq)]
-pub struct ClientConfig {
-    http_url: String,
-    ws_url: String,
-    owner_path: String,
-    creator_path: String,
-    user_path: String,
-    blink_program: Pubkey,
-}
+use client::instructions::blink_instructions::*;
+use client::instructions::clock::*;
+use client::instructions::event_instructions_parse::*;
+use client::instructions::metadata::*;
+use client::instructions::query::*;
+use client::instructions::rpc::*;
+use client::instructions::signer::signer_from_uri;
+use client::instructions::token::{parse_amount, parse_mint};
+use client::{load_cfg, BlinkContent, ClientConfig, TranslationContent};
 
-fn load_cfg(client_config: &String) -> Result<ClientConfig> {
-    let mut config = Ini::new();
-    let _map = config.load(client_config).unwrap();
-    let http_url = config.get("Global", "http_url").unwrap();
-    if http_url.is_empty() {
-        panic!("http_url must not be empty");
-    }
-    let ws_url = config.get("Global", "ws_url").unwrap();
-    if ws_url.is_empty() {
-        panic!("ws_url must not be empty");
-    }
-    let owner_path = config.get("Global", "owner_path").unwrap();
-    if owner_path.is_empty() {
-        panic!("owner_path must not be empty");
-    }
-    let creator_path = config.get("Global", "creator_path").unwrap();
-    if creator_path.is_empty() {
-        panic!("creator_path must not be empty");
-    }
-    let user_path = config.get("Global", "user_path").unwrap();
-    if user_path.is_empty() {
-        panic!("user_path must not be empty");
-    }
-
-    let blink_program_str = config.get("Global", "blink_program").unwrap();
-    if blink_program_str.is_empty() {
-        panic!("blink_program must not be empty");
-    }
-    let blink_program = Pubkey::from_str(&blink_program_str).unwrap();
-
-    Ok(ClientConfig {
-        http_url,
-        ws_url,
-        owner_path,
-        creator_path,
-        user_path,
-        blink_program,
-    })
+#[derive(Debug, Parser)]
+pub struct Opts {
+    #[clap(subcommand)]
+    pub command: BlinkCommands,
+    /// Retries on rpc errors and expired blockhashes, with exponential backoff.
+    #[clap(long, global = true, default_value_t = 5)]
+    pub retries: u32,
+    /// First retry delay in milliseconds, doubled for each one after.
+    #[clap(long, global = true, default_value_t = 500)]
+    pub backoff_ms: u64,
+    /// Wait until transactions are `processed`, `confirmed` or `finalized`.
+    #[clap(long, global = true, default_value = "confirmed")]
+    pub commitment: CommitmentLevel,
+    /// Only simulate: print the logs, events, compute units and any program
+    /// error, without sending.
+    #[clap(long, global = true, conflicts_with = "sign_only")]
+    pub simulate: bool,
+    /// Print the transaction, signed by the local keypairs it needs, for the
+    /// other signers to `sign` and anyone to `broadcast`.
+    #[clap(long, global = true)]
+    pub sign_only: bool,
+    /// Send without simulating first, failing transactions then pay fees.
+    #[clap(long, global = true)]
+    pub skip_preflight: bool,
+    /// Compute unit price in micro-lamports, or `auto` to match recent fees
+    /// paid for the same accounts.
+    #[clap(long, global = true)]
+    pub priority_fee: Option<PriorityFee>,
+    /// Compute unit limit, by default what a simulation uses plus a margin.
+    #[clap(long, global = true)]
+    pub compute_limit: Option<u32>,
+    /// Sign with the durable nonce of this account instead of a blockhash, so
+    /// the transaction does not expire while it is passed around.
+    #[clap(long, global = true)]
+    pub nonce: Option<Pubkey>,
+    /// Signer URI of the nonce authority, defaults to the fee payer.
+    #[clap(long, global = true, requires = "nonce")]
+    pub nonce_authority: Option<String>,
+    /// Signer URI of the owner, who administers the program, instead of
+    /// `owner_path`: a keypair file, `config` for the Solana CLI keypair,
+    /// `prompt[:<derivation path>]` for a seed phrase, `env:<VAR>`, or a
+    /// pubkey that signs elsewhere.
+    #[clap(long, global = true)]
+    pub owner: Option<String>,
+    /// Signer URI of the creator, instead of `creator_path`.
+    #[clap(long, global = true)]
+    pub creator: Option<String>,
+    /// Signer URI of the user, instead of `user_path`.
+    #[clap(long, global = true)]
+    pub user: Option<String>,
+    /// Signer URI of the time manager, instead of `time_manager_path`. The
+    /// owner signs when neither is set.
+    #[clap(long, global = true)]
+    pub time_manager: Option<String>,
+    /// Signer URI of the resolver, instead of `resolver_path`. The owner
+    /// signs when neither is set.
+    #[clap(long, global = true)]
+    pub resolver: Option<String>,
+    /// Signer URI of the pause guardian, instead of `pause_guardian_path`.
+    /// The owner signs when neither is set.
+    #[clap(long, global = true)]
+    pub pause_guardian: Option<String>,
+    /// Signer URI paying the transaction fees, such as a sponsor for users'
+    /// submits, along with the rent of the accounts a submit or claim
+    /// creates. Defaults to whoever signs the command.
+    #[clap(long, global = true)]
+    pub fee_payer: Option<String>,
 }
 
-fn read_keypair_file(s: &str) -> Result<Keypair> {
-    solana_sdk::signature::read_keypair_file(s)
-        .map_err(|_| format_err!("failed to read keypair from {}", s))
+#[derive(Debug, Parser)]
+pub enum SeriesCommands {
+    /// Fund a streak bonus; each consecutive correct answer adds `bonus_bps`
+    /// of the reward, up to `max_streak` steps, on claims until `end_time`,
+    /// a clock value or `+<duration>`.
+    Create {
+        id: u64,
+        #[clap(value_parser = parse_mint)]
+        token_mint: Pubkey,
+        bonus: String,
+        end_time: String,
+        #[clap(long, default_value_t = 1000)]
+        bonus_bps: u16,
+        #[clap(long, default_value_t = 5)]
+        max_streak: u32,
+    },
+    Fund {
+        id: u64,
+        amount: String,
+    },
+    AddBlink {
+        id: u64,
+        index: u64,
+    },
+    /// Take back what is left of the bonus once the series has ended.
+    Withdraw {
+        id: u64,
+    },
+    /// Show a series, and `--user`'s streak in it.
+    Show {
+        id: u64,
+        #[clap(long)]
+        user: Option<Pubkey>,
+    },
 }
 
 #[derive(Debug, Parser)]
-pub struct Opts {
-    #[clap(subcommand)]
-    pub command: BlinkCommands,
+pub enum SeasonCommands {
+    /// Fund a season prize; `end_time` is a clock value or `+<duration>`.
+    Create {
+        id: u64,
+        #[clap(value_parser = parse_mint)]
+        token_mint: Pubkey,
+        prize: String,
+        end_time: String,
+        #[clap(long, default_value_t = 3)]
+        top_n: u8,
+    },
+    AddBlink {
+        id: u64,
+        index: u64,
+    },
+    /// Count a user's correct answer on a season blink.
+    Score {
+        id: u64,
+        index: u64,
+        user: Pubkey,
+    },
+    /// Pay every unpaid leaderboard entry of an ended season.
+    Payout {
+        id: u64,
+    },
+    Leaderboard {
+        id: u64,
+    },
 }
 
 #[derive(Debug, Parser)]
 pub enum BlinkCommands {
+    /// Sign as the program's upgrade authority, who becomes the admin.
+    CreateGlobal,
+    ProposeAdmin {
+        admin: Pubkey,
+    },
+    /// Sign as the proposed admin, given as a signer URI.
+    AcceptAdmin {
+        admin: String,
+    },
+    SetTimeManager {
+        time_manager: Pubkey,
+    },
+    SetResolver {
+        resolver: Pubkey,
+    },
+    SetPauseGuardian {
+        pause_guardian: Pubkey,
+    },
+    /// Stop everything but admin changes until `unpause`.
+    Pause,
+    Unpause,
+    FreezeBlink {
+        index: u64,
+        #[clap(long)]
+        unfreeze: bool,
+    },
+    /// Times are absolute clock values (unix timestamp, or slot when the
+    /// program runs on slots) or `+<duration>` from now; periods are clock units
+    /// or durations such as `10m`, `2h`, `1d`.
     CreateTime {
-        open_time: u64,
-        period: u64,
+        open_time: String,
+        period: String,
     },
     UpdateTime {
-        open_time: u64,
-        period: u64,
+        open_time: String,
+        period: String,
     },
+    /// `token_mint` may be `sol`, the pot is then wrapped from lamports and
+    /// `amount` is in SOL. With `--sign-only` the transaction is bound to the
+    /// next index, broadcast it before any other blink is created.
     Initialize {
-        index: u16,
+        #[clap(value_parser = parse_mint)]
+        token_mint: Pubkey,
+        amount: String,
+        #[clap(flatten)]
+        content: BlinkContent,
+    },
+    /// Create a blink whose content is the JSON document at `uri`. The hash is
+    /// taken from `--source` (a local copy) when given, otherwise from `uri`.
+    /// A `--sign-only` one must be broadcast before any other create.
+    InitializeMetadata {
+        #[clap(value_parser = parse_mint)]
         token_mint: Pubkey,
-        amount: u64,
-        pic: String,
-        content: String,
-        option1: String,
-        option2: String,
-        option3: String,
-        option4: String,
+        amount: String,
+        uri: String,
+        #[clap(long)]
+        source: Option<String>,
+    },
+    /// Replace the content of a blink that has not opened yet. A metadata
+    /// blink becomes an inline one.
+    UpdateConfig {
+        index: u64,
+        #[clap(flatten)]
+        content: BlinkContent,
+    },
+    /// Point a blink that has not opened yet at the document at `uri`.
+    UpdateMetadata {
+        index: u64,
+        uri: String,
+        #[clap(long)]
+        source: Option<String>,
+    },
+    /// Add a language variant to a blink that has not opened yet.
+    AddTranslation {
+        index: u64,
+        lang: String,
+        #[clap(flatten)]
+        content: TranslationContent,
+    },
+    /// Write a metadata document; `--localized <lang>=<path>` may repeat.
+    MetadataBuild {
+        #[clap(flatten)]
+        content: BlinkContent,
+        #[clap(long)]
+        localized: Vec<String>,
+        #[clap(long)]
+        out: String,
+    },
+    MetadataHash {
+        source: String,
+    },
+    /// Check a blink's metadata against its on-chain hash.
+    MetadataVerify {
+        index: u64,
+        #[clap(long)]
+        source: Option<String>,
     },
     Submit {
-        index: u16,
+        index: u64,
         answer: u8,
     },
+    /// Create the user's profile, which counts their submits and claims from
+    /// then on. `--fee-payer` may sponsor its rent.
+    InitProfile,
+    /// Resolve as the creator, or with `--as-resolver` as the resolver role
+    /// once the grace period after the close time is over.
     Close {
-        index: u16,
+        index: u64,
         answer: u8,
+        #[clap(long)]
+        as_resolver: bool,
     },
+    /// Claim a reward, with `--series` counting it towards a streak.
     Claim {
-        index: u16,
+        index: u64,
+        #[clap(long)]
+        series: Option<u64>,
+    },
+    ExtendDeadline {
+        index: u64,
+        close_time: String,
+    },
+    EndEarly {
+        index: u64,
+        close_time: String,
+    },
+    /// Move accounts still stored with the layout from before versioning to
+    /// the current one, `batch` per transaction.
+    Migrate {
+        #[clap(long, default_value_t = 4)]
+        batch: usize,
+        #[clap(long)]
+        dry_run: bool,
+    },
+    #[clap(subcommand)]
+    Season(SeasonCommands),
+    #[clap(subcommand)]
+    Series(SeriesCommands),
+    /// Participation totals of a wallet.
+    Profile {
+        wallet: Pubkey,
+    },
+    QueryGlobal,
+    /// Show content in `--lang` when the blink has that translation.
+    QueryBlink {
+        index: u64,
+        #[clap(long)]
+        lang: Option<String>,
     },
     DecodeInstruction {
         instr_hex_data: String,
@@ -121,131 +324,549 @@ pub enum BlinkCommands {
     DecodeTxLog {
         tx_id: String,
     },
+    /// Add the signature of `signer`, a signer URI, to a transaction from
+    /// `--sign-only`.
+    Sign {
+        transaction: String,
+        signer: String,
+    },
+    /// Send a transaction once everyone has signed it.
+    Broadcast {
+        transaction: String,
+    },
 }
+/// Name the program error behind a failed transaction, when there is one.
+fn explain_error(err: anyhow::Error) -> anyhow::Error {
+    match transaction_error(&err)
+        .as_ref()
+        .and_then(errors::program_error)
+    {
+        Some((index, code)) => err.context(format!(
+            "instruction {} failed with {:?}: {}",
+            index, code, code
+        )),
+        None => err,
+    }
+}
+
+/// Create a blink, built again on the counter's next index each time a racing
+/// creator takes the one it was built for. Returns the index it got.
+fn initialize_blink(
+    config: &ClientConfig,
+    creator: &dyn Signer,
+    token_mint: Pubkey,
+    amount: u64,
+    source: BlinkSource,
+    retries: u32,
+    submit: impl Fn(&[Instruction], &dyn Signer) -> Result<()>,
+) -> Result<u64> {
+    let mut attempt = 0;
+    loop {
+        let (initialize, index) = initialize_instr(
+            config,
+            &creator.pubkey(),
+            token_mint,
+            amount,
+            source.clone(),
+        )?;
+        match submit(&initialize, creator) {
+            Ok(()) => return Ok(index),
+            Err(err) if attempt < retries && is_index_taken(&err) => {
+                attempt += 1;
+                eprintln!("index {} was taken, retry {}/{}", index, attempt, retries);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Blink instructions of `txn`, for a signer to review, then the transaction
+/// itself and who still has to sign it.
+fn print_txn(program: &Pubkey, txn: &Transaction) -> Result<()> {
+    for instruction in txn.message.instructions.iter() {
+        if txn.message.account_keys[instruction.program_id_index as usize] == *program {
+            handle_program_instruction(
+                &hex::encode(&instruction.data),
+                InstructionDecodeType::BaseHex,
+            )?;
+        }
+    }
+    println!("{}", encode_txn(txn)?);
+    for signer in missing_signers(txn) {
+        eprintln!("missing signature: {}", signer);
+    }
+    Ok(())
+}
+
+/// Signer of a role, from its flag or else `client_config.ini`.
+fn role_signer(
+    uri: &Option<String>,
+    configured: &Option<String>,
+    role: &str,
+) -> Result<Rc<dyn Signer>> {
+    let uri = uri
+        .as_deref()
+        .or(configured.as_deref())
+        .ok_or_else(|| format_err!("no --{role} given and {role}_path is empty"))?;
+    Ok(Rc::from(signer_from_uri(uri)?))
+}
+
 fn main() -> Result<()> {
     let client_config = "client_config.ini";
     let pool_config = load_cfg(&client_config.to_string())?;
-    // cluster params.
-    let payer = read_keypair_file(&pool_config.owner_path)?;
     // solana rpc client
     let rpc_client = RpcClient::new(pool_config.http_url.to_string());
 
     let anchor_config = pool_config.clone();
     let url = Cluster::Custom(anchor_config.http_url, anchor_config.ws_url);
-    let wallet = read_keypair_file(&anchor_config.owner_path)?;
-    let anchor_client = Client::new(url, Rc::new(wallet));
-    let _program = anchor_client.program(anchor_config.blink_program)?;
+    // queries only read, any wallet does
+    let anchor_client = Client::new(url, Rc::new(Keypair::new()));
+    let program = anchor_client.program(anchor_config.blink_program)?;
 
     let opts = Opts::parse();
+    // only commands taking times need to know how the program measures them
+    let slot_clock = || fetch_slot_clock(&rpc_client, &pool_config.blink_program);
+    // role keys are only read, or prompted for, by the commands signing as them
+    let owner = || role_signer(&opts.owner, &pool_config.owner_path, "owner");
+    let creator = || role_signer(&opts.creator, &pool_config.creator_path, "creator");
+    let user = || role_signer(&opts.user, &pool_config.user_path, "user");
+    // create_global hands every global role to the admin, so until they are
+    // set apart the owner signs for them
+    let role_or_owner = |uri: &Option<String>, configured: &Option<String>| match uri
+        .as_deref()
+        .or(configured.as_deref())
+    {
+        Some(uri) => Ok(Rc::<dyn Signer>::from(signer_from_uri(uri)?)),
+        None => owner(),
+    };
+    let time_manager = || role_or_owner(&opts.time_manager, &pool_config.time_manager_path);
+    let resolver = || role_or_owner(&opts.resolver, &pool_config.resolver_path);
+    let pause_guardian = || role_or_owner(&opts.pause_guardian, &pool_config.pause_guardian_path);
+    let fee_payer = opts
+        .fee_payer
+        .as_deref()
+        .map(signer_from_uri)
+        .transpose()?
+        .map(Rc::<dyn Signer>::from);
+    // for instructions that take nothing but a payer
+    let payer_or = |role: &dyn Fn() -> Result<Rc<dyn Signer>>| match &fee_payer {
+        Some(fee_payer) => Ok(fee_payer.clone()),
+        None => role(),
+    };
+    let nonce_authority = opts
+        .nonce_authority
+        .as_deref()
+        .map(signer_from_uri)
+        .transpose()?;
+    if opts.sign_only && opts.nonce.is_none() {
+        eprintln!("without --nonce the transaction expires with its blockhash in about a minute");
+    }
+    let sender = TxnSender::new(
+        pool_config.http_url.clone(),
+        SendConfig {
+            retries: opts.retries,
+            backoff: Duration::from_millis(opts.backoff_ms),
+            commitment: CommitmentConfig {
+                commitment: opts.commitment,
+            },
+            skip_preflight: opts.skip_preflight,
+            priority_fee: opts.priority_fee,
+            compute_limit: opts.compute_limit,
+            nonce: opts.nonce,
+            nonce_authority: nonce_authority.as_ref().map(|authority| authority.pubkey()),
+        },
+    )?;
+    // send, or with `--simulate` only report what the transaction would do,
+    // or with `--sign-only` hand it on
+    let submit = |instructions: &[Instruction], signer: &dyn Signer| -> Result<()> {
+        let program = pool_config.blink_program.to_string();
+        let payer = fee_payer.as_deref().unwrap_or(signer);
+        let mut signers = vec![payer, signer];
+        signers.extend(nonce_authority.as_deref());
+        if opts.sign_only {
+            let txn = sender.sign_only(instructions, &payer.pubkey(), &signers)?;
+            return print_txn(&pool_config.blink_program, &txn);
+        }
+        if !opts.simulate {
+            let signature = sender
+                .send(instructions, &payer.pubkey(), &signers)
+                .map_err(explain_error)?;
+            println!("{}", signature);
+            return Ok(());
+        }
+        let (txn, result) = sender.simulate(instructions, &payer.pubkey(), &signers)?;
+        let logs = result.logs.unwrap_or_default();
+        for log in logs.iter() {
+            println!("{}", log);
+        }
+        parse_program_logs(&program, &logs)?;
+        parse_inner_events(
+            &program,
+            &txn.message.account_keys,
+            &result.inner_instructions.unwrap_or_default(),
+        )?;
+        if let Some(units) = result.units_consumed {
+            println!("compute units: {}", units);
+        }
+        match result.err {
+            Some(err) => Err(explain_error(err.into())),
+            None => Ok(()),
+        }
+    };
     match opts.command {
+        BlinkCommands::CreateGlobal => {
+            let admin = owner()?;
+            let create_global = create_global_instr(&pool_config, &admin.pubkey())?;
+
+            submit(&create_global, &*admin)?;
+        }
+        BlinkCommands::ProposeAdmin { admin: new_admin } => {
+            let admin = owner()?;
+            let propose_admin =
+                set_role_instr(&pool_config, &admin.pubkey(), Role::Admin, new_admin)?;
+
+            submit(&propose_admin, &*admin)?;
+        }
+        BlinkCommands::AcceptAdmin { admin } => {
+            let admin = signer_from_uri(&admin)?;
+            let accept_admin = accept_admin_instr(&pool_config, admin.pubkey())?;
+
+            submit(&accept_admin, &*admin)?;
+        }
+        BlinkCommands::SetTimeManager { time_manager } => {
+            let admin = owner()?;
+            let set_role = set_role_instr(
+                &pool_config,
+                &admin.pubkey(),
+                Role::TimeManager,
+                time_manager,
+            )?;
+
+            submit(&set_role, &*admin)?;
+        }
+        BlinkCommands::SetResolver { resolver } => {
+            let admin = owner()?;
+            let set_role = set_role_instr(&pool_config, &admin.pubkey(), Role::Resolver, resolver)?;
+
+            submit(&set_role, &*admin)?;
+        }
+        BlinkCommands::SetPauseGuardian { pause_guardian } => {
+            let admin = owner()?;
+            let set_role = set_role_instr(
+                &pool_config,
+                &admin.pubkey(),
+                Role::PauseGuardian,
+                pause_guardian,
+            )?;
+
+            submit(&set_role, &*admin)?;
+        }
+        BlinkCommands::Pause => {
+            let pause_guardian = pause_guardian()?;
+            let pause = pause_instr(&pool_config, &pause_guardian.pubkey(), true)?;
+
+            submit(&pause, &*pause_guardian)?;
+        }
+        BlinkCommands::Unpause => {
+            let pause_guardian = pause_guardian()?;
+            let unpause = pause_instr(&pool_config, &pause_guardian.pubkey(), false)?;
+
+            submit(&unpause, &*pause_guardian)?;
+        }
+        BlinkCommands::FreezeBlink { index, unfreeze } => {
+            let pause_guardian = pause_guardian()?;
+            let freeze_blink =
+                freeze_blink_instr(&pool_config, &pause_guardian.pubkey(), index, !unfreeze)?;
+
+            submit(&freeze_blink, &*pause_guardian)?;
+        }
         BlinkCommands::CreateTime { open_time, period } => {
-            let create_config = create_config_instr(&pool_config, open_time, period)?;
-
-            let signers = vec![&payer];
-            let recent_hash = rpc_client.get_latest_blockhash()?;
-            let txn = Transaction::new_signed_with_payer(
-                &create_config,
-                Some(&payer.pubkey()),
-                &signers,
-                recent_hash,
-            );
-            let signature = send_txn(&rpc_client, &txn, true)?;
-            println!("{}", signature);
+            let slot_clock = slot_clock()?;
+            let open_time = resolve_time(&rpc_client, slot_clock, &open_time)?;
+            let period = resolve_period(&rpc_client, slot_clock, &period)?;
+            let time_manager = time_manager()?;
+            let create_config =
+                create_config_instr(&pool_config, &time_manager.pubkey(), open_time, period)?;
+
+            submit(&create_config, &*time_manager)?;
         }
         BlinkCommands::UpdateTime { open_time, period } => {
-            let update_config = update_config_instr(&pool_config, open_time, period)?;
-
-            let signers = vec![&payer];
-            let recent_hash = rpc_client.get_latest_blockhash()?;
-            let txn = Transaction::new_signed_with_payer(
-                &update_config,
-                Some(&payer.pubkey()),
-                &signers,
-                recent_hash,
-            );
-            let signature = send_txn(&rpc_client, &txn, true)?;
-            println!("{}", signature);
+            let slot_clock = slot_clock()?;
+            let open_time = resolve_time(&rpc_client, slot_clock, &open_time)?;
+            let period = resolve_period(&rpc_client, slot_clock, &period)?;
+            let time_manager = time_manager()?;
+            let update_config =
+                update_config_instr(&pool_config, &time_manager.pubkey(), open_time, period)?;
+
+            submit(&update_config, &*time_manager)?;
         }
         BlinkCommands::Initialize {
-            index,
             token_mint,
             amount,
-            pic,
             content,
-            option1,
-            option2,
-            option3,
-            option4,
         } => {
-            let initialize = initialize_instr(
+            let amount = parse_amount(&token_mint, &amount)?;
+            let source = BlinkSource::Inline(content);
+            print_initialize_rent(&rpc_client, &source)?;
+            let creator = creator()?;
+            let index = initialize_blink(
                 &pool_config,
-                index,
+                &*creator,
                 token_mint,
                 amount,
-                pic,
-                content,
-                option1,
-                option2,
-                option3,
-                option4,
+                source,
+                opts.retries,
+                submit,
             )?;
-            let payer = read_keypair_file(&pool_config.creator_path)?;
-
-            let signers = vec![&payer];
-            let recent_hash = rpc_client.get_latest_blockhash()?;
-            let txn = Transaction::new_signed_with_payer(
-                &initialize,
-                Some(&payer.pubkey()),
-                &signers,
-                recent_hash,
-            );
-            let signature = send_txn(&rpc_client, &txn, true)?;
-            println!("{}", signature);
+            println!("index: {}", index);
+        }
+        BlinkCommands::InitializeMetadata {
+            token_mint,
+            amount,
+            uri,
+            source,
+        } => {
+            let amount = parse_amount(&token_mint, &amount)?;
+            let data = load_metadata(source.as_deref().unwrap_or(&uri))?;
+            parse_metadata(&data)?;
+            let source = BlinkSource::Metadata {
+                uri,
+                metadata_hash: metadata_hash(&data),
+            };
+            print_initialize_rent(&rpc_client, &source)?;
+            let creator = creator()?;
+            let index = initialize_blink(
+                &pool_config,
+                &*creator,
+                token_mint,
+                amount,
+                source,
+                opts.retries,
+                submit,
+            )?;
+            println!("index: {}", index);
+        }
+        BlinkCommands::UpdateConfig { index, content } => {
+            let creator = creator()?;
+            let update_config =
+                update_blink_config_instr(&pool_config, &creator.pubkey(), index, content)?;
+
+            submit(&update_config, &*creator)?;
+        }
+        BlinkCommands::UpdateMetadata { index, uri, source } => {
+            let data = load_metadata(source.as_deref().unwrap_or(&uri))?;
+            parse_metadata(&data)?;
+            let creator = creator()?;
+            let update_metadata = update_metadata_instr(
+                &pool_config,
+                &creator.pubkey(),
+                index,
+                uri,
+                metadata_hash(&data),
+            )?;
+
+            submit(&update_metadata, &*creator)?;
+        }
+        BlinkCommands::AddTranslation {
+            index,
+            lang,
+            content,
+        } => {
+            let creator = creator()?;
+            let add_translation =
+                add_translation_instr(&pool_config, &creator.pubkey(), index, lang, content)?;
+
+            submit(&add_translation, &*creator)?;
         }
         BlinkCommands::Submit { index, answer } => {
-            let sumbit = submit_instr(&pool_config, index, answer)?;
-            let payer = read_keypair_file(&pool_config.user_path)?;
-
-            let signers = vec![&payer];
-            let recent_hash = rpc_client.get_latest_blockhash()?;
-            let txn = Transaction::new_signed_with_payer(
-                &sumbit,
-                Some(&payer.pubkey()),
-                &signers,
-                recent_hash,
-            );
-            let signature = send_txn(&rpc_client, &txn, true)?;
-            println!("{}", signature);
+            let user = user()?;
+            let payer = payer_or(&|| Ok(user.clone()))?;
+            let sumbit =
+                submit_instr(&pool_config, &user.pubkey(), &payer.pubkey(), index, answer)?;
+
+            submit(&sumbit, &*user)?;
         }
-        BlinkCommands::Close { index, answer } => {
-            let close = close_instr(&pool_config, index, answer)?;
-            let payer = read_keypair_file(&pool_config.creator_path)?;
-
-            let signers = vec![&payer];
-            let recent_hash = rpc_client.get_latest_blockhash()?;
-            let txn = Transaction::new_signed_with_payer(
-                &close,
-                Some(&payer.pubkey()),
-                &signers,
-                recent_hash,
-            );
-            let signature = send_txn(&rpc_client, &txn, true)?;
-            println!("{}", signature);
+        BlinkCommands::InitProfile => {
+            let user = user()?;
+            let payer = payer_or(&|| Ok(user.clone()))?;
+            let create_profile =
+                create_profile_instr(&pool_config, &user.pubkey(), &payer.pubkey())?;
+
+            submit(&create_profile, &*user)?;
         }
-        BlinkCommands::Claim { index } => {
-            let claim = claim_instr(&pool_config, index)?;
-            let payer = read_keypair_file(&pool_config.user_path)?;
-
-            let signers = vec![&payer];
-            let recent_hash = rpc_client.get_latest_blockhash()?;
-            let txn = Transaction::new_signed_with_payer(
-                &claim,
-                Some(&payer.pubkey()),
-                &signers,
-                recent_hash,
-            );
-            let signature = send_txn(&rpc_client, &txn, true)?;
-            println!("{}", signature);
+        BlinkCommands::Close {
+            index,
+            answer,
+            as_resolver,
+        } => {
+            let resolver = if as_resolver { resolver()? } else { creator()? };
+            let close = close_instr(&pool_config, &resolver.pubkey(), index, answer)?;
+
+            submit(&close, &*resolver)?;
+        }
+        BlinkCommands::Claim { index, series } => {
+            let user = user()?;
+            let payer = payer_or(&|| Ok(user.clone()))?;
+            let claim = claim_instr(&pool_config, &user.pubkey(), &payer.pubkey(), index, series)?;
+
+            submit(&claim, &*user)?;
+        }
+        BlinkCommands::ExtendDeadline { index, close_time } => {
+            let close_time = resolve_time(&rpc_client, slot_clock()?, &close_time)?;
+            let creator = creator()?;
+            let extend_deadline =
+                extend_deadline_instr(&pool_config, &creator.pubkey(), index, close_time)?;
+
+            submit(&extend_deadline, &*creator)?;
+        }
+        BlinkCommands::EndEarly { index, close_time } => {
+            let close_time = resolve_time(&rpc_client, slot_clock()?, &close_time)?;
+            let creator = creator()?;
+            let end_early = end_early_instr(&pool_config, &creator.pubkey(), index, close_time)?;
+
+            submit(&end_early, &*creator)?;
+        }
+        BlinkCommands::Migrate { batch, dry_run } => {
+            let legacy = find_legacy_accounts(&rpc_client, &pool_config.blink_program)?;
+            println!("{} accounts to migrate", legacy.len());
+            for account in legacy.iter() {
+                println!("{:?}", account);
+            }
+            if !dry_run {
+                let payer = payer_or(&owner)?;
+                for chunk in legacy.chunks(batch.max(1)) {
+                    let migrate = migrate_instr(&pool_config, &payer.pubkey(), chunk)?;
+
+                    submit(&migrate, &*payer)?;
+                }
+            }
+        }
+        // one transaction each, payouts can carry transfer hook accounts
+        BlinkCommands::Season(season_command) => match season_command {
+            SeasonCommands::Create {
+                id,
+                token_mint,
+                prize,
+                end_time,
+                top_n,
+            } => {
+                let prize = parse_amount(&token_mint, &prize)?;
+                let end_time = resolve_time(&rpc_client, slot_clock()?, &end_time)?;
+                let owner = creator()?;
+                let create_season = create_season_instr(
+                    &pool_config,
+                    &owner.pubkey(),
+                    id,
+                    token_mint,
+                    prize,
+                    end_time,
+                    top_n,
+                )?;
+
+                submit(&create_season, &*owner)?;
+            }
+            SeasonCommands::AddBlink { id, index } => {
+                let owner = creator()?;
+                let add_blink = add_season_blink_instr(&pool_config, &owner.pubkey(), id, index)?;
+
+                submit(&add_blink, &*owner)?;
+            }
+            SeasonCommands::Score { id, index, user } => {
+                let payer = payer_or(&creator)?;
+                let score = score_instr(&pool_config, &payer.pubkey(), id, index, user)?;
+
+                submit(&score, &*payer)?;
+            }
+            SeasonCommands::Payout { id } => {
+                let season = blink_accounts::fetch_season(&program.rpc(), &program.id(), id)?;
+                let payer = payer_or(&creator)?;
+                for rank in 0..season.leaderboard.len() as u8 {
+                    if season.paid & (1 << rank) == 0 {
+                        let payout = payout_season_instr(&pool_config, &payer.pubkey(), id, rank)?;
+
+                        submit(&payout, &*payer)?;
+                    }
+                }
+            }
+            SeasonCommands::Leaderboard { id } => {
+                query_season(&program, id)?;
+            }
+        },
+        BlinkCommands::Series(series_command) => match series_command {
+            SeriesCommands::Create {
+                id,
+                token_mint,
+                bonus,
+                end_time,
+                bonus_bps,
+                max_streak,
+            } => {
+                let bonus = parse_amount(&token_mint, &bonus)?;
+                let end_time = resolve_time(&rpc_client, slot_clock()?, &end_time)?;
+                let owner = creator()?;
+                let create_series = create_series_instr(
+                    &pool_config,
+                    &owner.pubkey(),
+                    id,
+                    token_mint,
+                    bonus,
+                    end_time,
+                    bonus_bps,
+                    max_streak,
+                )?;
+
+                submit(&create_series, &*owner)?;
+            }
+            SeriesCommands::Fund { id, amount } => {
+                let series = blink_accounts::fetch_series(&program.rpc(), &program.id(), id)?;
+                let amount = parse_amount(&series.token_mint, &amount)?;
+                let owner = creator()?;
+                let fund_series = fund_series_instr(&pool_config, &owner.pubkey(), id, amount)?;
+
+                submit(&fund_series, &*owner)?;
+            }
+            SeriesCommands::AddBlink { id, index } => {
+                let owner = creator()?;
+                let add_blink = add_series_blink_instr(&pool_config, &owner.pubkey(), id, index)?;
+
+                submit(&add_blink, &*owner)?;
+            }
+            SeriesCommands::Withdraw { id } => {
+                let owner = creator()?;
+                let withdraw_series = withdraw_series_instr(&pool_config, &owner.pubkey(), id)?;
+
+                submit(&withdraw_series, &*owner)?;
+            }
+            SeriesCommands::Show { id, user } => {
+                query_series(&program, id, user)?;
+            }
+        },
+        BlinkCommands::Profile { wallet } => {
+            query_profile(&program, wallet)?;
+        }
+        BlinkCommands::QueryGlobal => {
+            query_global(&program)?;
+        }
+        BlinkCommands::QueryBlink { index, lang } => {
+            query_blink(&program, index, lang)?;
+        }
+        BlinkCommands::MetadataBuild {
+            content,
+            localized,
+            out,
+        } => {
+            let data = build_metadata(content, &localized)?;
+            std::fs::write(&out, &data)?;
+            println!("{}", out);
+            println!("hash: {}", hex::encode(metadata_hash(&data)));
+        }
+        BlinkCommands::MetadataHash { source } => {
+            let data = load_metadata(&source)?;
+            parse_metadata(&data)?;
+            println!("{}", hex::encode(metadata_hash(&data)));
+        }
+        BlinkCommands::MetadataVerify { index, source } => {
+            verify_metadata(&program, index, source)?;
         }
         BlinkCommands::DecodeInstruction { instr_hex_data } => {
             handle_program_instruction(&instr_hex_data, InstructionDecodeType::BaseHex)?;
@@ -272,15 +893,29 @@ fn main() -> Result<()> {
             };
             // get encoded_transaction
             let encoded_transaction = transaction.transaction;
-            // decode instruction data
+            // decode instruction data, and events emitted through self-CPI
             parse_program_instruction(
                 &pool_config.blink_program.to_string(),
                 encoded_transaction,
                 meta.clone(),
             )?;
-            // decode logs
+            // decode events logged by transactions from before `emit_cpi!`
             parse_program_event(&pool_config.blink_program.to_string(), meta.clone())?;
         }
+        BlinkCommands::Sign {
+            transaction,
+            signer,
+        } => {
+            let mut txn = decode_txn(&transaction)?;
+            let signer = signer_from_uri(&signer)?;
+            txn.try_partial_sign(&[&*signer], txn.message.recent_blockhash)?;
+            print_txn(&pool_config.blink_program, &txn)?;
+        }
+        BlinkCommands::Broadcast { transaction } => {
+            let txn = decode_txn(&transaction)?;
+            let signature = sender.broadcast(&txn).map_err(explain_error)?;
+            println!("{}", signature);
+        }
     }
     Ok(())
 }
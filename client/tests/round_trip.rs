@@ -0,0 +1,265 @@
+//! Transactions passed between signers, against a local
+//! `solana-test-validator` running the built program. Needs the validator on
+//! `PATH` and `target/deploy/blink.so` from `anchor build`:
+//!
+//!     cargo test -p client --test round_trip -- --ignored --test-threads 1
+use solana_client::{nonce_utils, rpc_client::RpcClient};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{write_keypair_file, Keypair, Signer},
+    system_instruction,
+    transaction::{uses_durable_nonce, Transaction},
+};
+
+use blink_sdk::accounts as blink_accounts;
+use client::instructions::rpc::decode_txn;
+use std::fs;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A validator with the blink program deployed, killed on drop.
+struct Validator {
+    process: Child,
+    dir: PathBuf,
+    rpc_client: RpcClient,
+    http_url: String,
+    ws_url: String,
+}
+
+impl Validator {
+    /// `upgrade_authority` may call `create_global`.
+    fn start(name: &str, upgrade_authority: &Pubkey) -> Validator {
+        let program = Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/deploy/blink.so");
+        assert!(
+            program.exists(),
+            "{} is missing, run `anchor build` first",
+            program.display()
+        );
+        let dir = std::env::temp_dir().join(format!("blink-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let (rpc_port, faucet_port) = (free_port(), free_port());
+        let process = Command::new("solana-test-validator")
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(dir.join("ledger"))
+            .args(["--rpc-port", &rpc_port.to_string()])
+            .args(["--faucet-port", &faucet_port.to_string()])
+            .arg("--upgradeable-program")
+            .arg(blink::ID.to_string())
+            .arg(&program)
+            .arg(upgrade_authority.to_string())
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("solana-test-validator not found");
+
+        let http_url = format!("http://127.0.0.1:{}", rpc_port);
+        let ws_url = format!("ws://127.0.0.1:{}", rpc_port + 1);
+        let rpc_client =
+            RpcClient::new_with_commitment(http_url.clone(), CommitmentConfig::confirmed());
+        let started = Instant::now();
+        while rpc_client.get_latest_blockhash().is_err() {
+            assert!(
+                started.elapsed() < Duration::from_secs(60),
+                "validator did not start"
+            );
+            thread::sleep(Duration::from_millis(500));
+        }
+        Validator {
+            process,
+            dir,
+            rpc_client,
+            http_url,
+            ws_url,
+        }
+    }
+
+    fn airdrop(&self, to: &Pubkey) {
+        let signature = self
+            .rpc_client
+            .request_airdrop(to, 10 * LAMPORTS_PER_SOL)
+            .unwrap();
+        while !self.rpc_client.confirm_transaction(&signature).unwrap() {
+            thread::sleep(Duration::from_millis(200));
+        }
+    }
+
+    /// Path of `keypair` written next to the ledger.
+    fn keypair_file(&self, name: &str, keypair: &Keypair) -> String {
+        let path = self.dir.join(format!("{}.json", name));
+        write_keypair_file(keypair, &path).unwrap();
+        path.to_string_lossy().into_owned()
+    }
+
+    /// Run the cli from a directory holding its `client_config.ini`, which
+    /// names only the `owner`, and return the last line it prints.
+    fn cli(&self, owner: &str, args: &[&str]) -> String {
+        fs::write(
+            self.dir.join("client_config.ini"),
+            format!(
+                "[Global]\nhttp_url = {url}\nws_url = {ws}\nowner_path = {owner}\n\
+                 creator_path =\nuser_path =\nblink_program = {program}\n",
+                url = self.http_url,
+                ws = self.ws_url,
+                owner = owner,
+                program = blink::ID,
+            ),
+        )
+        .unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_client"))
+            .current_dir(&self.dir)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "client {:?} failed:\n{}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .last()
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
+impl Drop for Validator {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// The owner signs where the command runs, the sponsor paying the fee signs
+/// the printed transaction elsewhere, then anyone broadcasts it.
+#[test]
+#[ignore = "needs solana-test-validator and an `anchor build`"]
+fn sign_only_then_sign_then_broadcast() {
+    let (owner, sponsor) = (Keypair::new(), Keypair::new());
+    let validator = Validator::start("sign-only", &owner.pubkey());
+    validator.airdrop(&owner.pubkey());
+    validator.airdrop(&sponsor.pubkey());
+    let owner_path = validator.keypair_file("owner", &owner);
+    let sponsor_path = validator.keypair_file("sponsor", &sponsor);
+    let sponsor_pubkey = sponsor.pubkey().to_string();
+
+    let unsigned = validator.cli(
+        &owner_path,
+        &[
+            "create-global",
+            "--fee-payer",
+            &sponsor_pubkey,
+            "--sign-only",
+        ],
+    );
+    assert!(
+        validator
+            .rpc_client
+            .get_account(&blink_sdk::pda::global_config(&blink::ID))
+            .is_err(),
+        "--sign-only must not send"
+    );
+
+    let signed = validator.cli(&owner_path, &["sign", &unsigned, &sponsor_path]);
+    let sponsor_before = validator.rpc_client.get_balance(&sponsor.pubkey()).unwrap();
+    validator.cli(&owner_path, &["broadcast", &signed]);
+
+    let global_config =
+        blink_accounts::fetch_global_config(&validator.rpc_client, &blink::ID).unwrap();
+    assert_eq!(global_config.admin, owner.pubkey());
+    // the sponsor paid the fee and nothing else
+    let fee = validator
+        .rpc_client
+        .get_fee_for_message(&decode_txn(&signed).unwrap().message)
+        .unwrap();
+    assert_eq!(
+        validator.rpc_client.get_balance(&sponsor.pubkey()).unwrap(),
+        sponsor_before - fee
+    );
+}
+
+/// Signed against a durable nonce, the transaction stays valid however long
+/// it takes to collect the signatures, and lands once.
+#[test]
+#[ignore = "needs solana-test-validator and an `anchor build`"]
+fn sign_only_with_durable_nonce() {
+    let (owner, sponsor, nonce) = (Keypair::new(), Keypair::new(), Keypair::new());
+    let validator = Validator::start("nonce", &owner.pubkey());
+    validator.airdrop(&owner.pubkey());
+    validator.airdrop(&sponsor.pubkey());
+    let owner_path = validator.keypair_file("owner", &owner);
+    let sponsor_path = validator.keypair_file("sponsor", &sponsor);
+    let sponsor_pubkey = sponsor.pubkey().to_string();
+    validator.cli(&owner_path, &["create-global"]);
+
+    // a nonce account the sponsor advances
+    let rent = validator
+        .rpc_client
+        .get_minimum_balance_for_rent_exemption(solana_sdk::nonce::State::size())
+        .unwrap();
+    let create_nonce = system_instruction::create_nonce_account(
+        &sponsor.pubkey(),
+        &nonce.pubkey(),
+        &sponsor.pubkey(),
+        rent,
+    );
+    let txn = Transaction::new_signed_with_payer(
+        &create_nonce,
+        Some(&sponsor.pubkey()),
+        &[&sponsor, &nonce],
+        validator.rpc_client.get_latest_blockhash().unwrap(),
+    );
+    validator
+        .rpc_client
+        .send_and_confirm_transaction(&txn)
+        .unwrap();
+    let nonce_pubkey = nonce.pubkey().to_string();
+
+    let unsigned = validator.cli(
+        &owner_path,
+        &[
+            "pause",
+            "--fee-payer",
+            &sponsor_pubkey,
+            "--nonce",
+            &nonce_pubkey,
+            "--nonce-authority",
+            &sponsor_pubkey,
+            "--sign-only",
+        ],
+    );
+    let signed = validator.cli(&owner_path, &["sign", &unsigned, &sponsor_path]);
+    validator.cli(&owner_path, &["broadcast", &signed]);
+
+    let global_config =
+        blink_accounts::fetch_global_config(&validator.rpc_client, &blink::ID).unwrap();
+    assert!(global_config.paused);
+
+    // signed against the nonce, which landing advanced so it cannot land again
+    let signed = decode_txn(&signed).unwrap();
+    let account = nonce_utils::get_account(&validator.rpc_client, &nonce.pubkey()).unwrap();
+    let durable_nonce = nonce_utils::data_from_account(&account)
+        .unwrap()
+        .blockhash();
+    assert_ne!(durable_nonce, signed.message.recent_blockhash);
+    assert!(uses_durable_nonce(&signed).is_some());
+}
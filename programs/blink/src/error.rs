@@ -22,4 +22,36 @@ pub enum ErrorCode {
     InvalidOpenTime,
     #[msg("Invalid CloseTime")]
     InvalidCloseTime,
+    #[msg("Extension Too Long")]
+    ExtensionTooLong,
+    #[msg("Notice Too Short")]
+    NoticeTooShort,
+    #[msg("Invalid Pending Admin")]
+    InvalidPendingAdmin,
+    #[msg("Invalid Resolver")]
+    InvalidResolver,
+    #[msg("Paused")]
+    Paused,
+    #[msg("Invalid Version")]
+    InvalidVersion,
+    #[msg("Content Too Long")]
+    ContentTooLong,
+    #[msg("Invalid Uri")]
+    InvalidUri,
+    #[msg("Invalid Lang")]
+    InvalidLang,
+    #[msg("Season Full")]
+    SeasonFull,
+    #[msg("Not In Season")]
+    NotInSeason,
+    #[msg("Scored Already")]
+    ScoredAlready,
+    #[msg("Invalid Rank")]
+    InvalidRank,
+    #[msg("Series Full")]
+    SeriesFull,
+    #[msg("Invalid Series")]
+    InvalidSeries,
+    #[msg("Invalid Upgrade Authority")]
+    InvalidUpgradeAuthority,
 }
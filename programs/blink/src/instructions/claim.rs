@@ -1,14 +1,20 @@
 use crate::error::ErrorCode;
+use crate::instructions::advance_streak;
 use crate::state::*;
+use crate::utils::{get_transfer_fee, transfer_from_pool_vault_to_user};
 
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-pub fn claim(ctx: Context<Claim>, index: u16) -> Result<()> {
+pub fn claim<'info>(ctx: Context<'_, '_, 'info, 'info, Claim<'info>>, index: u64) -> Result<()> {
     let blink_state = &ctx.accounts.blink_state.load()?;
+    if ctx.accounts.global_config.paused || blink_state.frozen {
+        return err!(ErrorCode::Paused);
+    }
+
     if !blink_state.closed {
         return err!(ErrorCode::Opening);
     }
@@ -35,38 +41,107 @@ pub fn claim(ctx: Context<Claim>, index: u16) -> Result<()> {
 
     submit_state.claim = true;
 
-    let signer_seeds: [&[&[u8]]; 1] = [&[crate::AUTH_SEED.as_bytes(), &[blink_state.auth_bump]]];
-
-    let transfer_accounts = TransferChecked {
-        from: ctx.accounts.vault.to_account_info(),
-        mint: ctx.accounts.token_mint.to_account_info(),
-        to: ctx.accounts.user_account.to_account_info(),
-        authority: ctx.accounts.authority.to_account_info(),
+    let user = ctx.accounts.user.key();
+    let (bonus, transfer_accounts) = match (
+        &mut ctx.accounts.series,
+        &mut ctx.accounts.streak,
+        &ctx.accounts.series_vault,
+    ) {
+        (Some(series), Some(streak), Some(series_vault)) => {
+            if series_vault.key() != series.vault {
+                return err!(ErrorCode::InvalidSeries);
+            }
+            let (bonus, transfer_accounts) =
+                advance_streak(series, streak, &user, index, reward, ctx.remaining_accounts)?;
+            emit_cpi!(StreakEvent {
+                id: series.id,
+                index,
+                user,
+                streak: streak.streak,
+                bonus
+            });
+            (bonus, transfer_accounts)
+        }
+        (None, None, None) => (0, ctx.remaining_accounts),
+        _ => return err!(ErrorCode::InvalidSeries),
     };
+    let amount = reward.checked_add(bonus).unwrap();
 
-    let cpi_context = CpiContext::new_with_signer(
+    let signer_seeds: [&[&[u8]]; 1] = [&[crate::AUTH_SEED.as_bytes(), &[blink_state.auth_bump]]];
+
+    // the mint withholds its transfer fee from what the user receives, on
+    // each of the two transfers
+    let token_mint = ctx.accounts.token_mint.to_account_info();
+    let fee = get_transfer_fee(&token_mint, reward)?
+        .checked_add(get_transfer_fee(&token_mint, bonus)?)
+        .unwrap();
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.user_account.to_account_info(),
+        token_mint.clone(),
         ctx.accounts.token_program.to_account_info(),
         transfer_accounts,
+        reward,
+        ctx.accounts.token_mint.decimals,
         &signer_seeds,
-    );
+    )?;
+    // the bonus comes out of the series' own vault, which the series signs for
+    if let (Some(series), Some(series_vault)) = (&ctx.accounts.series, &ctx.accounts.series_vault) {
+        let id = series.id.to_le_bytes();
+        let series_seeds: [&[&[u8]]; 1] = [&[SERIES_SEED.as_bytes(), &id, &[series.bump]]];
+        transfer_from_pool_vault_to_user(
+            series.to_account_info(),
+            series_vault.to_account_info(),
+            ctx.accounts.user_account.to_account_info(),
+            token_mint,
+            ctx.accounts.token_program.to_account_info(),
+            transfer_accounts,
+            bonus,
+            ctx.accounts.token_mint.decimals,
+            &series_seeds,
+        )?;
+    }
 
-    transfer_checked(cpi_context, reward, ctx.accounts.token_mint.decimals)?;
+    if let Some(user_profile) = &mut ctx.accounts.user_profile {
+        user_profile.correct = user_profile.correct.checked_add(1).unwrap();
+        // what reached the user, a transfer fee never does
+        user_profile.rewards = user_profile
+            .rewards
+            .checked_add(amount.checked_sub(fee).unwrap())
+            .unwrap();
+        user_profile.last_index = index;
+    }
 
-    emit!(ClaimEvent {
+    emit_cpi!(ClaimEvent {
         index,
-        user: ctx.accounts.user.key(),
-        reward
+        user,
+        reward,
+        bonus,
+        fee
     });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(index: u16)]
+#[instruction(index: u64)]
 pub struct Claim<'info> {
-    #[account(mut)]
     pub user: Signer<'info>,
 
+    /// Pays for the user's token account if needed. The user or a sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     #[account(
         mut,
         seeds = [
@@ -99,7 +174,7 @@ pub struct Claim<'info> {
 
     #[account(
         init_if_needed,
-        payer = user,
+        payer = payer,
         associated_token::mint = token_mint,
         associated_token::authority = user,
         associated_token::token_program = token_program
@@ -123,4 +198,43 @@ pub struct Claim<'info> {
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [
+            PROFILE_SEED.as_bytes(),
+            user.key().as_ref(),
+        ],
+        bump = user_profile.bump,
+    )]
+    pub user_profile: Option<Account<'info, UserProfile>>,
+
+    /// Series the blink belongs to, passed together with `streak` and
+    /// `series_vault` to earn the streak bonus. The remaining accounts then
+    /// start with the pairs `advance_streak` reads
+    #[account(
+        mut,
+        seeds = [
+            SERIES_SEED.as_bytes(),
+            &series.id.to_le_bytes().as_ref(),
+        ],
+        bump = series.bump,
+        has_one = token_mint @ ErrorCode::InvalidSeries,
+    )]
+    pub series: Option<Box<Account<'info, Series>>>,
+
+    #[account(
+        mut,
+        seeds = [
+            STREAK_SEED.as_bytes(),
+            &streak.series.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+        ],
+        bump = streak.bump,
+    )]
+    pub streak: Option<Box<Account<'info, Streak>>>,
+
+    /// The series' vault the bonus is paid from
+    #[account(mut)]
+    pub series_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 }
@@ -1,16 +1,32 @@
 use crate::error::ErrorCode;
 use crate::state::*;
+use crate::utils::current_time;
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::clock;
 
-pub fn close(ctx: Context<Close>, index: u16, answer: u8) -> Result<()> {
+pub fn close(ctx: Context<Close>, index: u64, answer: u8) -> Result<()> {
     let blink_state = &mut ctx.accounts.blink_state.load_mut()?;
+    if ctx.accounts.global_config.paused || blink_state.frozen {
+        return err!(ErrorCode::Paused);
+    }
 
-    let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
-    if block_timestamp < blink_state.close_time {
+    let now = current_time()?;
+    if now < blink_state.close_time {
         return err!(ErrorCode::InvalidCloseTime);
     }
 
+    // the creator resolves, the resolver role only once the grace period is over
+    let payer = ctx.accounts.payer.key();
+    if payer != blink_state.creator
+        && (payer != ctx.accounts.global_config.resolver
+            || now
+                < blink_state
+                    .close_time
+                    .checked_add(RESOLVE_GRACE_PERIOD)
+                    .unwrap())
+    {
+        return err!(ErrorCode::InvalidResolver);
+    }
+
     if blink_state.index != index {
         return err!(ErrorCode::InvalidIndex);
     }
@@ -21,32 +37,41 @@ pub fn close(ctx: Context<Close>, index: u16, answer: u8) -> Result<()> {
     blink_state.closed = true;
     blink_state.answer = answer;
 
-    let mut rights = 0;
-
-    if 1 == answer {
-        rights = blink_state.right1;
-    } else if 2 == answer {
-        rights = blink_state.right2;
-    } else if 3 == answer {
-        rights = blink_state.right3;
-    } else if 4 == answer {
-        rights = blink_state.right4;
-    }
-
+    let rights = blink_state.rights(answer);
     if rights > 0 {
         blink_state.reward = blink_state.amount.checked_div(rights.into()).unwrap()
     }
 
-    emit!(CloseEvent { index });
+    emit_cpi!(CloseEvent {
+        index,
+        answer,
+        reward: blink_state.reward,
+        rights: [
+            blink_state.right1,
+            blink_state.right2,
+            blink_state.right3,
+            blink_state.right4
+        ],
+        timestamp: now
+    });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(index: u16)]
+#[instruction(index: u64)]
 pub struct Close<'info> {
     pub payer: Signer<'info>,
 
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     #[account(mut,
         seeds = [
             BLINK_SEED.as_bytes(),
@@ -0,0 +1,94 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::current_time;
+use anchor_lang::prelude::*;
+
+pub fn extend_deadline(ctx: Context<UpdateDeadline>, index: u64, close_time: u64) -> Result<()> {
+    let blink_state = &mut ctx.accounts.blink_state.load_mut()?;
+    if ctx.accounts.global_config.paused || blink_state.frozen {
+        return err!(ErrorCode::Paused);
+    }
+
+    let now = current_time()?;
+    if blink_state.closed {
+        return err!(ErrorCode::CloseAlready);
+    }
+    if blink_state.index != index {
+        return err!(ErrorCode::InvalidIndex);
+    }
+    // only a still running blink can be pushed later
+    if now >= blink_state.close_time || close_time <= blink_state.close_time {
+        return err!(ErrorCode::InvalidCloseTime);
+    }
+    if close_time - blink_state.open_time > MAX_BLINK_PERIOD {
+        return err!(ErrorCode::ExtensionTooLong);
+    }
+
+    let old_close_time = blink_state.close_time;
+    blink_state.close_time = close_time;
+
+    emit_cpi!(DeadlineExtendedEvent {
+        index,
+        old_close_time,
+        close_time
+    });
+
+    Ok(())
+}
+
+pub fn end_early(ctx: Context<UpdateDeadline>, index: u64, close_time: u64) -> Result<()> {
+    let blink_state = &mut ctx.accounts.blink_state.load_mut()?;
+    if ctx.accounts.global_config.paused || blink_state.frozen {
+        return err!(ErrorCode::Paused);
+    }
+
+    let now = current_time()?;
+    if blink_state.closed {
+        return err!(ErrorCode::CloseAlready);
+    }
+    if blink_state.index != index {
+        return err!(ErrorCode::InvalidIndex);
+    }
+    if close_time >= blink_state.close_time || close_time < blink_state.open_time {
+        return err!(ErrorCode::InvalidCloseTime);
+    }
+    if close_time < now.checked_add(MIN_CLOSE_NOTICE).unwrap() {
+        return err!(ErrorCode::NoticeTooShort);
+    }
+
+    let old_close_time = blink_state.close_time;
+    blink_state.close_time = close_time;
+
+    emit_cpi!(EndEarlyEvent {
+        index,
+        old_close_time,
+        close_time
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct UpdateDeadline<'info> {
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(mut,
+        seeds = [
+            BLINK_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+        ],
+        bump = blink_state.load()?.bump,
+        has_one = creator,
+    )]
+    pub blink_state: AccountLoader<'info, BlinkState>,
+}
@@ -0,0 +1,171 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::SLOT_CLOCK;
+
+use anchor_lang::prelude::*;
+use std::ops::DerefMut;
+
+pub fn create_global(ctx: Context<CreateGlobalConfig>) -> Result<()> {
+    let admin = ctx.accounts.admin.key();
+
+    let global_config = ctx.accounts.global_config.deref_mut();
+    global_config.admin = admin;
+    global_config.pending_admin = Pubkey::default();
+    global_config.time_manager = admin;
+    global_config.resolver = admin;
+    global_config.pause_guardian = admin;
+    global_config.paused = false;
+    global_config.slot_clock = SLOT_CLOCK;
+    global_config.bump = ctx.bumps.global_config;
+    global_config.version = GLOBAL_CONFIG_VERSION;
+
+    let blink_counter = ctx.accounts.blink_counter.deref_mut();
+    blink_counter.next_index = LEGACY_INDEX_END;
+    blink_counter.bump = ctx.bumps.blink_counter;
+    blink_counter.version = BLINK_COUNTER_VERSION;
+
+    Ok(())
+}
+
+pub fn propose_admin(ctx: Context<UpdateGlobalConfig>, admin: Pubkey) -> Result<()> {
+    let global_config = ctx.accounts.global_config.deref_mut();
+    global_config.pending_admin = admin;
+
+    emit_cpi!(AdminProposedEvent {
+        admin: global_config.admin,
+        pending_admin: admin
+    });
+
+    Ok(())
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let global_config = ctx.accounts.global_config.deref_mut();
+    let old = global_config.admin;
+    global_config.admin = global_config.pending_admin;
+    global_config.pending_admin = Pubkey::default();
+
+    emit_cpi!(RoleUpdatedEvent {
+        role: Role::Admin,
+        old,
+        new: global_config.admin
+    });
+
+    Ok(())
+}
+
+pub fn set_time_manager(ctx: Context<UpdateGlobalConfig>, time_manager: Pubkey) -> Result<()> {
+    let global_config = ctx.accounts.global_config.deref_mut();
+    let old = global_config.time_manager;
+    global_config.time_manager = time_manager;
+
+    emit_cpi!(RoleUpdatedEvent {
+        role: Role::TimeManager,
+        old,
+        new: time_manager
+    });
+
+    Ok(())
+}
+
+pub fn set_resolver(ctx: Context<UpdateGlobalConfig>, resolver: Pubkey) -> Result<()> {
+    let global_config = ctx.accounts.global_config.deref_mut();
+    let old = global_config.resolver;
+    global_config.resolver = resolver;
+
+    emit_cpi!(RoleUpdatedEvent {
+        role: Role::Resolver,
+        old,
+        new: resolver
+    });
+
+    Ok(())
+}
+
+pub fn set_pause_guardian(ctx: Context<UpdateGlobalConfig>, pause_guardian: Pubkey) -> Result<()> {
+    let global_config = ctx.accounts.global_config.deref_mut();
+    let old = global_config.pause_guardian;
+    global_config.pause_guardian = pause_guardian;
+
+    emit_cpi!(RoleUpdatedEvent {
+        role: Role::PauseGuardian,
+        old,
+        new: pause_guardian
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateGlobalConfig<'info> {
+    /// Has to be the program's upgrade authority, who becomes the admin
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        constraint = blink_program.programdata_address()? == Some(program_data.key()),
+    )]
+    pub blink_program: Program<'info, crate::program::Blink>,
+
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key())
+            @ ErrorCode::InvalidUpgradeAuthority,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init,
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump,
+        payer = admin,
+        space = ANCHOR_DISCRIMINATOR + GlobalConfig::INIT_SPACE
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        seeds = [
+            COUNTER_SEED.as_bytes(),
+        ],
+        bump,
+        payer = admin,
+        space = ANCHOR_DISCRIMINATOR + BlinkCounter::INIT_SPACE
+    )]
+    pub blink_counter: Account<'info, BlinkCounter>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateGlobalConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+        has_one = admin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+        has_one = pending_admin @ ErrorCode::InvalidPendingAdmin,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
@@ -1,17 +1,18 @@
+use crate::error::ErrorCode;
 use crate::state::*;
+use crate::utils::{realloc_account, transfer_from_user_to_pool_vault};
 
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 use std::ops::DerefMut;
 
 #[allow(clippy::too_many_arguments)]
-pub fn initialize(
-    ctx: Context<Initialize>,
-    index: u16,
+pub fn initialize<'info>(
+    ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
     amount: u64,
     pic: String,
     content: String,
@@ -20,30 +21,82 @@ pub fn initialize(
     option3: String,
     option4: String,
 ) -> Result<()> {
-    let transfer_accounts = TransferChecked {
-        from: ctx.accounts.creator_token.to_account_info(),
-        mint: ctx.accounts.token_mint.to_account_info(),
-        to: ctx.accounts.vault.to_account_info(),
-        authority: ctx.accounts.creator.to_account_info(),
-    };
-
-    let cpi_context = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        transfer_accounts,
-    );
+    if !BlinkConfig::check_len(&pic, &content, [&option1, &option2, &option3, &option4]) {
+        return err!(ErrorCode::ContentTooLong);
+    }
+    create_blink(ctx, amount, |blink_config| {
+        blink_config.pic = pic;
+        blink_config.content = content;
+        blink_config.option1 = option1;
+        blink_config.option2 = option2;
+        blink_config.option3 = option3;
+        blink_config.option4 = option4;
+    })
+}
+
+/// Same as `initialize`, but the question and options live in a JSON
+/// document at `uri` whose sha256 is `metadata_hash`.
+pub fn initialize_with_metadata<'info>(
+    ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
+    amount: u64,
+    uri: String,
+    metadata_hash: [u8; 32],
+) -> Result<()> {
+    if uri.is_empty() {
+        return err!(ErrorCode::InvalidUri);
+    }
+    if uri.len() > MAX_URI_LEN {
+        return err!(ErrorCode::ContentTooLong);
+    }
+    create_blink(ctx, amount, |blink_config| {
+        blink_config.uri = uri;
+        blink_config.metadata_hash = metadata_hash;
+    })
+}
 
-    transfer_checked(cpi_context, amount, ctx.accounts.token_mint.decimals)?;
+fn create_blink<'info>(
+    ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
+    amount: u64,
+    set_content: impl FnOnce(&mut BlinkConfig),
+) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+
+    let blink_counter = ctx.accounts.blink_counter.deref_mut();
+    let index = blink_counter.next_index;
+    blink_counter.next_index = index.checked_add(1).unwrap();
+
+    // the pot is what the vault actually received, after any transfer fee
+    let vault_before = ctx.accounts.vault.amount;
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.creator_token.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+    ctx.accounts.vault.reload()?;
+    let amount = ctx.accounts.vault.amount.checked_sub(vault_before).unwrap();
 
     let blink_config = ctx.accounts.blink_config.deref_mut();
     blink_config.creator = ctx.accounts.creator.key();
     blink_config.index = index;
-    blink_config.pic = pic;
-    blink_config.content = content;
-    blink_config.option1 = option1;
-    blink_config.option2 = option2;
-    blink_config.option3 = option3;
-    blink_config.option4 = option4;
+    set_content(blink_config);
     blink_config.bump = ctx.bumps.blink_config;
+    blink_config.version = BLINK_CONFIG_VERSION;
+    let content_hash = blink_config.content_hash();
+    let space = ANCHOR_DISCRIMINATOR + blink_config.size();
+    // blink_config is created empty, grow it to fit the content
+    realloc_account(
+        &ctx.accounts.blink_config.to_account_info(),
+        &ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        space,
+    )?;
 
     let blink_state = &mut ctx.accounts.blink_state.load_init()?;
     blink_state.index = index;
@@ -52,6 +105,7 @@ pub fn initialize(
     blink_state.vault = ctx.accounts.vault.key();
     blink_state.token_mint = ctx.accounts.token_mint.key();
     blink_state.closed = false;
+    blink_state.frozen = false;
     blink_state.answer = 0;
     blink_state.right1 = 0;
     blink_state.right2 = 0;
@@ -64,21 +118,25 @@ pub fn initialize(
 
     blink_state.auth_bump = ctx.bumps.authority;
     blink_state.bump = ctx.bumps.blink_state;
+    blink_state.version = BLINK_STATE_VERSION;
 
-    emit!(InitializeEvent {
+    emit_cpi!(InitializeEvent {
         index,
         creator: ctx.accounts.creator.key(),
         valut: ctx.accounts.vault.key(),
         token_mint: ctx.accounts.token_mint.key(),
         config: ctx.accounts.blink_config.key(),
-        amount
+        amount,
+        open_time: blink_state.open_time,
+        close_time: blink_state.close_time,
+        content_hash
     });
 
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(index: u16)]
 pub struct Initialize<'info> {
     /// Address paying to create the pool. Can be anyone
     #[account(mut)]
@@ -95,15 +153,32 @@ pub struct Initialize<'info> {
 
     pub time_config: Account<'info, TimeConfig>,
 
+    #[account(
+        mut,
+        seeds = [
+            COUNTER_SEED.as_bytes(),
+        ],
+        bump = blink_counter.bump,
+    )]
+    pub blink_counter: Account<'info, BlinkCounter>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     #[account(
         init,
         seeds = [
             CONFIG_SEED.as_bytes(),
-            &index.to_le_bytes().as_ref(),
+            &blink_counter.next_index.to_le_bytes().as_ref(),
         ],
         bump,
         payer = creator,
-        space = ANCHOR_DISCRIMINATOR + BlinkConfig::INIT_SPACE
+        space = ANCHOR_DISCRIMINATOR + BlinkConfig::space("", "", ["", "", "", ""], "")
     )]
     pub blink_config: Account<'info, BlinkConfig>,
 
@@ -111,7 +186,7 @@ pub struct Initialize<'info> {
         init,
         seeds = [
             BLINK_SEED.as_bytes(),
-            &index.to_le_bytes().as_ref(),
+            &blink_counter.next_index.to_le_bytes().as_ref(),
         ],
         bump,
         payer = creator,
@@ -0,0 +1,325 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::{close_account, from_unix_timestamp, realloc_account};
+
+use anchor_lang::prelude::*;
+use anchor_lang::{Discriminator, Space};
+use std::ops::DerefMut;
+
+/// Rewrite the legacy time config in place, growing it to the current layout.
+pub fn migrate_time_config(ctx: Context<MigrateTimeConfig>) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let account = ctx.accounts.time_config.to_account_info();
+    let legacy = read_legacy::<TimeConfig, LegacyTimeConfig>(&account)?;
+
+    realloc_account(
+        &account,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        ANCHOR_DISCRIMINATOR + TimeConfig::INIT_SPACE,
+    )?;
+    let time_config = TimeConfig {
+        open_time: from_unix_timestamp(legacy.open_time)?,
+        close_time: from_unix_timestamp(legacy.close_time)?,
+        bump: legacy.bump,
+        version: TIME_CONFIG_VERSION,
+        reserved: [0; RESERVED_SPACE],
+    };
+    let mut data = account.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    time_config.try_serialize(&mut writer)?;
+
+    emit_cpi!(MigrateEvent {
+        account: account.key(),
+        from_version: 0,
+        to_version: TIME_CONFIG_VERSION
+    });
+
+    Ok(())
+}
+
+/// Move a blink stored under its `u16` index, config and state together, to
+/// the accounts of the same `u64` index.
+pub fn migrate_blink_state(ctx: Context<MigrateBlinkState>, index: u16) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let legacy_config = read_legacy::<BlinkConfig, LegacyBlinkConfig>(
+        &ctx.accounts.legacy_blink_config.to_account_info(),
+    )?;
+    let legacy_state = read_legacy::<BlinkState, LegacyBlinkState>(
+        &ctx.accounts.legacy_blink_state.to_account_info(),
+    )?;
+    if legacy_config.index != index || legacy_state.index != index {
+        return err!(ErrorCode::InvalidIndex);
+    }
+
+    let blink_config = ctx.accounts.blink_config.deref_mut();
+    blink_config.index = index.into();
+    blink_config.creator = legacy_config.creator;
+    blink_config.pic = legacy_config.pic;
+    blink_config.content = legacy_config.content;
+    blink_config.option1 = legacy_config.option1;
+    blink_config.option2 = legacy_config.option2;
+    blink_config.option3 = legacy_config.option3;
+    blink_config.option4 = legacy_config.option4;
+    blink_config.bump = ctx.bumps.blink_config;
+    blink_config.version = BLINK_CONFIG_VERSION;
+    let space = ANCHOR_DISCRIMINATOR + blink_config.size();
+    realloc_account(
+        &ctx.accounts.blink_config.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        space,
+    )?;
+
+    let blink_state = &mut ctx.accounts.blink_state.load_init()?;
+    blink_state.index = index.into();
+    blink_state.creator = legacy_state.creator;
+    blink_state.blink_config = ctx.accounts.blink_config.key();
+    blink_state.vault = legacy_state.vault;
+    blink_state.token_mint = legacy_state.token_mint;
+    blink_state.right1 = legacy_state.right1;
+    blink_state.right2 = legacy_state.right2;
+    blink_state.right3 = legacy_state.right3;
+    blink_state.right4 = legacy_state.right4;
+    blink_state.amount = legacy_state.amount;
+    blink_state.closed = legacy_state.closed;
+    blink_state.frozen = false;
+    blink_state.answer = legacy_state.answer;
+    blink_state.reward = legacy_state.reward;
+    blink_state.open_time = from_unix_timestamp(legacy_state.open_time)?;
+    blink_state.close_time = from_unix_timestamp(legacy_state.close_time)?;
+    blink_state.auth_bump = legacy_state.auth_bump;
+    blink_state.bump = ctx.bumps.blink_state;
+    blink_state.version = BLINK_STATE_VERSION;
+
+    close_account(
+        &ctx.accounts.legacy_blink_config.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+    )?;
+    close_account(
+        &ctx.accounts.legacy_blink_state.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+    )?;
+
+    emit_cpi!(MigrateEvent {
+        account: ctx.accounts.blink_config.key(),
+        from_version: 0,
+        to_version: BLINK_CONFIG_VERSION
+    });
+    emit_cpi!(MigrateEvent {
+        account: ctx.accounts.blink_state.key(),
+        from_version: 0,
+        to_version: BLINK_STATE_VERSION
+    });
+
+    Ok(())
+}
+
+/// Move a submission stored under its blink's `u16` index to the account of
+/// the same `u64` index. The blink has to be migrated first.
+pub fn migrate_submit_state(
+    ctx: Context<MigrateSubmitState>,
+    index: u16,
+    user: Pubkey,
+) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let legacy = read_legacy::<SubmitState, LegacySubmitState>(
+        &ctx.accounts.legacy_submit_state.to_account_info(),
+    )?;
+    if legacy.index != index || legacy.user != user {
+        return err!(ErrorCode::InvalidIndex);
+    }
+
+    let submit_state = &mut ctx.accounts.submit_state.load_init()?;
+    submit_state.index = index.into();
+    submit_state.blink_state = ctx.accounts.blink_state.key();
+    submit_state.user = user;
+    submit_state.answer = legacy.answer;
+    submit_state.claim = legacy.claim;
+    submit_state.bump = ctx.bumps.submit_state;
+    submit_state.version = SUBMIT_STATE_VERSION;
+
+    close_account(
+        &ctx.accounts.legacy_submit_state.to_account_info(),
+        &ctx.accounts.payer.to_account_info(),
+    )?;
+
+    emit_cpi!(MigrateEvent {
+        account: ctx.accounts.submit_state.key(),
+        from_version: 0,
+        to_version: SUBMIT_STATE_VERSION
+    });
+
+    Ok(())
+}
+
+/// Decode `account` as the legacy layout `L` of `T`, checking it carries the
+/// discriminator of `T` and has exactly the legacy size.
+fn read_legacy<T: Discriminator, L: AnchorDeserialize + Space>(account: &AccountInfo) -> Result<L> {
+    let data = account.try_borrow_data()?;
+    if data.len() < ANCHOR_DISCRIMINATOR || data[..ANCHOR_DISCRIMINATOR] != T::DISCRIMINATOR {
+        return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+    if data.len() != ANCHOR_DISCRIMINATOR + L::INIT_SPACE {
+        return err!(ErrorCode::InvalidVersion);
+    }
+    Ok(L::deserialize(&mut &data[ANCHOR_DISCRIMINATOR..])?)
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateTimeConfig<'info> {
+    /// Pays the extra rent. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// CHECK: still holds the legacy layout, checked in read_legacy
+    #[account(
+        mut,
+        seeds = [
+            TIME_SEED.as_bytes(),
+        ],
+        bump,
+        owner = crate::ID @ ErrorCode::InvalidOwner,
+    )]
+    pub time_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(index: u16)]
+pub struct MigrateBlinkState<'info> {
+    /// Pays the rent of the new accounts and gets the legacy accounts' rent
+    /// back. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// CHECK: still holds the legacy layout, checked in read_legacy
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        owner = crate::ID @ ErrorCode::InvalidOwner,
+    )]
+    pub legacy_blink_config: UncheckedAccount<'info>,
+
+    /// CHECK: still holds the legacy layout, checked in read_legacy
+    #[account(
+        mut,
+        seeds = [
+            BLINK_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+        ],
+        bump,
+        owner = crate::ID @ ErrorCode::InvalidOwner,
+    )]
+    pub legacy_blink_state: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [
+            CONFIG_SEED.as_bytes(),
+            &u64::from(index).to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + BlinkConfig::space("", "", ["", "", "", ""], "")
+    )]
+    pub blink_config: Account<'info, BlinkConfig>,
+
+    #[account(
+        init,
+        seeds = [
+            BLINK_SEED.as_bytes(),
+            &u64::from(index).to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + BlinkState::INIT_SPACE
+    )]
+    pub blink_state: AccountLoader<'info, BlinkState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(index: u16, user: Pubkey)]
+pub struct MigrateSubmitState<'info> {
+    /// Pays the rent of the new account and gets the legacy account's rent
+    /// back. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    /// CHECK: still holds the legacy layout, checked in read_legacy
+    #[account(
+        mut,
+        seeds = [
+            SUBMIT_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+            user.as_ref(),
+        ],
+        bump,
+        owner = crate::ID @ ErrorCode::InvalidOwner,
+    )]
+    pub legacy_submit_state: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            BLINK_SEED.as_bytes(),
+            &u64::from(index).to_le_bytes().as_ref(),
+        ],
+        bump = blink_state.load()?.bump,
+    )]
+    pub blink_state: AccountLoader<'info, BlinkState>,
+
+    #[account(
+        init,
+        seeds = [
+            SUBMIT_SEED.as_bytes(),
+            &u64::from(index).to_le_bytes().as_ref(),
+            user.as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + SubmitState::INIT_SPACE
+    )]
+    pub submit_state: AccountLoader<'info, SubmitState>,
+
+    pub system_program: Program<'info, System>,
+}
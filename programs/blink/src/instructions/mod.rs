@@ -1,9 +1,24 @@
+pub mod global_config;
+pub use global_config::*;
+
+pub mod pause;
+pub use pause::*;
+
 pub mod time_config;
 pub use time_config::*;
 
 pub mod initialize;
 pub use initialize::*;
 
+pub mod update_config;
+pub use update_config::*;
+
+pub mod translation;
+pub use translation::*;
+
+pub mod profile;
+pub use profile::*;
+
 pub mod submit;
 pub use submit::*;
 
@@ -12,3 +27,15 @@ pub use close::*;
 
 pub mod claim;
 pub use claim::*;
+
+pub mod season;
+pub use season::*;
+
+pub mod series;
+pub use series::*;
+
+pub mod deadline;
+pub use deadline::*;
+
+pub mod migrate;
+pub use migrate::*;
@@ -0,0 +1,67 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+
+use anchor_lang::prelude::*;
+use std::ops::DerefMut;
+
+pub fn set_paused(ctx: Context<UpdatePause>, paused: bool) -> Result<()> {
+    let global_config = ctx.accounts.global_config.deref_mut();
+    global_config.paused = paused;
+
+    emit_cpi!(PauseEvent { paused });
+
+    Ok(())
+}
+
+pub fn freeze_blink(ctx: Context<FreezeBlink>, index: u64, frozen: bool) -> Result<()> {
+    let blink_state = &mut ctx.accounts.blink_state.load_mut()?;
+    if blink_state.index != index {
+        return err!(ErrorCode::InvalidIndex);
+    }
+    blink_state.frozen = frozen;
+
+    emit_cpi!(FreezeEvent { index, frozen });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdatePause<'info> {
+    pub pause_guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+        has_one = pause_guardian,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct FreezeBlink<'info> {
+    pub pause_guardian: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+        has_one = pause_guardian,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(mut,
+        seeds = [
+            BLINK_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+        ],
+        bump = blink_state.load()?.bump,
+    )]
+    pub blink_state: AccountLoader<'info, BlinkState>,
+}
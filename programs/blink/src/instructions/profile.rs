@@ -0,0 +1,57 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+
+use anchor_lang::prelude::*;
+use std::ops::DerefMut;
+
+pub fn create_profile(ctx: Context<CreateProfile>) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let user_profile = ctx.accounts.user_profile.deref_mut();
+    user_profile.user = ctx.accounts.user.key();
+    user_profile.submissions = 0;
+    user_profile.correct = 0;
+    user_profile.rewards = 0;
+    user_profile.last_index = 0;
+    user_profile.bump = ctx.bumps.user_profile;
+    user_profile.version = USER_PROFILE_VERSION;
+
+    emit_cpi!(ProfileCreatedEvent {
+        user: user_profile.user
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateProfile<'info> {
+    pub user: Signer<'info>,
+
+    /// Pays for the new profile account. The user or a sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        seeds = [
+            PROFILE_SEED.as_bytes(),
+            user.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + UserProfile::INIT_SPACE
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+
+    pub system_program: Program<'info, System>,
+}
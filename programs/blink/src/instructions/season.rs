@@ -0,0 +1,429 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::{
+    current_time, transfer_from_pool_vault_to_user, transfer_from_user_to_pool_vault,
+};
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use std::ops::DerefMut;
+
+pub fn create_season<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateSeason<'info>>,
+    id: u64,
+    end_time: u64,
+    top_n: u8,
+    prize: u64,
+) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    if top_n == 0 || top_n as usize > MAX_LEADERBOARD {
+        return err!(ErrorCode::InvalidRank);
+    }
+    if end_time <= current_time()? {
+        return err!(ErrorCode::InvalidCloseTime);
+    }
+
+    // the prize is what the vault actually received, after any transfer fee
+    let vault_before = ctx.accounts.vault.amount;
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.owner.to_account_info(),
+        ctx.accounts.owner_token.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.remaining_accounts,
+        prize,
+        ctx.accounts.token_mint.decimals,
+    )?;
+    ctx.accounts.vault.reload()?;
+    let prize = ctx.accounts.vault.amount.checked_sub(vault_before).unwrap();
+
+    let season = ctx.accounts.season.deref_mut();
+    season.id = id;
+    season.owner = ctx.accounts.owner.key();
+    season.token_mint = ctx.accounts.token_mint.key();
+    season.vault = ctx.accounts.vault.key();
+    season.prize = prize;
+    season.end_time = end_time;
+    season.top_n = top_n;
+    season.paid = 0;
+    season.blinks = Vec::new();
+    season.leaderboard = Vec::new();
+    season.bump = ctx.bumps.season;
+    season.version = SEASON_VERSION;
+
+    emit_cpi!(SeasonCreatedEvent {
+        id,
+        owner: season.owner,
+        token_mint: season.token_mint,
+        prize,
+        end_time,
+        top_n
+    });
+
+    Ok(())
+}
+
+pub fn add_season_blink(ctx: Context<AddSeasonBlink>, id: u64, index: u64) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let season = ctx.accounts.season.deref_mut();
+    if current_time()? >= season.end_time {
+        return err!(ErrorCode::InvalidCloseTime);
+    }
+    if season.blinks.contains(&index) {
+        return err!(ErrorCode::InvalidIndex);
+    }
+    if season.blinks.len() >= MAX_SEASON_BLINKS {
+        return err!(ErrorCode::SeasonFull);
+    }
+    season.blinks.push(index);
+
+    emit_cpi!(SeasonBlinkAddedEvent { id, index });
+
+    Ok(())
+}
+
+/// Permissionless: count `user`'s correct answer on blink `index` towards the
+/// season. Scores are final once the season ends.
+pub fn score(ctx: Context<Score>, id: u64, index: u64, user: Pubkey) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let season = ctx.accounts.season.deref_mut();
+    if current_time()? >= season.end_time {
+        return err!(ErrorCode::InvalidCloseTime);
+    }
+    if !season.blinks.contains(&index) {
+        return err!(ErrorCode::NotInSeason);
+    }
+
+    {
+        let blink_state = &ctx.accounts.blink_state.load()?;
+        let submit_state = &ctx.accounts.submit_state.load()?;
+        if !blink_state.closed {
+            return err!(ErrorCode::Opening);
+        }
+        if blink_state.answer != submit_state.answer {
+            return err!(ErrorCode::InvalidClaim);
+        }
+    }
+
+    let score_marker = ctx.accounts.score_marker.deref_mut();
+    if score_marker.version != 0 {
+        return err!(ErrorCode::ScoredAlready);
+    }
+    score_marker.season = id;
+    score_marker.index = index;
+    score_marker.user = user;
+    score_marker.bump = ctx.bumps.score_marker;
+    score_marker.version = SCORE_MARKER_VERSION;
+
+    let season_score = ctx.accounts.season_score.deref_mut();
+    if season_score.version == 0 {
+        season_score.season = id;
+        season_score.user = user;
+        season_score.bump = ctx.bumps.season_score;
+        season_score.version = SEASON_SCORE_VERSION;
+    }
+    season_score.points = season_score.points.checked_add(1).unwrap();
+    season.update_leaderboard(user, season_score.points);
+
+    emit_cpi!(ScoreEvent {
+        id,
+        index,
+        user,
+        points: season_score.points
+    });
+
+    Ok(())
+}
+
+/// Permissionless crank: pay leaderboard entry `rank` its equal share of the
+/// prize once the season has ended.
+pub fn payout_season<'info>(
+    ctx: Context<'_, '_, '_, 'info, PayoutSeason<'info>>,
+    id: u64,
+    rank: u8,
+) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let season = ctx.accounts.season.deref_mut();
+    if current_time()? < season.end_time {
+        return err!(ErrorCode::InvalidCloseTime);
+    }
+    let entry = match season.leaderboard.get(rank as usize) {
+        Some(entry) => *entry,
+        None => return err!(ErrorCode::InvalidRank),
+    };
+    if entry.user != ctx.accounts.winner.key() {
+        return err!(ErrorCode::InvalidRank);
+    }
+    if season.paid & (1 << rank) != 0 {
+        return err!(ErrorCode::ClaimAlready);
+    }
+    season.paid |= 1 << rank;
+
+    let amount = season
+        .prize
+        .checked_div(season.leaderboard.len() as u64)
+        .unwrap();
+    if amount == 0 {
+        return err!(ErrorCode::RewardZero);
+    }
+
+    let signer_seeds: [&[&[u8]]; 1] = [&[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.authority]]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.winner_token.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.token_mint.decimals,
+        &signer_seeds,
+    )?;
+
+    emit_cpi!(SeasonPayoutEvent {
+        id,
+        rank,
+        user: entry.user,
+        amount
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateSeason<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        seeds = [
+            SEASON_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + Season::INIT_SPACE
+    )]
+    pub season: Account<'info, Season>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = token_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct AddSeasonBlink<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            SEASON_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+        ],
+        bump = season.bump,
+        has_one = owner,
+    )]
+    pub season: Account<'info, Season>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(id: u64, index: u64, user: Pubkey)]
+pub struct Score<'info> {
+    /// Pays for a new score account. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            SEASON_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+        ],
+        bump = season.bump,
+    )]
+    pub season: Account<'info, Season>,
+
+    #[account(
+        seeds = [
+            BLINK_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+        ],
+        bump = blink_state.load()?.bump,
+    )]
+    pub blink_state: AccountLoader<'info, BlinkState>,
+
+    #[account(
+        seeds = [
+            SUBMIT_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+            user.as_ref(),
+        ],
+        bump = submit_state.load()?.bump,
+    )]
+    pub submit_state: AccountLoader<'info, SubmitState>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            SCORE_MARKER_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+            &index.to_le_bytes().as_ref(),
+            user.as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + ScoreMarker::INIT_SPACE
+    )]
+    pub score_marker: Account<'info, ScoreMarker>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            SEASON_SCORE_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+            user.as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + SeasonScore::INIT_SPACE
+    )]
+    pub season_score: Account<'info, SeasonScore>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct PayoutSeason<'info> {
+    /// Pays for the winner's token account if needed. Can be anyone
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            SEASON_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+        ],
+        bump = season.bump,
+        has_one = token_mint,
+        has_one = vault,
+    )]
+    pub season: Account<'info, Season>,
+
+    /// CHECK: pool vault authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: checked against the leaderboard entry
+    pub winner: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = winner,
+        associated_token::token_program = token_program
+    )]
+    pub winner_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
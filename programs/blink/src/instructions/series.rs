@@ -0,0 +1,494 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::{
+    current_time, transfer_from_pool_vault_to_user, transfer_from_user_to_pool_vault,
+};
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use std::ops::DerefMut;
+
+pub fn create_series<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateSeries<'info>>,
+    id: u64,
+    end_time: u64,
+    bonus_bps: u16,
+    max_streak: u32,
+    bonus: u64,
+) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    if bonus_bps as u64 > BPS_DENOMINATOR || max_streak == 0 {
+        return err!(ErrorCode::InvalidSeries);
+    }
+    if end_time <= current_time()? {
+        return err!(ErrorCode::InvalidCloseTime);
+    }
+
+    let bonus = fund(
+        &ctx.accounts.owner,
+        &ctx.accounts.owner_token,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        bonus,
+    )?;
+
+    let series = ctx.accounts.series.deref_mut();
+    series.id = id;
+    series.owner = ctx.accounts.owner.key();
+    series.token_mint = ctx.accounts.token_mint.key();
+    series.vault = ctx.accounts.vault.key();
+    series.bonus = bonus;
+    series.bonus_bps = bonus_bps;
+    series.max_streak = max_streak;
+    series.end_time = end_time;
+    series.blinks = Vec::new();
+    series.bump = ctx.bumps.series;
+    series.version = SERIES_VERSION;
+
+    emit_cpi!(SeriesCreatedEvent {
+        id,
+        owner: series.owner,
+        token_mint: series.token_mint,
+        bonus,
+        bonus_bps,
+        max_streak,
+        end_time
+    });
+
+    Ok(())
+}
+
+pub fn fund_series<'info>(
+    ctx: Context<'_, '_, '_, 'info, FundSeries<'info>>,
+    id: u64,
+    amount: u64,
+) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let amount = fund(
+        &ctx.accounts.owner,
+        &ctx.accounts.owner_token,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        amount,
+    )?;
+    let series = ctx.accounts.series.deref_mut();
+    series.bonus = series.bonus.checked_add(amount).unwrap();
+
+    emit_cpi!(SeriesFundedEvent {
+        id,
+        amount,
+        bonus: series.bonus
+    });
+
+    Ok(())
+}
+
+/// Blinks count towards a streak in the order they are added.
+pub fn add_series_blink(ctx: Context<AddSeriesBlink>, id: u64, index: u64) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let series = ctx.accounts.series.deref_mut();
+    if current_time()? >= series.end_time {
+        return err!(ErrorCode::InvalidCloseTime);
+    }
+    if series.blinks.contains(&index) {
+        return err!(ErrorCode::InvalidIndex);
+    }
+    if series.blinks.len() >= MAX_SERIES_BLINKS {
+        return err!(ErrorCode::SeriesFull);
+    }
+    series.blinks.push(index);
+
+    emit_cpi!(SeriesBlinkAddedEvent { id, index });
+
+    Ok(())
+}
+
+/// Return what is left of the bonus funding to the owner once the series
+/// has ended, no claim can earn a bonus any more by then.
+pub fn withdraw_series<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawSeries<'info>>,
+    id: u64,
+) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let series = &ctx.accounts.series;
+    if current_time()? < series.end_time {
+        return err!(ErrorCode::InvalidCloseTime);
+    }
+
+    // all of it, tokens sent to the vault directly included
+    let amount = ctx.accounts.vault.amount;
+    let id_bytes = id.to_le_bytes();
+    let signer_seeds: [&[&[u8]]; 1] = [&[SERIES_SEED.as_bytes(), &id_bytes, &[series.bump]]];
+    transfer_from_pool_vault_to_user(
+        series.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+        ctx.accounts.owner_token.to_account_info(),
+        ctx.accounts.token_mint.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+        ctx.remaining_accounts,
+        amount,
+        ctx.accounts.token_mint.decimals,
+        &signer_seeds,
+    )?;
+    ctx.accounts.series.bonus = 0;
+
+    emit_cpi!(SeriesWithdrawnEvent { id, amount });
+
+    Ok(())
+}
+
+pub fn create_streak(ctx: Context<CreateStreak>, id: u64) -> Result<()> {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let streak = ctx.accounts.streak.deref_mut();
+    streak.series = id;
+    streak.user = ctx.accounts.user.key();
+    streak.streak = 0;
+    streak.last_position = 0;
+    streak.bump = ctx.bumps.streak;
+    streak.version = STREAK_VERSION;
+
+    emit_cpi!(StreakCreatedEvent {
+        id,
+        user: streak.user
+    });
+
+    Ok(())
+}
+
+/// Count a correct claim on blink `index` towards `user`'s streak and return
+/// the bonus it earns, taken out of the series funding, with the accounts
+/// left after the streak's. Claims after the series ended still count, but
+/// earn nothing.
+///
+/// The streak is the run of correct answers ending at `index`, whatever
+/// order they are claimed in. `previous` starts with the user's
+/// `(blink_state, submit_state)` pairs for the blinks right before it in the
+/// series, newest first; the run ends at the first pair missing or answered
+/// wrong.
+pub(crate) fn advance_streak<'info>(
+    series: &mut Series,
+    streak: &mut Streak,
+    user: &Pubkey,
+    index: u64,
+    reward: u64,
+    previous: &'info [AccountInfo<'info>],
+) -> Result<(u64, &'info [AccountInfo<'info>])> {
+    if streak.series != series.id || streak.user != *user {
+        return err!(ErrorCode::InvalidSeries);
+    }
+    let position = match series.blinks.iter().position(|blink| *blink == index) {
+        Some(position) => position,
+        None => return err!(ErrorCode::InvalidSeries),
+    };
+
+    // steps beyond `max_streak` earn nothing, so the run stops there
+    let mut run = 0;
+    for index in series.blinks[..position]
+        .iter()
+        .rev()
+        .take(series.max_streak as usize)
+    {
+        let Some([blink_state, submit_state]) = previous.get(2 * run..2 * run + 2) else {
+            break;
+        };
+        if !answered_right(blink_state, submit_state, *index, user)? {
+            break;
+        }
+        run += 1;
+    }
+    streak.streak = run as u32 + 1;
+    streak.last_position = position as u32;
+
+    let bonus = if current_time()? < series.end_time {
+        series.streak_bonus(streak.streak, reward)
+    } else {
+        0
+    };
+    series.bonus -= bonus;
+
+    Ok((bonus, &previous[2 * run..]))
+}
+
+/// Whether `submit_state` is `user`'s submission on blink `index` and matches
+/// the answer `blink_state` closed with.
+fn answered_right<'info>(
+    blink_state: &'info AccountInfo<'info>,
+    submit_state: &'info AccountInfo<'info>,
+    index: u64,
+    user: &Pubkey,
+) -> Result<bool> {
+    let (expected, _) = Pubkey::find_program_address(
+        &[SUBMIT_SEED.as_bytes(), &index.to_le_bytes(), user.as_ref()],
+        &crate::ID,
+    );
+    if *submit_state.key != expected {
+        return Ok(false);
+    }
+    let submit_state = AccountLoader::<SubmitState>::try_from(submit_state)?;
+    let submit_state = submit_state.load()?;
+    if submit_state.blink_state != *blink_state.key {
+        return Ok(false);
+    }
+    let blink_state = AccountLoader::<BlinkState>::try_from(blink_state)?;
+    let blink_state = blink_state.load()?;
+    Ok(blink_state.closed && blink_state.answer == submit_state.answer)
+}
+
+/// Move `amount` of the owner's tokens into the series vault, returning what
+/// it actually received after any transfer fee.
+fn fund<'info>(
+    owner: &Signer<'info>,
+    owner_token: &InterfaceAccount<'info, TokenAccount>,
+    vault: &mut InterfaceAccount<'info, TokenAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    remaining_accounts: &[AccountInfo<'info>],
+    amount: u64,
+) -> Result<u64> {
+    let vault_before = vault.amount;
+    transfer_from_user_to_pool_vault(
+        owner.to_account_info(),
+        owner_token.to_account_info(),
+        vault.to_account_info(),
+        token_mint.to_account_info(),
+        token_program.to_account_info(),
+        remaining_accounts,
+        amount,
+        token_mint.decimals,
+    )?;
+    vault.reload()?;
+    Ok(vault.amount.checked_sub(vault_before).unwrap())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateSeries<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        init,
+        seeds = [
+            SERIES_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = ANCHOR_DISCRIMINATOR + Series::INIT_SPACE
+    )]
+    pub series: Account<'info, Series>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Holds the bonus funding, owned by the series
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = token_mint,
+        associated_token::authority = series,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct FundSeries<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            SERIES_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+        ],
+        bump = series.bump,
+        has_one = owner,
+        has_one = token_mint,
+        has_one = vault,
+    )]
+    pub series: Account<'info, Series>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct AddSeriesBlink<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            SERIES_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+        ],
+        bump = series.bump,
+        has_one = owner,
+    )]
+    pub series: Account<'info, Series>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct CreateStreak<'info> {
+    pub user: Signer<'info>,
+
+    /// Pays for the new streak account. The user or a sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        seeds = [
+            SERIES_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+        ],
+        bump = series.bump,
+    )]
+    pub series: Account<'info, Series>,
+
+    #[account(
+        init,
+        seeds = [
+            STREAK_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+            user.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + Streak::INIT_SPACE
+    )]
+    pub streak: Account<'info, Streak>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct WithdrawSeries<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            SERIES_SEED.as_bytes(),
+            &id.to_le_bytes().as_ref(),
+        ],
+        bump = series.bump,
+        has_one = owner,
+        has_one = token_mint,
+        has_one = vault,
+    )]
+    pub series: Account<'info, Series>,
+
+    #[account(
+        mint::token_program = token_program,
+    )]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program
+    )]
+    pub owner_token: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,14 +1,17 @@
+use crate::utils::current_time;
 use crate::{error::ErrorCode, state::*};
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::clock;
 
-pub fn submit(ctx: Context<Submit>, index: u16, answer: u8) -> Result<()> {
+pub fn submit(ctx: Context<Submit>, index: u64, answer: u8) -> Result<()> {
     let blink_state = &mut ctx.accounts.blink_state.load_mut()?;
+    if ctx.accounts.global_config.paused || blink_state.frozen {
+        return err!(ErrorCode::Paused);
+    }
 
-    let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
-    if blink_state.open_time > block_timestamp {
+    let now = current_time()?;
+    if blink_state.open_time > now {
         return err!(ErrorCode::InvalidOpenTime);
-    } else if block_timestamp > blink_state.close_time {
+    } else if now > blink_state.close_time {
         return err!(ErrorCode::InvalidCloseTime);
     }
 
@@ -35,8 +38,14 @@ pub fn submit(ctx: Context<Submit>, index: u16, answer: u8) -> Result<()> {
     submit_state.answer = answer;
     submit_state.claim = false;
     submit_state.bump = ctx.bumps.submit_state;
+    submit_state.version = SUBMIT_STATE_VERSION;
+
+    if let Some(user_profile) = &mut ctx.accounts.user_profile {
+        user_profile.submissions = user_profile.submissions.checked_add(1).unwrap();
+        user_profile.last_index = index;
+    }
 
-    emit!(SubmitEvent {
+    emit_cpi!(SubmitEvent {
         index,
         user: ctx.accounts.user.key(),
         answer
@@ -45,12 +54,24 @@ pub fn submit(ctx: Context<Submit>, index: u16, answer: u8) -> Result<()> {
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(index: u16)]
+#[instruction(index: u64)]
 pub struct Submit<'info> {
-    #[account(mut)]
     pub user: Signer<'info>,
 
+    /// Pays for the new submit account. The user or a sponsor
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
     #[account(
         init,
         seeds = [
@@ -59,7 +80,7 @@ pub struct Submit<'info> {
             user.key().as_ref(),
         ],
         bump,
-        payer = user,
+        payer = payer,
         space = ANCHOR_DISCRIMINATOR + SubmitState::INIT_SPACE
     )]
     pub submit_state: AccountLoader<'info, SubmitState>,
@@ -74,4 +95,14 @@ pub struct Submit<'info> {
     )]
     pub blink_state: AccountLoader<'info, BlinkState>,
     pub system_program: Program<'info, System>,
+
+    #[account(
+        mut,
+        seeds = [
+            PROFILE_SEED.as_bytes(),
+            user.key().as_ref(),
+        ],
+        bump = user_profile.bump,
+    )]
+    pub user_profile: Option<Account<'info, UserProfile>>,
 }
@@ -1,29 +1,38 @@
 use crate::error::ErrorCode;
 use crate::state::*;
 
+use crate::utils::current_time;
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::clock;
 use std::ops::DerefMut;
 
 pub fn create_time(ctx: Context<CreateTimeConfig>, open_time: u64, period: u64) -> Result<()> {
-    let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
-    if open_time < block_timestamp {
+    let now = current_time()?;
+    if open_time < now {
         return err!(ErrorCode::InvalidOpenTime);
     }
     let close_time = open_time.checked_add(period).unwrap();
 
     let time_config = ctx.accounts.time_config.deref_mut();
-    time_config.owner = ctx.accounts.owner.key();
     time_config.open_time = open_time;
     time_config.close_time = close_time;
     time_config.bump = ctx.bumps.time_config;
+    time_config.version = TIME_CONFIG_VERSION;
+
+    emit_cpi!(TimeConfigCreatedEvent {
+        time_manager: ctx.accounts.time_manager.key(),
+        open_time,
+        close_time
+    });
 
     Ok(())
 }
 
 pub fn update_time(ctx: Context<UpdateTimeConfig>, open_time: u64, period: u64) -> Result<()> {
-    let block_timestamp = clock::Clock::get()?.unix_timestamp as u64;
-    if open_time < block_timestamp {
+    if ctx.accounts.global_config.paused {
+        return err!(ErrorCode::Paused);
+    }
+    let now = current_time()?;
+    if open_time < now {
         return err!(ErrorCode::InvalidOpenTime);
     }
     let close_time = open_time.checked_add(period).unwrap();
@@ -32,13 +41,29 @@ pub fn update_time(ctx: Context<UpdateTimeConfig>, open_time: u64, period: u64)
     time_config.open_time = open_time;
     time_config.close_time = close_time;
 
+    emit_cpi!(TimeConfigUpdatedEvent {
+        time_manager: ctx.accounts.time_manager.key(),
+        open_time,
+        close_time
+    });
+
     Ok(())
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CreateTimeConfig<'info> {
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub time_manager: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+        has_one = time_manager,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
 
     #[account(
         init,
@@ -46,7 +71,7 @@ pub struct CreateTimeConfig<'info> {
             TIME_SEED.as_bytes(),
         ],
         bump,
-        payer = owner,
+        payer = time_manager,
         space = ANCHOR_DISCRIMINATOR + TimeConfig::INIT_SPACE
     )]
     pub time_config: Account<'info, TimeConfig>,
@@ -54,14 +79,27 @@ pub struct CreateTimeConfig<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct UpdateTimeConfig<'info> {
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub time_manager: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+        has_one = time_manager,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
 
     #[account(
         mut,
-        has_one = owner,
+        seeds = [
+            TIME_SEED.as_bytes(),
+        ],
+        bump = time_config.bump,
     )]
     pub time_config: Account<'info, TimeConfig>,
 }
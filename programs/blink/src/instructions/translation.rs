@@ -0,0 +1,106 @@
+use super::update_config::check_editable;
+use crate::error::ErrorCode;
+use crate::state::*;
+
+use anchor_lang::prelude::*;
+use std::ops::DerefMut;
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_translation(
+    ctx: Context<AddTranslation>,
+    index: u64,
+    lang: String,
+    content: String,
+    option1: String,
+    option2: String,
+    option3: String,
+    option4: String,
+) -> Result<()> {
+    check_editable(
+        &ctx.accounts.global_config,
+        &ctx.accounts.blink_state,
+        index,
+    )?;
+    // `lang` is checked already, by the seeds of `blink_translation`
+    if !BlinkConfig::check_len("", &content, [&option1, &option2, &option3, &option4]) {
+        return err!(ErrorCode::ContentTooLong);
+    }
+
+    let blink_translation = ctx.accounts.blink_translation.deref_mut();
+    blink_translation.index = index;
+    blink_translation.lang = lang;
+    blink_translation.content = content;
+    blink_translation.option1 = option1;
+    blink_translation.option2 = option2;
+    blink_translation.option3 = option3;
+    blink_translation.option4 = option4;
+    blink_translation.bump = ctx.bumps.blink_translation;
+    blink_translation.version = BLINK_TRANSLATION_VERSION;
+
+    emit_cpi!(TranslationAddedEvent {
+        index,
+        lang: blink_translation.lang.clone(),
+        content_hash: blink_translation.content_hash()
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(
+    index: u64,
+    lang: String,
+    content: String,
+    option1: String,
+    option2: String,
+    option3: String,
+    option4: String,
+)]
+pub struct AddTranslation<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        seeds = [
+            CONFIG_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+        ],
+        bump = blink_config.bump,
+        has_one = creator,
+    )]
+    pub blink_config: Account<'info, BlinkConfig>,
+
+    #[account(
+        seeds = [
+            BLINK_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+        ],
+        bump = blink_state.load()?.bump,
+    )]
+    pub blink_state: AccountLoader<'info, BlinkState>,
+
+    #[account(
+        init,
+        seeds = [
+            TRANSLATION_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+            BlinkTranslation::lang_seed(&lang)?,
+        ],
+        bump,
+        payer = creator,
+        space = ANCHOR_DISCRIMINATOR
+            + BlinkTranslation::space(&lang, &content, [&option1, &option2, &option3, &option4])
+    )]
+    pub blink_translation: Account<'info, BlinkTranslation>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,198 @@
+use crate::error::ErrorCode;
+use crate::state::*;
+use crate::utils::current_time;
+
+use anchor_lang::prelude::*;
+use std::ops::DerefMut;
+
+/// Replace the content with inline strings, dropping any metadata uri.
+#[allow(clippy::too_many_arguments)]
+pub fn update_config(
+    ctx: Context<UpdateConfig>,
+    index: u64,
+    pic: String,
+    content: String,
+    option1: String,
+    option2: String,
+    option3: String,
+    option4: String,
+) -> Result<()> {
+    check_editable(
+        &ctx.accounts.global_config,
+        &ctx.accounts.blink_state,
+        index,
+    )?;
+    if !BlinkConfig::check_len(&pic, &content, [&option1, &option2, &option3, &option4]) {
+        return err!(ErrorCode::ContentTooLong);
+    }
+
+    let blink_config = ctx.accounts.blink_config.deref_mut();
+    blink_config.pic = pic;
+    blink_config.content = content;
+    blink_config.option1 = option1;
+    blink_config.option2 = option2;
+    blink_config.option3 = option3;
+    blink_config.option4 = option4;
+    blink_config.uri = String::new();
+    blink_config.metadata_hash = [0; 32];
+
+    emit_cpi!(ConfigUpdatedEvent {
+        index,
+        content_hash: blink_config.content_hash()
+    });
+
+    Ok(())
+}
+
+/// Point the content at a metadata document, dropping any inline strings.
+pub fn update_metadata(
+    ctx: Context<UpdateMetadata>,
+    index: u64,
+    uri: String,
+    metadata_hash: [u8; 32],
+) -> Result<()> {
+    check_editable(
+        &ctx.accounts.global_config,
+        &ctx.accounts.blink_state,
+        index,
+    )?;
+    if uri.is_empty() {
+        return err!(ErrorCode::InvalidUri);
+    }
+    if uri.len() > MAX_URI_LEN {
+        return err!(ErrorCode::ContentTooLong);
+    }
+
+    let blink_config = ctx.accounts.blink_config.deref_mut();
+    blink_config.pic = String::new();
+    blink_config.content = String::new();
+    blink_config.option1 = String::new();
+    blink_config.option2 = String::new();
+    blink_config.option3 = String::new();
+    blink_config.option4 = String::new();
+    blink_config.uri = uri;
+    blink_config.metadata_hash = metadata_hash;
+
+    emit_cpi!(ConfigUpdatedEvent {
+        index,
+        content_hash: metadata_hash
+    });
+
+    Ok(())
+}
+
+pub(crate) fn check_editable(
+    global_config: &GlobalConfig,
+    blink_state: &AccountLoader<BlinkState>,
+    index: u64,
+) -> Result<()> {
+    let blink_state = &blink_state.load()?;
+    if global_config.paused || blink_state.frozen {
+        return err!(ErrorCode::Paused);
+    }
+    if blink_state.index != index {
+        return err!(ErrorCode::InvalidIndex);
+    }
+    // content is locked once the blink opens
+    if current_time()? >= blink_state.open_time {
+        return err!(ErrorCode::InvalidOpenTime);
+    }
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(
+    index: u64,
+    pic: String,
+    content: String,
+    option1: String,
+    option2: String,
+    option3: String,
+    option4: String,
+)]
+pub struct UpdateConfig<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+        ],
+        bump = blink_config.bump,
+        has_one = creator,
+        realloc = ANCHOR_DISCRIMINATOR
+            + BlinkConfig::space(
+                &pic,
+                &content,
+                [&option1, &option2, &option3, &option4],
+                "",
+            ),
+        realloc::payer = creator,
+        realloc::zero = false,
+    )]
+    pub blink_config: Account<'info, BlinkConfig>,
+
+    #[account(
+        seeds = [
+            BLINK_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+        ],
+        bump = blink_state.load()?.bump,
+    )]
+    pub blink_state: AccountLoader<'info, BlinkState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(index: u64, uri: String)]
+pub struct UpdateMetadata<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [
+            GLOBAL_SEED.as_bytes(),
+        ],
+        bump = global_config.bump,
+    )]
+    pub global_config: Account<'info, GlobalConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            CONFIG_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+        ],
+        bump = blink_config.bump,
+        has_one = creator,
+        realloc = ANCHOR_DISCRIMINATOR
+            + BlinkConfig::space("", "", ["", "", "", ""], &uri),
+        realloc::payer = creator,
+        realloc::zero = false,
+    )]
+    pub blink_config: Account<'info, BlinkConfig>,
+
+    #[account(
+        seeds = [
+            BLINK_SEED.as_bytes(),
+            &index.to_le_bytes().as_ref(),
+        ],
+        bump = blink_state.load()?.bump,
+    )]
+    pub blink_state: AccountLoader<'info, BlinkState>,
+
+    pub system_program: Program<'info, System>,
+}
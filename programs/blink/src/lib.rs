@@ -14,6 +14,45 @@ declare_id!("5JpxgkxQ2euSxEVWrpY52biJRCYAWp2f6KaZSiiCetc2");
 pub mod blink {
     use super::*;
 
+    pub fn create_global(ctx: Context<CreateGlobalConfig>) -> Result<()> {
+        instructions::create_global(ctx)
+    }
+
+    pub fn propose_admin(ctx: Context<UpdateGlobalConfig>, admin: Pubkey) -> Result<()> {
+        instructions::propose_admin(ctx, admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    pub fn set_time_manager(ctx: Context<UpdateGlobalConfig>, time_manager: Pubkey) -> Result<()> {
+        instructions::set_time_manager(ctx, time_manager)
+    }
+
+    pub fn set_resolver(ctx: Context<UpdateGlobalConfig>, resolver: Pubkey) -> Result<()> {
+        instructions::set_resolver(ctx, resolver)
+    }
+
+    pub fn set_pause_guardian(
+        ctx: Context<UpdateGlobalConfig>,
+        pause_guardian: Pubkey,
+    ) -> Result<()> {
+        instructions::set_pause_guardian(ctx, pause_guardian)
+    }
+
+    pub fn pause(ctx: Context<UpdatePause>) -> Result<()> {
+        instructions::set_paused(ctx, true)
+    }
+
+    pub fn unpause(ctx: Context<UpdatePause>) -> Result<()> {
+        instructions::set_paused(ctx, false)
+    }
+
+    pub fn freeze_blink(ctx: Context<FreezeBlink>, index: u64, frozen: bool) -> Result<()> {
+        instructions::freeze_blink(ctx, index, frozen)
+    }
+
     pub fn create_time(ctx: Context<CreateTimeConfig>, open_time: u64, period: u64) -> Result<()> {
         instructions::create_time(ctx, open_time, period)
     }
@@ -23,9 +62,8 @@ pub mod blink {
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub fn initialize(
-        ctx: Context<Initialize>,
-        index: u16,
+    pub fn initialize<'info>(
+        ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
         amount: u64,
         pic: String,
         content: String,
@@ -35,19 +73,162 @@ pub mod blink {
         option4: String,
     ) -> Result<()> {
         instructions::initialize(
-            ctx, index, amount, pic, content, option1, option2, option3, option4,
+            ctx, amount, pic, content, option1, option2, option3, option4,
         )
     }
 
-    pub fn submit(ctx: Context<Submit>, index: u16, answer: u8) -> Result<()> {
+    pub fn initialize_with_metadata<'info>(
+        ctx: Context<'_, '_, '_, 'info, Initialize<'info>>,
+        amount: u64,
+        uri: String,
+        metadata_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::initialize_with_metadata(ctx, amount, uri, metadata_hash)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        index: u64,
+        pic: String,
+        content: String,
+        option1: String,
+        option2: String,
+        option3: String,
+        option4: String,
+    ) -> Result<()> {
+        instructions::update_config(ctx, index, pic, content, option1, option2, option3, option4)
+    }
+
+    pub fn update_metadata(
+        ctx: Context<UpdateMetadata>,
+        index: u64,
+        uri: String,
+        metadata_hash: [u8; 32],
+    ) -> Result<()> {
+        instructions::update_metadata(ctx, index, uri, metadata_hash)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_translation(
+        ctx: Context<AddTranslation>,
+        index: u64,
+        lang: String,
+        content: String,
+        option1: String,
+        option2: String,
+        option3: String,
+        option4: String,
+    ) -> Result<()> {
+        instructions::add_translation(
+            ctx, index, lang, content, option1, option2, option3, option4,
+        )
+    }
+
+    pub fn create_profile(ctx: Context<CreateProfile>) -> Result<()> {
+        instructions::create_profile(ctx)
+    }
+
+    pub fn submit(ctx: Context<Submit>, index: u64, answer: u8) -> Result<()> {
         instructions::submit(ctx, index, answer)
     }
 
-    pub fn close(ctx: Context<Close>, index: u16, answer: u8) -> Result<()> {
+    pub fn close(ctx: Context<Close>, index: u64, answer: u8) -> Result<()> {
         instructions::close(ctx, index, answer)
     }
 
-    pub fn claim(ctx: Context<Claim>, index: u16) -> Result<()> {
+    pub fn claim<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Claim<'info>>,
+        index: u64,
+    ) -> Result<()> {
         instructions::claim(ctx, index)
     }
+
+    pub fn create_season<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateSeason<'info>>,
+        id: u64,
+        end_time: u64,
+        top_n: u8,
+        prize: u64,
+    ) -> Result<()> {
+        instructions::create_season(ctx, id, end_time, top_n, prize)
+    }
+
+    pub fn add_season_blink(ctx: Context<AddSeasonBlink>, id: u64, index: u64) -> Result<()> {
+        instructions::add_season_blink(ctx, id, index)
+    }
+
+    pub fn score(ctx: Context<Score>, id: u64, index: u64, user: Pubkey) -> Result<()> {
+        instructions::score(ctx, id, index, user)
+    }
+
+    pub fn payout_season<'info>(
+        ctx: Context<'_, '_, '_, 'info, PayoutSeason<'info>>,
+        id: u64,
+        rank: u8,
+    ) -> Result<()> {
+        instructions::payout_season(ctx, id, rank)
+    }
+
+    pub fn create_series<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateSeries<'info>>,
+        id: u64,
+        end_time: u64,
+        bonus_bps: u16,
+        max_streak: u32,
+        bonus: u64,
+    ) -> Result<()> {
+        instructions::create_series(ctx, id, end_time, bonus_bps, max_streak, bonus)
+    }
+
+    pub fn fund_series<'info>(
+        ctx: Context<'_, '_, '_, 'info, FundSeries<'info>>,
+        id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::fund_series(ctx, id, amount)
+    }
+
+    pub fn add_series_blink(ctx: Context<AddSeriesBlink>, id: u64, index: u64) -> Result<()> {
+        instructions::add_series_blink(ctx, id, index)
+    }
+
+    pub fn withdraw_series<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawSeries<'info>>,
+        id: u64,
+    ) -> Result<()> {
+        instructions::withdraw_series(ctx, id)
+    }
+
+    pub fn create_streak(ctx: Context<CreateStreak>, id: u64) -> Result<()> {
+        instructions::create_streak(ctx, id)
+    }
+
+    pub fn migrate_time_config(ctx: Context<MigrateTimeConfig>) -> Result<()> {
+        instructions::migrate_time_config(ctx)
+    }
+
+    pub fn migrate_blink_state(ctx: Context<MigrateBlinkState>, index: u16) -> Result<()> {
+        instructions::migrate_blink_state(ctx, index)
+    }
+
+    pub fn migrate_submit_state(
+        ctx: Context<MigrateSubmitState>,
+        index: u16,
+        user: Pubkey,
+    ) -> Result<()> {
+        instructions::migrate_submit_state(ctx, index, user)
+    }
+
+    pub fn extend_deadline(
+        ctx: Context<UpdateDeadline>,
+        index: u64,
+        close_time: u64,
+    ) -> Result<()> {
+        instructions::extend_deadline(ctx, index, close_time)
+    }
+
+    pub fn end_early(ctx: Context<UpdateDeadline>, index: u64, close_time: u64) -> Result<()> {
+        instructions::end_early(ctx, index, close_time)
+    }
 }
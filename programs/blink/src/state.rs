@@ -1,46 +1,243 @@
+use crate::error::ErrorCode;
+use crate::utils::seconds;
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
 
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
+/// Bytes left unused at the end of every state account so later fields can
+/// be added without changing the account size.
+pub const RESERVED_SPACE: usize = 64;
+
+pub const GLOBAL_SEED: &str = "global_seed";
+/// How long after `close_time` the creator alone may close a blink before
+/// the resolver role can step in.
+pub const RESOLVE_GRACE_PERIOD: u64 = seconds(24 * 60 * 60);
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalConfig {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+    pub time_manager: Pubkey,
+    pub resolver: Pubkey,
+    pub pause_guardian: Pubkey,
+    pub paused: bool,
+    /// Blink times are slot numbers rather than unix timestamps, as the
+    /// program was built with the `open-time` feature. Clients read it to
+    /// convert human durations.
+    pub slot_clock: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const GLOBAL_CONFIG_VERSION: u8 = 1;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub enum Role {
+    Admin,
+    TimeManager,
+    Resolver,
+    PauseGuardian,
+}
+
+pub const COUNTER_SEED: &str = "counter_seed";
+/// Blinks created before the counter picked their own `u16` index. The
+/// counter starts above them so migrated blinks keep their index.
+pub const LEGACY_INDEX_END: u64 = 1 << 16;
+/// Hands out blink indices; `initialize` takes `next_index` and bumps it.
+#[account]
+#[derive(InitSpace)]
+pub struct BlinkCounter {
+    pub next_index: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const BLINK_COUNTER_VERSION: u8 = 1;
 
 pub const TIME_SEED: &str = "time_seed";
+/// `open_time` and `close_time` hold slot numbers when
+/// `GlobalConfig::slot_clock` is set and unix timestamps otherwise.
 #[account]
 #[derive(InitSpace)]
 pub struct TimeConfig {
-    pub owner: Pubkey,
     pub open_time: u64,
     pub close_time: u64,
     pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
 }
+pub const TIME_CONFIG_VERSION: u8 = 1;
 
 pub const CONFIG_SEED: &str = "config_seed";
+pub const MAX_PIC_LEN: usize = 200;
+pub const MAX_CONTENT_LEN: usize = 200;
+pub const MAX_OPTION_LEN: usize = 100;
+pub const MAX_URI_LEN: usize = 200;
 #[account]
 #[derive(InitSpace)] // automatically calculate the space required for the struct
 pub struct BlinkConfig {
-    pub index: u16,
+    pub index: u64,
     pub creator: Pubkey,
-    #[max_len(200)]
+    #[max_len(MAX_PIC_LEN)]
     pub pic: String, // 4 bytes + 200 bytes
 
-    #[max_len(200)]
+    #[max_len(MAX_CONTENT_LEN)]
     pub content: String, // 4 bytes + 200 bytes
 
-    #[max_len(100)]
+    #[max_len(MAX_OPTION_LEN)]
     pub option1: String, // 4 bytes + 100 bytes
-    #[max_len(100)]
+    #[max_len(MAX_OPTION_LEN)]
     pub option2: String, // 4 bytes + 100 bytes
-    #[max_len(100)]
+    #[max_len(MAX_OPTION_LEN)]
     pub option3: String, // 4 bytes + 100 bytes
-    #[max_len(100)]
+    #[max_len(MAX_OPTION_LEN)]
     pub option4: String, // 4 bytes + 100 bytes
+
+    /// Off-chain content, empty when the content is stored inline.
+    #[max_len(MAX_URI_LEN)]
+    pub uri: String, // 4 bytes + 200 bytes
+    /// sha256 of the JSON document served at `uri`.
+    pub metadata_hash: [u8; 32],
     pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const BLINK_CONFIG_VERSION: u8 = 1;
+
+impl BlinkConfig {
+    /// Space needed to store a config holding exactly these strings.
+    pub fn space(pic: &str, content: &str, options: [&str; 4], uri: &str) -> usize {
+        Self::INIT_SPACE - MAX_PIC_LEN - MAX_CONTENT_LEN - 4 * MAX_OPTION_LEN - MAX_URI_LEN
+            + pic.len()
+            + content.len()
+            + options.iter().map(|option| option.len()).sum::<usize>()
+            + uri.len()
+    }
+
+    /// Space needed to store this config as it is.
+    pub fn size(&self) -> usize {
+        Self::space(
+            &self.pic,
+            &self.content,
+            [&self.option1, &self.option2, &self.option3, &self.option4],
+            &self.uri,
+        )
+    }
+
+    /// Whether the content lives off-chain at `uri`.
+    pub fn is_metadata(&self) -> bool {
+        !self.uri.is_empty()
+    }
+
+    pub fn check_len(pic: &str, content: &str, options: [&str; 4]) -> bool {
+        pic.len() <= MAX_PIC_LEN
+            && content.len() <= MAX_CONTENT_LEN
+            && options.iter().all(|option| option.len() <= MAX_OPTION_LEN)
+    }
+
+    /// sha256 over the length-prefixed pic, content and options, or the
+    /// metadata hash when the content lives off-chain.
+    pub fn content_hash(&self) -> [u8; 32] {
+        if self.is_metadata() {
+            return self.metadata_hash;
+        }
+        hash_fields(&[
+            &self.pic,
+            &self.content,
+            &self.option1,
+            &self.option2,
+            &self.option3,
+            &self.option4,
+        ])
+    }
+}
+
+/// sha256 over length-prefixed strings.
+fn hash_fields(fields: &[&str]) -> [u8; 32] {
+    let lens: Vec<[u8; 4]> = fields
+        .iter()
+        .map(|field| (field.len() as u32).to_le_bytes())
+        .collect();
+    let mut vals: Vec<&[u8]> = Vec::with_capacity(fields.len() * 2);
+    for (field, len) in fields.iter().zip(lens.iter()) {
+        vals.push(len);
+        vals.push(field.as_bytes());
+    }
+    hashv(&vals).to_bytes()
+}
+
+pub const TRANSLATION_SEED: &str = "translation_seed";
+pub const MAX_LANG_LEN: usize = 8;
+/// A language variant of a blink's content, one per blink and language code.
+#[account]
+#[derive(InitSpace)]
+pub struct BlinkTranslation {
+    pub index: u64,
+    #[max_len(MAX_LANG_LEN)]
+    pub lang: String, // 4 bytes + 8 bytes
+
+    #[max_len(MAX_CONTENT_LEN)]
+    pub content: String, // 4 bytes + 200 bytes
+
+    #[max_len(MAX_OPTION_LEN)]
+    pub option1: String, // 4 bytes + 100 bytes
+    #[max_len(MAX_OPTION_LEN)]
+    pub option2: String, // 4 bytes + 100 bytes
+    #[max_len(MAX_OPTION_LEN)]
+    pub option3: String, // 4 bytes + 100 bytes
+    #[max_len(MAX_OPTION_LEN)]
+    pub option4: String, // 4 bytes + 100 bytes
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const BLINK_TRANSLATION_VERSION: u8 = 1;
+
+impl BlinkTranslation {
+    /// Space needed to store a translation holding exactly these strings.
+    pub fn space(lang: &str, content: &str, options: [&str; 4]) -> usize {
+        Self::INIT_SPACE - MAX_LANG_LEN - MAX_CONTENT_LEN - 4 * MAX_OPTION_LEN
+            + lang.len()
+            + content.len()
+            + options.iter().map(|option| option.len()).sum::<usize>()
+    }
+
+    /// Language codes are short ascii tags such as `es` or `pt-BR`.
+    pub fn check_lang(lang: &str) -> bool {
+        !lang.is_empty()
+            && lang.len() <= MAX_LANG_LEN
+            && lang.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-')
+    }
+
+    /// `lang` as a PDA seed. Checked here since the seeds are derived before
+    /// any constraint runs, and one over 32 bytes would abort the derivation.
+    pub fn lang_seed(lang: &str) -> Result<&[u8]> {
+        require!(Self::check_lang(lang), ErrorCode::InvalidLang);
+        Ok(lang.as_bytes())
+    }
+
+    pub fn content_hash(&self) -> [u8; 32] {
+        hash_fields(&[
+            &self.content,
+            &self.option1,
+            &self.option2,
+            &self.option3,
+            &self.option4,
+        ])
+    }
 }
 
 pub const BLINK_SEED: &str = "blink_seed";
+/// Longest window (open_time to close_time) a creator may extend a blink to.
+pub const MAX_BLINK_PERIOD: u64 = seconds(30 * 24 * 60 * 60);
+/// Minimum notice a creator must give when ending a blink early.
+pub const MIN_CLOSE_NOTICE: u64 = seconds(60 * 60);
 
 #[account(zero_copy(unsafe))]
 #[derive(InitSpace)] // automatically calculate the space required for the struct
 pub struct BlinkState {
-    pub index: u16,
+    pub index: u64,
     pub creator: Pubkey,
     pub blink_config: Pubkey,
     pub vault: Pubkey,
@@ -51,18 +248,111 @@ pub struct BlinkState {
     pub right4: u32,
     pub amount: u64,
     pub closed: bool,
+    pub frozen: bool,
     pub answer: u8,
     pub reward: u64,
     pub open_time: u64,
     pub close_time: u64,
     pub auth_bump: u8,
     pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const BLINK_STATE_VERSION: u8 = 1;
+
+impl BlinkState {
+    /// Number of users who submitted `answer`, 0 for anything but 1 to 4.
+    pub fn rights(&self, answer: u8) -> u32 {
+        match answer {
+            1 => self.right1,
+            2 => self.right2,
+            3 => self.right3,
+            4 => self.right4,
+            _ => 0,
+        }
+    }
 }
 
 pub const SUBMIT_SEED: &str = "submit_seed";
 #[account(zero_copy(unsafe))]
 #[derive(InitSpace)] // automatically calculate the space required for the struct
 pub struct SubmitState {
+    pub index: u64,
+    pub blink_state: Pubkey,
+    pub user: Pubkey,
+    pub answer: u8,
+    pub claim: bool,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const SUBMIT_STATE_VERSION: u8 = 1;
+
+/// `TimeConfig` as stored before accounts were versioned. Legacy accounts
+/// carry the discriminator of the account they became and are only read by
+/// the `migrate_*` instructions.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LegacyTimeConfig {
+    pub owner: Pubkey,
+    /// Unix timestamp.
+    pub open_time: u64,
+    /// Unix timestamp.
+    pub close_time: u64,
+    pub bump: u8,
+}
+
+/// `BlinkConfig` as stored under its `u16` index.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LegacyBlinkConfig {
+    pub index: u16,
+    pub creator: Pubkey,
+    #[max_len(MAX_PIC_LEN)]
+    pub pic: String,
+    #[max_len(MAX_CONTENT_LEN)]
+    pub content: String,
+    #[max_len(MAX_OPTION_LEN)]
+    pub option1: String,
+    #[max_len(MAX_OPTION_LEN)]
+    pub option2: String,
+    #[max_len(MAX_OPTION_LEN)]
+    pub option3: String,
+    #[max_len(MAX_OPTION_LEN)]
+    pub option4: String,
+    pub bump: u8,
+}
+
+/// `BlinkState` as stored under its `u16` index. The packed zero-copy layout
+/// reads the same as borsh.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LegacyBlinkState {
+    pub index: u16,
+    pub creator: Pubkey,
+    pub blink_config: Pubkey,
+    pub vault: Pubkey,
+    pub token_mint: Pubkey,
+    pub right1: u32,
+    pub right2: u32,
+    pub right3: u32,
+    pub right4: u32,
+    pub amount: u64,
+    pub closed: bool,
+    pub answer: u8,
+    pub reward: u64,
+    /// Unix timestamp.
+    pub open_time: u64,
+    /// Unix timestamp.
+    pub close_time: u64,
+    pub auth_bump: u8,
+    pub bump: u8,
+}
+
+/// `SubmitState` as stored under its blink's `u16` index.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LegacySubmitState {
     pub index: u16,
     pub blink_state: Pubkey,
     pub user: Pubkey,
@@ -71,23 +361,364 @@ pub struct SubmitState {
     pub bump: u8,
 }
 
+pub const SEASON_SEED: &str = "season_seed";
+pub const SEASON_SCORE_SEED: &str = "season_score_seed";
+pub const SCORE_MARKER_SEED: &str = "score_marker_seed";
+pub const MAX_SEASON_BLINKS: usize = 32;
+pub const MAX_LEADERBOARD: usize = 10;
+/// A run of blinks scored together, with a prize pool split between the
+/// top `top_n` users once `end_time` has passed.
+#[account]
+#[derive(InitSpace)]
+pub struct Season {
+    pub id: u64,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub vault: Pubkey,
+    pub prize: u64,
+    pub end_time: u64,
+    pub top_n: u8,
+    /// Bit `rank` is set once that leaderboard entry has been paid.
+    pub paid: u16,
+    #[max_len(MAX_SEASON_BLINKS)]
+    pub blinks: Vec<u64>,
+    /// Best `top_n` scores, highest first.
+    #[max_len(MAX_LEADERBOARD)]
+    pub leaderboard: Vec<LeaderboardEntry>,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const SEASON_VERSION: u8 = 1;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct LeaderboardEntry {
+    pub user: Pubkey,
+    pub points: u64,
+}
+
+impl Season {
+    /// Record `user`'s new total and keep the best `top_n` entries.
+    pub fn update_leaderboard(&mut self, user: Pubkey, points: u64) {
+        match self.leaderboard.iter_mut().find(|entry| entry.user == user) {
+            Some(entry) => entry.points = points,
+            None => self.leaderboard.push(LeaderboardEntry { user, points }),
+        }
+        // stable, so earlier scorers keep ties
+        self.leaderboard
+            .sort_by_key(|entry| std::cmp::Reverse(entry.points));
+        self.leaderboard.truncate(self.top_n as usize);
+    }
+}
+
+/// A user's running total within one season.
+#[account]
+#[derive(InitSpace)]
+pub struct SeasonScore {
+    pub season: u64,
+    pub user: Pubkey,
+    /// One point per correct answer.
+    pub points: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const SEASON_SCORE_VERSION: u8 = 1;
+
+/// Marks one submission as counted towards one season, so a blink in
+/// several seasons scores once in each.
+#[account]
+#[derive(InitSpace)]
+pub struct ScoreMarker {
+    pub season: u64,
+    pub index: u64,
+    pub user: Pubkey,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const SCORE_MARKER_VERSION: u8 = 1;
+
+pub const SERIES_SEED: &str = "series_seed";
+pub const STREAK_SEED: &str = "streak_seed";
+pub const MAX_SERIES_BLINKS: usize = 32;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// An ordered run of blinks whose consecutive correct answers earn a bonus
+/// from the owner's funding.
+#[account]
+#[derive(InitSpace)]
+pub struct Series {
+    pub id: u64,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    /// The series' own token account, the associated one of its address,
+    /// holding the bonus funding apart from pots and season prizes.
+    pub vault: Pubkey,
+    /// Bonus funding left in `vault`.
+    pub bonus: u64,
+    /// Bonus per streak step, in bps of the reward.
+    pub bonus_bps: u16,
+    /// Streak steps beyond this earn no extra bonus.
+    pub max_streak: u32,
+    /// Claims earn bonuses until this blink clock value, then the owner can
+    /// withdraw what is left.
+    pub end_time: u64,
+    #[max_len(MAX_SERIES_BLINKS)]
+    pub blinks: Vec<u64>,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const SERIES_VERSION: u8 = 1;
+
+impl Series {
+    /// Bonus a claim of `reward` earns at `streak` consecutive correct
+    /// answers, capped by the funding left.
+    pub fn streak_bonus(&self, streak: u32, reward: u64) -> u64 {
+        let steps = streak.saturating_sub(1).min(self.max_streak) as u128;
+        (reward as u128 * steps * self.bonus_bps as u128 / BPS_DENOMINATOR as u128)
+            .min(self.bonus as u128) as u64
+    }
+}
+
+/// A user's run of consecutive correct answers within one series.
+#[account]
+#[derive(InitSpace)]
+pub struct Streak {
+    pub series: u64,
+    pub user: Pubkey,
+    /// Run of correct answers ending at the last claim counted, up to
+    /// `max_streak + 1`.
+    pub streak: u32,
+    /// Position in `Series::blinks` of the last claim counted.
+    pub last_position: u32,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const STREAK_VERSION: u8 = 1;
+
+pub const PROFILE_SEED: &str = "profile_seed";
+/// Participation totals for one wallet. `submit` and `claim` keep it up to
+/// date when it is passed in.
+#[account]
+#[derive(InitSpace)]
+pub struct UserProfile {
+    pub user: Pubkey,
+    pub submissions: u64,
+    pub correct: u64,
+    /// Sum of claimed rewards in base units as received, after any transfer
+    /// fee, across all mints.
+    pub rewards: u64,
+    pub last_index: u64,
+    pub bump: u8,
+    pub version: u8,
+    pub reserved: [u8; RESERVED_SPACE],
+}
+pub const USER_PROFILE_VERSION: u8 = 1;
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct AdminProposedEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct RoleUpdatedEvent {
+    pub role: Role,
+    pub old: Pubkey,
+    pub new: Pubkey,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct PauseEvent {
+    pub paused: bool,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct FreezeEvent {
+    #[index]
+    pub index: u64,
+    pub frozen: bool,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct MigrateEvent {
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
 pub struct InitializeEvent {
     #[index]
-    pub index: u16,
+    pub index: u64,
     pub creator: Pubkey,
     pub valut: Pubkey,
     pub token_mint: Pubkey,
     pub config: Pubkey,
     pub amount: u64,
+    pub open_time: u64,
+    pub close_time: u64,
+    /// `BlinkConfig::content_hash` of the content the blink opened with.
+    pub content_hash: [u8; 32],
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TimeConfigCreatedEvent {
+    pub time_manager: Pubkey,
+    pub open_time: u64,
+    pub close_time: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TimeConfigUpdatedEvent {
+    pub time_manager: Pubkey,
+    pub open_time: u64,
+    pub close_time: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ConfigUpdatedEvent {
+    #[index]
+    pub index: u64,
+    pub content_hash: [u8; 32],
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct TranslationAddedEvent {
+    #[index]
+    pub index: u64,
+    pub lang: String,
+    pub content_hash: [u8; 32],
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SeasonCreatedEvent {
+    #[index]
+    pub id: u64,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub prize: u64,
+    pub end_time: u64,
+    pub top_n: u8,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SeasonBlinkAddedEvent {
+    #[index]
+    pub id: u64,
+    pub index: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ScoreEvent {
+    #[index]
+    pub id: u64,
+    pub index: u64,
+    #[index]
+    pub user: Pubkey,
+    pub points: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SeasonPayoutEvent {
+    #[index]
+    pub id: u64,
+    pub rank: u8,
+    #[index]
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SeriesCreatedEvent {
+    #[index]
+    pub id: u64,
+    pub owner: Pubkey,
+    pub token_mint: Pubkey,
+    pub bonus: u64,
+    pub bonus_bps: u16,
+    pub max_streak: u32,
+    pub end_time: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SeriesBlinkAddedEvent {
+    #[index]
+    pub id: u64,
+    pub index: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SeriesFundedEvent {
+    #[index]
+    pub id: u64,
+    pub amount: u64,
+    /// Funding left after this one.
+    pub bonus: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct SeriesWithdrawnEvent {
+    #[index]
+    pub id: u64,
+    /// What was left in the series vault.
+    pub amount: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct StreakCreatedEvent {
+    #[index]
+    pub id: u64,
+    #[index]
+    pub user: Pubkey,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct StreakEvent {
+    #[index]
+    pub id: u64,
+    pub index: u64,
+    #[index]
+    pub user: Pubkey,
+    pub streak: u32,
+    pub bonus: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct ProfileCreatedEvent {
+    #[index]
+    pub user: Pubkey,
 }
 
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
 pub struct SubmitEvent {
     #[index]
-    pub index: u16,
+    pub index: u64,
     pub user: Pubkey,
     pub answer: u8,
 }
@@ -96,15 +727,44 @@ pub struct SubmitEvent {
 #[cfg_attr(feature = "client", derive(Debug))]
 pub struct CloseEvent {
     #[index]
-    pub index: u16,
+    pub index: u64,
+    pub answer: u8,
+    /// Paid to each correct submission.
+    pub reward: u64,
+    /// Submissions per option, `right1` to `right4`.
+    pub rights: [u32; 4],
+    /// Blink clock value the blink was resolved at.
+    pub timestamp: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct DeadlineExtendedEvent {
+    #[index]
+    pub index: u64,
+    pub old_close_time: u64,
+    pub close_time: u64,
+}
+
+#[event]
+#[cfg_attr(feature = "client", derive(Debug))]
+pub struct EndEarlyEvent {
+    #[index]
+    pub index: u64,
+    pub old_close_time: u64,
+    pub close_time: u64,
 }
 
 #[event]
 #[cfg_attr(feature = "client", derive(Debug))]
 pub struct ClaimEvent {
     #[index]
-    pub index: u16,
+    pub index: u64,
     #[index]
     pub user: Pubkey,
     pub reward: u64,
+    /// Streak bonus paid on top of `reward`.
+    pub bonus: u64,
+    /// Withheld by a Token-2022 transfer fee from `reward` plus `bonus`.
+    pub fee: u64,
 }
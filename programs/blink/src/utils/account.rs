@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Grow `account` to `space` bytes, topping up rent from `payer`.
+pub fn realloc_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    space: usize,
+) -> Result<()> {
+    let lamports = Rent::get()?.minimum_balance(space);
+    let missing = lamports.saturating_sub(account.lamports());
+    if missing > 0 {
+        transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account.to_account_info(),
+                },
+            ),
+            missing,
+        )?;
+    }
+    account.realloc(space, true)?;
+    Ok(())
+}
+
+/// Close `account`, moving its lamports to `destination`.
+pub fn close_account<'a>(account: &AccountInfo<'a>, destination: &AccountInfo<'a>) -> Result<()> {
+    let lamports = account.lamports();
+    **destination.try_borrow_mut_lamports()? =
+        destination.lamports().checked_add(lamports).unwrap();
+    **account.try_borrow_mut_lamports()? = 0;
+    account.assign(&anchor_lang::system_program::ID);
+    account.realloc(0, false)?;
+    Ok(())
+}
@@ -1,2 +1,8 @@
+pub mod account;
+pub use account::*;
+
 pub mod token;
 pub use token::*;
+
+pub mod time;
+pub use time::*;
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::clock;
+
+/// Whether blink windows are measured in slots (`open-time` feature) or in
+/// unix timestamps. Recorded in `GlobalConfig::slot_clock` for clients.
+pub const SLOT_CLOCK: bool = cfg!(feature = "open-time");
+
+/// Approximate slots produced per second, used to express fixed periods in
+/// slot units.
+pub const SLOTS_PER_SECOND_NUM: u64 = 5;
+pub const SLOTS_PER_SECOND_DEN: u64 = 2;
+
+/// Current point on the blink clock.
+pub fn current_time() -> Result<u64> {
+    let clock = clock::Clock::get()?;
+    if SLOT_CLOCK {
+        Ok(clock.slot)
+    } else {
+        Ok(clock.unix_timestamp as u64)
+    }
+}
+
+/// Convert a number of seconds into blink clock units.
+pub const fn seconds(secs: u64) -> u64 {
+    if SLOT_CLOCK {
+        secs * SLOTS_PER_SECOND_NUM / SLOTS_PER_SECOND_DEN
+    } else {
+        secs
+    }
+}
+
+/// Move a unix timestamp onto the blink clock. On slots the slot is
+/// estimated from the current one.
+pub fn from_unix_timestamp(timestamp: u64) -> Result<u64> {
+    let clock = clock::Clock::get()?;
+    if !SLOT_CLOCK {
+        return Ok(timestamp);
+    }
+    let now = clock.unix_timestamp as u64;
+    if timestamp >= now {
+        Ok(clock.slot.checked_add(seconds(timestamp - now)).unwrap())
+    } else {
+        Ok(clock.slot.saturating_sub(seconds(now - timestamp)))
+    }
+}
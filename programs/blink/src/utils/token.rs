@@ -5,7 +5,8 @@ use anchor_spl::{
         self,
         spl_token_2022::{
             self,
-            extension::{ExtensionType, StateWithExtensions},
+            extension::{transfer_fee::TransferFeeConfig, ExtensionType, StateWithExtensions},
+            onchain,
         },
     },
     token_interface::{
@@ -13,31 +14,34 @@ use anchor_spl::{
     },
 };
 
+/// Move `amount` from a user into a vault, resolving the extra accounts of a
+/// Token-2022 transfer hook from `remaining_accounts`.
+#[allow(clippy::too_many_arguments)]
 pub fn transfer_from_user_to_pool_vault<'a>(
     authority: AccountInfo<'a>,
     from: AccountInfo<'a>,
     to_vault: AccountInfo<'a>,
     mint: AccountInfo<'a>,
     token_program: AccountInfo<'a>,
+    remaining_accounts: &[AccountInfo<'a>],
     amount: u64,
     mint_decimals: u8,
 ) -> Result<()> {
     if amount == 0 {
         return Ok(());
     }
-    token_2022::transfer_checked(
-        CpiContext::new(
-            token_program.to_account_info(),
-            token_2022::TransferChecked {
-                from,
-                to: to_vault,
-                authority,
-                mint,
-            },
-        ),
+    onchain::invoke_transfer_checked(
+        token_program.key,
+        from,
+        mint,
+        to_vault,
+        authority,
+        remaining_accounts,
         amount,
         mint_decimals,
-    )
+        &[],
+    )?;
+    Ok(())
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -47,6 +51,7 @@ pub fn transfer_from_pool_vault_to_user<'a>(
     to: AccountInfo<'a>,
     mint: AccountInfo<'a>,
     token_program: AccountInfo<'a>,
+    remaining_accounts: &[AccountInfo<'a>],
     amount: u64,
     mint_decimals: u8,
     signer_seeds: &[&[&[u8]]],
@@ -54,20 +59,35 @@ pub fn transfer_from_pool_vault_to_user<'a>(
     if amount == 0 {
         return Ok(());
     }
-    token_2022::transfer_checked(
-        CpiContext::new_with_signer(
-            token_program.to_account_info(),
-            token_2022::TransferChecked {
-                from: from_vault,
-                to,
-                authority,
-                mint,
-            },
-            signer_seeds,
-        ),
+    onchain::invoke_transfer_checked(
+        token_program.key,
+        from_vault,
+        mint,
+        to,
+        authority,
+        remaining_accounts,
         amount,
         mint_decimals,
-    )
+        signer_seeds,
+    )?;
+    Ok(())
+}
+
+/// Fee the mint withholds when transferring `amount`, zero for spl-token
+/// mints and Token-2022 mints without a transfer fee.
+pub fn get_transfer_fee(mint_info: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint_info.owner != token_2022::Token2022::id() {
+        return Ok(0);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .unwrap(),
+        Err(_) => 0,
+    };
+    Ok(fee)
 }
 
 pub fn create_token_account<'a>(
@@ -0,0 +1,103 @@
+//! Reading the program's accounts. `deserialize` works on raw account data,
+//! the `fetch_*` helpers go through an rpc client.
+use anchor_lang::AccountDeserialize;
+use anyhow::{format_err, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use blink::state::*;
+
+use crate::pda;
+
+/// Decode account data, discriminator included.
+pub fn deserialize<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    let mut data = data;
+    Ok(T::try_deserialize(&mut data)?)
+}
+
+pub fn account_exists(rpc_client: &RpcClient, key: &Pubkey) -> Result<bool> {
+    Ok(rpc_client
+        .get_account_with_commitment(key, rpc_client.commitment())?
+        .value
+        .is_some())
+}
+
+pub fn fetch<T: AccountDeserialize>(rpc_client: &RpcClient, key: &Pubkey) -> Result<T> {
+    fetch_optional(rpc_client, key)?.ok_or_else(|| format_err!("account {} not found", key))
+}
+
+/// `None` when the account does not exist (yet).
+pub fn fetch_optional<T: AccountDeserialize>(
+    rpc_client: &RpcClient,
+    key: &Pubkey,
+) -> Result<Option<T>> {
+    rpc_client
+        .get_account_with_commitment(key, rpc_client.commitment())?
+        .value
+        .map(|account| deserialize(&account.data))
+        .transpose()
+}
+
+pub fn fetch_global_config(rpc_client: &RpcClient, program_id: &Pubkey) -> Result<GlobalConfig> {
+    fetch(rpc_client, &pda::global_config(program_id))
+}
+
+pub fn fetch_blink_counter(rpc_client: &RpcClient, program_id: &Pubkey) -> Result<BlinkCounter> {
+    fetch(rpc_client, &pda::blink_counter(program_id))
+}
+
+pub fn fetch_time_config(rpc_client: &RpcClient, program_id: &Pubkey) -> Result<TimeConfig> {
+    fetch(rpc_client, &pda::time_config(program_id))
+}
+
+pub fn fetch_blink_config(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    index: u64,
+) -> Result<BlinkConfig> {
+    fetch(rpc_client, &pda::blink_config(program_id, index))
+}
+
+pub fn fetch_blink_state(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    index: u64,
+) -> Result<BlinkState> {
+    fetch(rpc_client, &pda::blink_state(program_id, index))
+}
+
+/// `None` until `user` has answered blink `index`.
+pub fn fetch_submit_state(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    index: u64,
+    user: &Pubkey,
+) -> Result<Option<SubmitState>> {
+    fetch_optional(rpc_client, &pda::submit_state(program_id, index, user))
+}
+
+pub fn fetch_user_profile(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    user: &Pubkey,
+) -> Result<Option<UserProfile>> {
+    fetch_optional(rpc_client, &pda::user_profile(program_id, user))
+}
+
+pub fn fetch_season(rpc_client: &RpcClient, program_id: &Pubkey, id: u64) -> Result<Season> {
+    fetch(rpc_client, &pda::season(program_id, id))
+}
+
+pub fn fetch_series(rpc_client: &RpcClient, program_id: &Pubkey, id: u64) -> Result<Series> {
+    fetch(rpc_client, &pda::series(program_id, id))
+}
+
+/// `None` until `user` has claimed in series `id`.
+pub fn fetch_streak(
+    rpc_client: &RpcClient,
+    program_id: &Pubkey,
+    id: u64,
+    user: &Pubkey,
+) -> Result<Option<Streak>> {
+    fetch_optional(rpc_client, &pda::streak(program_id, id, user))
+}
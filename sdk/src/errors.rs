@@ -0,0 +1,96 @@
+//! Turning failed transactions back into the program's `ErrorCode`.
+use solana_sdk::{instruction::InstructionError, transaction::TransactionError};
+
+pub use blink::error::ErrorCode;
+
+/// Every program error, for looking codes up. A test checks it against
+/// `ErrorCode`.
+const ERROR_CODES: [ErrorCode; 25] = [
+    ErrorCode::CloseAlready,
+    ErrorCode::Opening,
+    ErrorCode::InvalidClaim,
+    ErrorCode::InvalidIndex,
+    ErrorCode::RewardZero,
+    ErrorCode::ClaimAlready,
+    ErrorCode::InvalidOwner,
+    ErrorCode::InvalidOpenTime,
+    ErrorCode::InvalidCloseTime,
+    ErrorCode::ExtensionTooLong,
+    ErrorCode::NoticeTooShort,
+    ErrorCode::InvalidPendingAdmin,
+    ErrorCode::InvalidResolver,
+    ErrorCode::Paused,
+    ErrorCode::InvalidVersion,
+    ErrorCode::ContentTooLong,
+    ErrorCode::InvalidUri,
+    ErrorCode::InvalidLang,
+    ErrorCode::SeasonFull,
+    ErrorCode::NotInSeason,
+    ErrorCode::ScoredAlready,
+    ErrorCode::InvalidRank,
+    ErrorCode::SeriesFull,
+    ErrorCode::InvalidSeries,
+    ErrorCode::InvalidUpgradeAuthority,
+];
+
+/// The `ErrorCode` behind a custom program error `code`, if it is one.
+pub fn decode(code: u32) -> Option<ErrorCode> {
+    ERROR_CODES
+        .iter()
+        .copied()
+        .find(|error| u32::from(*error) == code)
+}
+
+/// The failing instruction's index and program error, when a transaction
+/// failed on an `ErrorCode`.
+pub fn program_error(err: &TransactionError) -> Option<(u8, ErrorCode)> {
+    match err {
+        TransactionError::InstructionError(index, InstructionError::Custom(code)) => {
+            decode(*code).map(|error| (*index, error))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_matches_error_code() {
+        // variants in declaration order, straight from the program source
+        let source = include_str!("../../programs/blink/src/error.rs");
+        let body = &source[source.find("pub enum ErrorCode {").unwrap()..];
+        let variants: Vec<&str> = body
+            .lines()
+            .skip(1)
+            .map(str::trim)
+            .take_while(|line| *line != "}")
+            .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("//"))
+            .map(|line| line.trim_end_matches(','))
+            .collect();
+        let names: Vec<String> = ERROR_CODES.iter().map(ErrorCode::name).collect();
+        assert_eq!(names, variants);
+    }
+
+    #[test]
+    fn decodes_custom_codes() {
+        assert!(matches!(decode(6000), Some(ErrorCode::CloseAlready)));
+        assert!(matches!(
+            decode(6000 + ERROR_CODES.len() as u32 - 1),
+            Some(ErrorCode::InvalidUpgradeAuthority)
+        ));
+        assert!(decode(6000 + ERROR_CODES.len() as u32).is_none());
+        // anchor's own errors are not ours
+        assert!(decode(2006).is_none());
+    }
+
+    #[test]
+    fn finds_the_failing_instruction() {
+        let err = TransactionError::InstructionError(2, InstructionError::Custom(6013));
+        assert!(matches!(program_error(&err), Some((2, ErrorCode::Paused))));
+        let err = TransactionError::InstructionError(0, InstructionError::InvalidArgument);
+        assert!(program_error(&err).is_none());
+        assert!(program_error(&TransactionError::AccountInUse).is_none());
+    }
+}
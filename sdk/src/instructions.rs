@@ -0,0 +1,807 @@
+//! One builder per program instruction. Signers and accounts are plain
+//! pubkeys and every PDA is derived here, so nothing touches the network.
+//!
+//! Transfers of Token-2022 mints with a transfer hook still need the hook's
+//! extra accounts appended to the returned instruction.
+use anchor_lang::{InstructionData, ToAccountMetas};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_associated_token_account as spl_associated;
+
+use blink::accounts as blink_accounts;
+use blink::instruction as blink_instructions;
+use blink::state::Role;
+
+use crate::pda;
+
+/// Question and answer labels stored on chain.
+#[derive(Clone, Debug, Default)]
+pub struct BlinkContent {
+    pub pic: String,
+    pub content: String,
+    pub option1: String,
+    pub option2: String,
+    pub option3: String,
+    pub option4: String,
+}
+
+/// Question and answer labels in another language.
+#[derive(Clone, Debug, Default)]
+pub struct TranslationContent {
+    pub content: String,
+    pub option1: String,
+    pub option2: String,
+    pub option3: String,
+    pub option4: String,
+}
+
+fn instruction(
+    program_id: &Pubkey,
+    accounts: impl ToAccountMetas,
+    args: impl InstructionData,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts.to_account_metas(None),
+        data: args.data(),
+    }
+}
+
+/// `admin` has to be the program's upgrade authority.
+pub fn create_global(program_id: &Pubkey, admin: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::CreateGlobalConfig {
+            admin: *admin,
+            blink_program: *program_id,
+            program_data: pda::program_data(program_id),
+            global_config: pda::global_config(program_id),
+            blink_counter: pda::blink_counter(program_id),
+            system_program: system_program::id(),
+        },
+        blink_instructions::CreateGlobal {},
+    )
+}
+
+pub fn accept_admin(program_id: &Pubkey, pending_admin: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::AcceptAdmin {
+            pending_admin: *pending_admin,
+            global_config: pda::global_config(program_id),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::AcceptAdmin {},
+    )
+}
+
+/// For `Role::Admin` the key is only proposed and has to accept the transfer.
+pub fn set_role(program_id: &Pubkey, admin: &Pubkey, role: Role, key: Pubkey) -> Instruction {
+    let accounts = blink_accounts::UpdateGlobalConfig {
+        admin: *admin,
+        global_config: pda::global_config(program_id),
+        event_authority: pda::event_authority(program_id),
+        program: *program_id,
+    };
+    match role {
+        Role::Admin => instruction(
+            program_id,
+            accounts,
+            blink_instructions::ProposeAdmin { admin: key },
+        ),
+        Role::TimeManager => instruction(
+            program_id,
+            accounts,
+            blink_instructions::SetTimeManager { time_manager: key },
+        ),
+        Role::Resolver => instruction(
+            program_id,
+            accounts,
+            blink_instructions::SetResolver { resolver: key },
+        ),
+        Role::PauseGuardian => instruction(
+            program_id,
+            accounts,
+            blink_instructions::SetPauseGuardian {
+                pause_guardian: key,
+            },
+        ),
+    }
+}
+
+pub fn pause(program_id: &Pubkey, pause_guardian: &Pubkey, paused: bool) -> Instruction {
+    let accounts = blink_accounts::UpdatePause {
+        pause_guardian: *pause_guardian,
+        global_config: pda::global_config(program_id),
+        event_authority: pda::event_authority(program_id),
+        program: *program_id,
+    };
+    if paused {
+        instruction(program_id, accounts, blink_instructions::Pause {})
+    } else {
+        instruction(program_id, accounts, blink_instructions::Unpause {})
+    }
+}
+
+pub fn freeze_blink(
+    program_id: &Pubkey,
+    pause_guardian: &Pubkey,
+    index: u64,
+    frozen: bool,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::FreezeBlink {
+            pause_guardian: *pause_guardian,
+            global_config: pda::global_config(program_id),
+            blink_state: pda::blink_state(program_id, index),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::FreezeBlink { index, frozen },
+    )
+}
+
+pub fn create_time(
+    program_id: &Pubkey,
+    time_manager: &Pubkey,
+    open_time: u64,
+    period: u64,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::CreateTimeConfig {
+            time_manager: *time_manager,
+            global_config: pda::global_config(program_id),
+            time_config: pda::time_config(program_id),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::CreateTime { open_time, period },
+    )
+}
+
+pub fn update_time(
+    program_id: &Pubkey,
+    time_manager: &Pubkey,
+    open_time: u64,
+    period: u64,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::UpdateTimeConfig {
+            time_manager: *time_manager,
+            global_config: pda::global_config(program_id),
+            time_config: pda::time_config(program_id),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::UpdateTime { open_time, period },
+    )
+}
+
+fn initialize_accounts(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    index: u64,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> blink_accounts::Initialize {
+    blink_accounts::Initialize {
+        creator: *creator,
+        global_config: pda::global_config(program_id),
+        authority: pda::authority(program_id),
+        time_config: pda::time_config(program_id),
+        blink_counter: pda::blink_counter(program_id),
+        blink_config: pda::blink_config(program_id, index),
+        blink_state: pda::blink_state(program_id, index),
+        token_mint: *token_mint,
+        creator_token: spl_associated::get_associated_token_address_with_program_id(
+            creator,
+            token_mint,
+            token_program,
+        ),
+        vault: pda::vault(program_id, token_mint, token_program),
+        token_program: *token_program,
+        associated_token_program: spl_associated::id(),
+        system_program: system_program::id(),
+        event_authority: pda::event_authority(program_id),
+        program: *program_id,
+    }
+}
+
+/// `index` has to be the counter's `next_index`, which the blink takes.
+pub fn initialize(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    index: u64,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+    blink_content: BlinkContent,
+) -> Instruction {
+    instruction(
+        program_id,
+        initialize_accounts(program_id, creator, index, token_mint, token_program),
+        blink_instructions::Initialize {
+            amount,
+            pic: blink_content.pic,
+            content: blink_content.content,
+            option1: blink_content.option1,
+            option2: blink_content.option2,
+            option3: blink_content.option3,
+            option4: blink_content.option4,
+        },
+    )
+}
+
+/// Same as `initialize`, with the content in a JSON document at `uri`.
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_with_metadata(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    index: u64,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+    uri: String,
+    metadata_hash: [u8; 32],
+) -> Instruction {
+    instruction(
+        program_id,
+        initialize_accounts(program_id, creator, index, token_mint, token_program),
+        blink_instructions::InitializeWithMetadata {
+            amount,
+            uri,
+            metadata_hash,
+        },
+    )
+}
+
+pub fn update_config(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    index: u64,
+    blink_content: BlinkContent,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::UpdateConfig {
+            creator: *creator,
+            global_config: pda::global_config(program_id),
+            blink_config: pda::blink_config(program_id, index),
+            blink_state: pda::blink_state(program_id, index),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::UpdateConfig {
+            index,
+            pic: blink_content.pic,
+            content: blink_content.content,
+            option1: blink_content.option1,
+            option2: blink_content.option2,
+            option3: blink_content.option3,
+            option4: blink_content.option4,
+        },
+    )
+}
+
+pub fn update_metadata(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    index: u64,
+    uri: String,
+    metadata_hash: [u8; 32],
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::UpdateMetadata {
+            creator: *creator,
+            global_config: pda::global_config(program_id),
+            blink_config: pda::blink_config(program_id, index),
+            blink_state: pda::blink_state(program_id, index),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::UpdateMetadata {
+            index,
+            uri,
+            metadata_hash,
+        },
+    )
+}
+
+pub fn add_translation(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    index: u64,
+    lang: String,
+    translation: TranslationContent,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::AddTranslation {
+            creator: *creator,
+            global_config: pda::global_config(program_id),
+            blink_config: pda::blink_config(program_id, index),
+            blink_state: pda::blink_state(program_id, index),
+            blink_translation: pda::blink_translation(program_id, index, &lang),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::AddTranslation {
+            index,
+            lang,
+            content: translation.content,
+            option1: translation.option1,
+            option2: translation.option2,
+            option3: translation.option3,
+            option4: translation.option4,
+        },
+    )
+}
+
+/// `payer` pays for the profile, the user themselves or a sponsor.
+pub fn create_profile(program_id: &Pubkey, user: &Pubkey, payer: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::CreateProfile {
+            user: *user,
+            payer: *payer,
+            global_config: pda::global_config(program_id),
+            user_profile: pda::user_profile(program_id, user),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::CreateProfile {},
+    )
+}
+
+/// With `with_profile` the submit is counted in the user's profile, which
+/// has to exist by then. `payer` pays for the submit account.
+pub fn submit(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    payer: &Pubkey,
+    index: u64,
+    answer: u8,
+    with_profile: bool,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::Submit {
+            user: *user,
+            payer: *payer,
+            global_config: pda::global_config(program_id),
+            submit_state: pda::submit_state(program_id, index, user),
+            blink_state: pda::blink_state(program_id, index),
+            system_program: system_program::id(),
+            user_profile: with_profile.then(|| pda::user_profile(program_id, user)),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::Submit { index, answer },
+    )
+}
+
+pub fn close(program_id: &Pubkey, payer: &Pubkey, index: u64, answer: u8) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::Close {
+            payer: *payer,
+            global_config: pda::global_config(program_id),
+            blink_state: pda::blink_state(program_id, index),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::Close { index, answer },
+    )
+}
+
+fn update_deadline_accounts(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    index: u64,
+) -> blink_accounts::UpdateDeadline {
+    blink_accounts::UpdateDeadline {
+        creator: *creator,
+        global_config: pda::global_config(program_id),
+        blink_state: pda::blink_state(program_id, index),
+        event_authority: pda::event_authority(program_id),
+        program: *program_id,
+    }
+}
+
+pub fn extend_deadline(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    index: u64,
+    close_time: u64,
+) -> Instruction {
+    instruction(
+        program_id,
+        update_deadline_accounts(program_id, creator, index),
+        blink_instructions::ExtendDeadline { index, close_time },
+    )
+}
+
+pub fn end_early(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    index: u64,
+    close_time: u64,
+) -> Instruction {
+    instruction(
+        program_id,
+        update_deadline_accounts(program_id, creator, index),
+        blink_instructions::EndEarly { index, close_time },
+    )
+}
+
+/// With `series` the claim also counts towards the user's streak in that
+/// series, whose streak account has to exist by then. `streak_blinks` are the
+/// blinks right before `index` in the series the user answered correctly,
+/// newest first; see `rewards::streak_blinks`. `payer` pays for the user's
+/// token account if it does not exist yet.
+#[allow(clippy::too_many_arguments)]
+pub fn claim(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    payer: &Pubkey,
+    index: u64,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+    with_profile: bool,
+    series: Option<u64>,
+    streak_blinks: &[u64],
+) -> Instruction {
+    let mut claim = instruction(
+        program_id,
+        blink_accounts::Claim {
+            user: *user,
+            payer: *payer,
+            global_config: pda::global_config(program_id),
+            submit_state: pda::submit_state(program_id, index, user),
+            blink_state: pda::blink_state(program_id, index),
+            authority: pda::authority(program_id),
+            user_account: spl_associated::get_associated_token_address_with_program_id(
+                user,
+                token_mint,
+                token_program,
+            ),
+            vault: pda::vault(program_id, token_mint, token_program),
+            token_mint: *token_mint,
+            token_program: *token_program,
+            associated_token_program: spl_associated::id(),
+            system_program: system_program::id(),
+            user_profile: with_profile.then(|| pda::user_profile(program_id, user)),
+            series: series.map(|id| pda::series(program_id, id)),
+            streak: series.map(|id| pda::streak(program_id, id, user)),
+            series_vault: series
+                .map(|id| pda::series_vault(program_id, id, token_mint, token_program)),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::Claim { index },
+    );
+    for index in streak_blinks {
+        claim.accounts.extend([
+            AccountMeta::new_readonly(pda::blink_state(program_id, *index), false),
+            AccountMeta::new_readonly(pda::submit_state(program_id, *index, user), false),
+        ]);
+    }
+    claim
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_season(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    id: u64,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+    prize: u64,
+    end_time: u64,
+    top_n: u8,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::CreateSeason {
+            owner: *owner,
+            global_config: pda::global_config(program_id),
+            season: pda::season(program_id, id),
+            authority: pda::authority(program_id),
+            token_mint: *token_mint,
+            owner_token: spl_associated::get_associated_token_address_with_program_id(
+                owner,
+                token_mint,
+                token_program,
+            ),
+            vault: pda::vault(program_id, token_mint, token_program),
+            token_program: *token_program,
+            associated_token_program: spl_associated::id(),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::CreateSeason {
+            id,
+            end_time,
+            top_n,
+            prize,
+        },
+    )
+}
+
+pub fn add_season_blink(program_id: &Pubkey, owner: &Pubkey, id: u64, index: u64) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::AddSeasonBlink {
+            owner: *owner,
+            global_config: pda::global_config(program_id),
+            season: pda::season(program_id, id),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::AddSeasonBlink { id, index },
+    )
+}
+
+pub fn score(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    id: u64,
+    index: u64,
+    user: Pubkey,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::Score {
+            payer: *payer,
+            global_config: pda::global_config(program_id),
+            season: pda::season(program_id, id),
+            blink_state: pda::blink_state(program_id, index),
+            submit_state: pda::submit_state(program_id, index, &user),
+            score_marker: pda::score_marker(program_id, id, index, &user),
+            season_score: pda::season_score(program_id, id, &user),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::Score { id, index, user },
+    )
+}
+
+/// `winner` has to be the season's leaderboard entry at `rank`.
+pub fn payout_season(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    id: u64,
+    rank: u8,
+    winner: &Pubkey,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::PayoutSeason {
+            payer: *payer,
+            global_config: pda::global_config(program_id),
+            season: pda::season(program_id, id),
+            authority: pda::authority(program_id),
+            winner: *winner,
+            winner_token: spl_associated::get_associated_token_address_with_program_id(
+                winner,
+                token_mint,
+                token_program,
+            ),
+            vault: pda::vault(program_id, token_mint, token_program),
+            token_mint: *token_mint,
+            token_program: *token_program,
+            associated_token_program: spl_associated::id(),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::PayoutSeason { id, rank },
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_series(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    id: u64,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+    bonus: u64,
+    end_time: u64,
+    bonus_bps: u16,
+    max_streak: u32,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::CreateSeries {
+            owner: *owner,
+            global_config: pda::global_config(program_id),
+            series: pda::series(program_id, id),
+            token_mint: *token_mint,
+            owner_token: spl_associated::get_associated_token_address_with_program_id(
+                owner,
+                token_mint,
+                token_program,
+            ),
+            vault: pda::series_vault(program_id, id, token_mint, token_program),
+            token_program: *token_program,
+            associated_token_program: spl_associated::id(),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::CreateSeries {
+            id,
+            end_time,
+            bonus_bps,
+            max_streak,
+            bonus,
+        },
+    )
+}
+
+pub fn fund_series(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    id: u64,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::FundSeries {
+            owner: *owner,
+            global_config: pda::global_config(program_id),
+            series: pda::series(program_id, id),
+            token_mint: *token_mint,
+            owner_token: spl_associated::get_associated_token_address_with_program_id(
+                owner,
+                token_mint,
+                token_program,
+            ),
+            vault: pda::series_vault(program_id, id, token_mint, token_program),
+            token_program: *token_program,
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::FundSeries { id, amount },
+    )
+}
+
+/// Everything left in the series vault goes back to the owner's token
+/// account, once the series has ended.
+pub fn withdraw_series(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    id: u64,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::WithdrawSeries {
+            owner: *owner,
+            global_config: pda::global_config(program_id),
+            series: pda::series(program_id, id),
+            token_mint: *token_mint,
+            owner_token: spl_associated::get_associated_token_address_with_program_id(
+                owner,
+                token_mint,
+                token_program,
+            ),
+            vault: pda::series_vault(program_id, id, token_mint, token_program),
+            token_program: *token_program,
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::WithdrawSeries { id },
+    )
+}
+
+pub fn add_series_blink(program_id: &Pubkey, owner: &Pubkey, id: u64, index: u64) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::AddSeriesBlink {
+            owner: *owner,
+            global_config: pda::global_config(program_id),
+            series: pda::series(program_id, id),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::AddSeriesBlink { id, index },
+    )
+}
+
+pub fn create_streak(program_id: &Pubkey, user: &Pubkey, payer: &Pubkey, id: u64) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::CreateStreak {
+            user: *user,
+            payer: *payer,
+            global_config: pda::global_config(program_id),
+            series: pda::series(program_id, id),
+            streak: pda::streak(program_id, id, user),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::CreateStreak { id },
+    )
+}
+
+pub fn migrate_time_config(program_id: &Pubkey, payer: &Pubkey) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::MigrateTimeConfig {
+            payer: *payer,
+            global_config: pda::global_config(program_id),
+            time_config: pda::time_config(program_id),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::MigrateTimeConfig {},
+    )
+}
+
+/// Moves the config and state of legacy blink `index` to their `u64`
+/// addresses.
+pub fn migrate_blink_state(program_id: &Pubkey, payer: &Pubkey, index: u16) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::MigrateBlinkState {
+            payer: *payer,
+            global_config: pda::global_config(program_id),
+            legacy_blink_config: pda::legacy_blink_config(program_id, index),
+            legacy_blink_state: pda::legacy_blink_state(program_id, index),
+            blink_config: pda::blink_config(program_id, index.into()),
+            blink_state: pda::blink_state(program_id, index.into()),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::MigrateBlinkState { index },
+    )
+}
+
+/// The blink has to be migrated first, possibly earlier in the same
+/// transaction.
+pub fn migrate_submit_state(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    index: u16,
+    user: Pubkey,
+) -> Instruction {
+    instruction(
+        program_id,
+        blink_accounts::MigrateSubmitState {
+            payer: *payer,
+            global_config: pda::global_config(program_id),
+            legacy_submit_state: pda::legacy_submit_state(program_id, index, &user),
+            blink_state: pda::blink_state(program_id, index.into()),
+            submit_state: pda::submit_state(program_id, index.into(), &user),
+            system_program: system_program::id(),
+            event_authority: pda::event_authority(program_id),
+            program: *program_id,
+        },
+        blink_instructions::MigrateSubmitState { index, user },
+    )
+}
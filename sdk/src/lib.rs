@@ -0,0 +1,12 @@
+//! Building blocks for talking to the blink program from Rust: account
+//! addresses, instruction builders, account readers, error codes and payout
+//! maths.
+//!
+//! Only `accounts::fetch*` need an rpc connection; everything else is pure.
+pub mod accounts;
+pub mod errors;
+pub mod instructions;
+pub mod pda;
+pub mod rewards;
+
+pub use blink::ID;
@@ -0,0 +1,255 @@
+//! Addresses of the blink program's accounts. Everything is derived from
+//! `program_id`, so deployments other than `blink::ID` work too.
+use solana_sdk::{bpf_loader_upgradeable, pubkey::Pubkey};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use blink::{state::*, AUTH_SEED};
+
+pub fn global_config(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[GLOBAL_SEED.as_bytes()], program_id).0
+}
+
+pub fn blink_counter(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[COUNTER_SEED.as_bytes()], program_id).0
+}
+
+pub fn time_config(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[TIME_SEED.as_bytes()], program_id).0
+}
+
+/// Upgradeable loader account holding the program's upgrade authority.
+pub fn program_data(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id()).0
+}
+
+/// Owns every vault.
+pub fn authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[AUTH_SEED.as_bytes()], program_id).0
+}
+
+/// Signs the self-CPI every `emit_cpi!` event travels in.
+pub fn event_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], program_id).0
+}
+
+/// The authority's token account for `token_mint`, shared by all blinks and
+/// seasons paying in that mint.
+pub fn vault(program_id: &Pubkey, token_mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(&authority(program_id), token_mint, token_program)
+}
+
+pub fn blink_config(program_id: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[CONFIG_SEED.as_bytes(), &index.to_le_bytes()[..]],
+        program_id,
+    )
+    .0
+}
+
+pub fn blink_state(program_id: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[BLINK_SEED.as_bytes(), &index.to_le_bytes()[..]],
+        program_id,
+    )
+    .0
+}
+
+pub fn blink_translation(program_id: &Pubkey, index: u64, lang: &str) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            TRANSLATION_SEED.as_bytes(),
+            &index.to_le_bytes()[..],
+            lang.as_bytes(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn submit_state(program_id: &Pubkey, index: u64, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SUBMIT_SEED.as_bytes(),
+            &index.to_le_bytes()[..],
+            user.as_ref(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn user_profile(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PROFILE_SEED.as_bytes(), user.as_ref()], program_id).0
+}
+
+pub fn season(program_id: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[SEASON_SEED.as_bytes(), &id.to_le_bytes()[..]], program_id).0
+}
+
+pub fn score_marker(program_id: &Pubkey, id: u64, index: u64, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SCORE_MARKER_SEED.as_bytes(),
+            &id.to_le_bytes()[..],
+            &index.to_le_bytes()[..],
+            user.as_ref(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn season_score(program_id: &Pubkey, id: u64, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SEASON_SCORE_SEED.as_bytes(),
+            &id.to_le_bytes()[..],
+            user.as_ref(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+pub fn series(program_id: &Pubkey, id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[SERIES_SEED.as_bytes(), &id.to_le_bytes()[..]], program_id).0
+}
+
+/// The series' own token account, holding its bonus funding.
+pub fn series_vault(
+    program_id: &Pubkey,
+    id: u64,
+    token_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    get_associated_token_address_with_program_id(&series(program_id, id), token_mint, token_program)
+}
+
+pub fn streak(program_id: &Pubkey, id: u64, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[STREAK_SEED.as_bytes(), &id.to_le_bytes()[..], user.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+/// Where blinks created before the counter keep their config until migrated.
+pub fn legacy_blink_config(program_id: &Pubkey, index: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[CONFIG_SEED.as_bytes(), &index.to_le_bytes()[..]],
+        program_id,
+    )
+    .0
+}
+
+pub fn legacy_blink_state(program_id: &Pubkey, index: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[BLINK_SEED.as_bytes(), &index.to_le_bytes()[..]],
+        program_id,
+    )
+    .0
+}
+
+pub fn legacy_submit_state(program_id: &Pubkey, index: u16, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SUBMIT_SEED.as_bytes(),
+            &index.to_le_bytes()[..],
+            user.as_ref(),
+        ],
+        program_id,
+    )
+    .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user() -> Pubkey {
+        Pubkey::new_from_array([7; 32])
+    }
+
+    #[test]
+    fn seeds_are_stable() {
+        let derive = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &blink::ID).0;
+        let index = 300u64.to_le_bytes();
+        let id = 2u64.to_le_bytes();
+        let user = user();
+
+        assert_eq!(global_config(&blink::ID), derive(&[b"global_seed"]));
+        assert_eq!(blink_counter(&blink::ID), derive(&[b"counter_seed"]));
+        assert_eq!(time_config(&blink::ID), derive(&[b"time_seed"]));
+        assert_eq!(authority(&blink::ID), derive(&[b"auth_seed"]));
+        assert_eq!(
+            blink_config(&blink::ID, 300),
+            derive(&[b"config_seed", &index])
+        );
+        assert_eq!(
+            blink_state(&blink::ID, 300),
+            derive(&[b"blink_seed", &index])
+        );
+        assert_eq!(
+            blink_translation(&blink::ID, 300, "fr"),
+            derive(&[b"translation_seed", &index, b"fr"])
+        );
+        assert_eq!(
+            submit_state(&blink::ID, 300, &user),
+            derive(&[b"submit_seed", &index, user.as_ref()])
+        );
+        assert_eq!(
+            user_profile(&blink::ID, &user),
+            derive(&[b"profile_seed", user.as_ref()])
+        );
+        assert_eq!(season(&blink::ID, 2), derive(&[b"season_seed", &id]));
+        assert_eq!(
+            season_score(&blink::ID, 2, &user),
+            derive(&[b"season_score_seed", &id, user.as_ref()])
+        );
+        assert_eq!(
+            score_marker(&blink::ID, 2, 300, &user),
+            derive(&[b"score_marker_seed", &id, &index, user.as_ref()])
+        );
+        assert_eq!(series(&blink::ID, 2), derive(&[b"series_seed", &id]));
+        assert_eq!(
+            streak(&blink::ID, 2, &user),
+            derive(&[b"streak_seed", &id, user.as_ref()])
+        );
+    }
+
+    #[test]
+    fn legacy_addresses_use_u16_indexes() {
+        let user = user();
+        assert_eq!(
+            legacy_blink_state(&blink::ID, 300),
+            Pubkey::find_program_address(&[b"blink_seed", &300u16.to_le_bytes()], &blink::ID).0
+        );
+        assert_ne!(
+            legacy_blink_config(&blink::ID, 300),
+            blink_config(&blink::ID, 300)
+        );
+        assert_ne!(
+            legacy_blink_state(&blink::ID, 300),
+            blink_state(&blink::ID, 300)
+        );
+        assert_ne!(
+            legacy_submit_state(&blink::ID, 300, &user),
+            submit_state(&blink::ID, 300, &user)
+        );
+    }
+
+    #[test]
+    fn follows_the_program_id() {
+        let other = Pubkey::new_unique();
+        let (mint, token_program) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_ne!(global_config(&other), global_config(&blink::ID));
+        assert_eq!(
+            vault(&other, &mint, &token_program),
+            get_associated_token_address_with_program_id(&authority(&other), &mint, &token_program)
+        );
+        assert_eq!(
+            program_data(&other),
+            Pubkey::find_program_address(&[other.as_ref()], &bpf_loader_upgradeable::id()).0
+        );
+    }
+}
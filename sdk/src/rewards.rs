@@ -0,0 +1,183 @@
+//! Payouts worked out the way the program does, from accounts already
+//! fetched. Amounts are what leaves the vault, before any transfer fee.
+use blink::state::*;
+
+/// Reward per correct answer if the blink resolves to `answer`.
+pub fn reward_per_winner(blink_state: &BlinkState, answer: u8) -> u64 {
+    match blink_state.rights(answer) {
+        0 => 0,
+        rights => blink_state.amount / rights as u64,
+    }
+}
+
+/// What the owner of `submit_state` can claim now, 0 while the blink is open,
+/// after a wrong answer or once claimed.
+pub fn claimable(blink_state: &BlinkState, submit_state: &SubmitState) -> u64 {
+    if !blink_state.closed || submit_state.claim || submit_state.answer != blink_state.answer {
+        return 0;
+    }
+    blink_state.reward
+}
+
+/// Blinks right before `index` in `series` that can extend a streak ending
+/// at it, newest first. The claim reads those `answered_right` says the user
+/// got right, stopping at the first one they did not.
+pub fn streak_blinks<E>(
+    series: &Series,
+    index: u64,
+    mut answered_right: impl FnMut(u64) -> Result<bool, E>,
+) -> Result<Vec<u64>, E> {
+    let position = match series.blinks.iter().position(|blink| *blink == index) {
+        Some(position) => position,
+        None => return Ok(Vec::new()),
+    };
+    let mut blinks = Vec::new();
+    for index in series.blinks[..position]
+        .iter()
+        .rev()
+        .take(series.max_streak as usize)
+    {
+        if !answered_right(*index)? {
+            break;
+        }
+        blinks.push(*index);
+    }
+    Ok(blinks)
+}
+
+/// Streak bonus a claim of `reward` on blink `index` earns at blink clock
+/// `now`, after correct answers on the `run` blinks right before it in the
+/// series.
+pub fn streak_bonus(series: &Series, index: u64, run: u32, reward: u64, now: u64) -> u64 {
+    if !series.blinks.contains(&index) || now >= series.end_time {
+        return 0;
+    }
+    series.streak_bonus(run.min(series.max_streak) + 1, reward)
+}
+
+/// Equal share each leaderboard entry is paid.
+pub fn season_share(season: &Season) -> u64 {
+    season
+        .prize
+        .checked_div(season.leaderboard.len() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+
+    fn blink_state(closed: bool, answer: u8, reward: u64) -> BlinkState {
+        BlinkState {
+            index: 1,
+            creator: Pubkey::default(),
+            blink_config: Pubkey::default(),
+            vault: Pubkey::default(),
+            token_mint: Pubkey::default(),
+            right1: 3,
+            right2: 0,
+            right3: 1,
+            right4: 0,
+            amount: 900,
+            closed,
+            frozen: false,
+            answer,
+            reward,
+            open_time: 0,
+            close_time: 0,
+            auth_bump: 0,
+            bump: 0,
+            version: BLINK_STATE_VERSION,
+            reserved: [0; RESERVED_SPACE],
+        }
+    }
+
+    fn submit_state(answer: u8, claim: bool) -> SubmitState {
+        SubmitState {
+            index: 1,
+            blink_state: Pubkey::default(),
+            user: Pubkey::default(),
+            answer,
+            claim,
+            bump: 0,
+            version: SUBMIT_STATE_VERSION,
+            reserved: [0; RESERVED_SPACE],
+        }
+    }
+
+    fn series(blinks: Vec<u64>, max_streak: u32, bonus: u64) -> Series {
+        Series {
+            id: 1,
+            owner: Pubkey::default(),
+            token_mint: Pubkey::default(),
+            vault: Pubkey::default(),
+            bonus,
+            bonus_bps: 1_000,
+            max_streak,
+            end_time: 100,
+            blinks,
+            bump: 0,
+            version: SERIES_VERSION,
+            reserved: [0; RESERVED_SPACE],
+        }
+    }
+
+    #[test]
+    fn reward_is_split_between_winners() {
+        let state = blink_state(false, 0, 0);
+        assert_eq!(reward_per_winner(&state, 1), 300);
+        assert_eq!(reward_per_winner(&state, 3), 900);
+        assert_eq!(reward_per_winner(&state, 2), 0);
+        assert_eq!(reward_per_winner(&state, 5), 0);
+    }
+
+    #[test]
+    fn claimable_only_after_a_right_answer() {
+        let closed = blink_state(true, 1, 300);
+        assert_eq!(claimable(&closed, &submit_state(1, false)), 300);
+        assert_eq!(claimable(&closed, &submit_state(2, false)), 0);
+        assert_eq!(claimable(&closed, &submit_state(1, true)), 0);
+        assert_eq!(
+            claimable(&blink_state(false, 1, 300), &submit_state(1, false)),
+            0
+        );
+    }
+
+    #[test]
+    fn streak_reads_back_until_a_wrong_answer() {
+        let series = series(vec![10, 11, 12, 13, 14], 8, u64::MAX);
+        let right = |index: u64| Ok::<_, ()>(index != 11);
+        assert_eq!(streak_blinks(&series, 14, right), Ok(vec![13, 12]));
+        assert_eq!(streak_blinks(&series, 10, right), Ok(vec![]));
+        assert_eq!(streak_blinks(&series, 99, right), Ok(vec![]));
+        assert_eq!(streak_blinks(&series, 12, |_| Err("rpc")), Err("rpc"));
+    }
+
+    #[test]
+    fn streak_stops_at_max_streak() {
+        let series = series(vec![10, 11, 12, 13, 14], 2, 1_000);
+        assert_eq!(
+            streak_blinks(&series, 14, |_| Ok::<_, ()>(true)),
+            Ok(vec![13, 12])
+        );
+        // 10% of the reward per step, steps capped at `max_streak`
+        assert_eq!(streak_bonus(&series, 14, 0, 500, 0), 0);
+        assert_eq!(streak_bonus(&series, 14, 1, 500, 0), 50);
+        assert_eq!(streak_bonus(&series, 14, 4, 500, 0), 100);
+        assert_eq!(streak_bonus(&series, 99, 4, 500, 0), 0);
+    }
+
+    #[test]
+    fn streak_bonus_is_capped_by_funding() {
+        let series = series(vec![10, 11], 8, 30);
+        assert_eq!(streak_bonus(&series, 11, 1, 500, 0), 30);
+    }
+
+    #[test]
+    fn streak_bonus_ends_with_the_series() {
+        let series = series(vec![10, 11], 8, 1_000);
+        assert_eq!(streak_bonus(&series, 11, 1, 500, 99), 50);
+        assert_eq!(streak_bonus(&series, 11, 1, 500, 100), 0);
+    }
+}
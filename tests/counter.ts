@@ -0,0 +1,71 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createBlink,
+  createTokenMint,
+  expectError,
+  initializeAccounts,
+  mintTokens,
+  newWallet,
+  nextIndex,
+  setupGlobal,
+  setWindow,
+} from "./utils";
+
+// indices below are left to blinks migrated from their u16 index
+const LEGACY_INDEX_END = 1 << 16;
+
+describe("counter", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+
+  let alice: Keypair, bob: Keypair;
+  let tokenMint: PublicKey;
+
+  before(async () => {
+    await setupGlobal(program);
+    alice = await newWallet(connection);
+    bob = await newWallet(connection);
+    tokenMint = await createTokenMint(program);
+    await mintTokens(program, tokenMint, alice.publicKey, 10 ** 9);
+    await mintTokens(program, tokenMint, bob.publicKey, 10 ** 9);
+    await setWindow(program, 60, 60);
+  });
+
+  it("hands out consecutive indices above the legacy range", async () => {
+    const first = await nextIndex(program);
+    expect(first.toNumber()).to.be.at.least(LEGACY_INDEX_END);
+
+    const a = await createBlink(program, alice, tokenMint, new BN(1000));
+    const b = await createBlink(program, bob, tokenMint, new BN(1000));
+    expect(a.index.eq(first)).to.be.true;
+    expect(b.index.eq(first.addn(1))).to.be.true;
+    expect((await nextIndex(program)).eq(first.addn(2))).to.be.true;
+
+    const config = await program.account.blinkConfig.fetch(b.blinkConfig);
+    expect(config.index.eq(b.index)).to.be.true;
+    expect(config.creator.toBase58()).to.equal(bob.publicKey.toBase58());
+    const state = await program.account.blinkState.fetch(b.blinkState);
+    expect(state.index.eq(b.index)).to.be.true;
+  });
+
+  it("rejects accounts for any index but the next one", async () => {
+    const next = await nextIndex(program);
+    await expectError(
+      program.methods
+        .initialize(new BN(1000), "pic", "content", "op1", "op2", "op3", "op4")
+        .accountsPartial(
+          initializeAccounts(program, alice.publicKey, next.addn(1), tokenMint)
+        )
+        .signers([alice])
+        .rpc(),
+      "ConstraintSeeds"
+    );
+    expect((await nextIndex(program)).eq(next)).to.be.true;
+  });
+});
@@ -0,0 +1,142 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  BlinkAccounts,
+  chainTime,
+  closeBlink,
+  createBlink,
+  createTokenMint,
+  expectError,
+  getBlinkAddress,
+  getGlobalAddress,
+  mintTokens,
+  newWallet,
+  setupGlobal,
+  setWindow,
+  waitUntil,
+} from "./utils";
+
+const HOUR = 60 * 60;
+const MAX_BLINK_PERIOD = 30 * 24 * HOUR;
+
+describe("deadline", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+
+  let creator: Keypair, stranger: Keypair;
+  let tokenMint: PublicKey;
+  let blink: BlinkAccounts, short: BlinkAccounts;
+  let openTime: number, closeTime: number, shortClose: number;
+
+  const extendDeadline = (signer: Keypair, index: BN, time: number) =>
+    program.methods
+      .extendDeadline(index, new BN(time))
+      .accountsPartial({
+        creator: signer.publicKey,
+        globalConfig: getGlobalAddress(program.programId)[0],
+        blinkState: getBlinkAddress(index, program.programId)[0],
+      })
+      .signers([signer])
+      .rpc();
+
+  const endEarly = (signer: Keypair, index: BN, time: number) =>
+    program.methods
+      .endEarly(index, new BN(time))
+      .accountsPartial({
+        creator: signer.publicKey,
+        globalConfig: getGlobalAddress(program.programId)[0],
+        blinkState: getBlinkAddress(index, program.programId)[0],
+      })
+      .signers([signer])
+      .rpc();
+
+  const fetchClose = async (blinkState: PublicKey) =>
+    (await program.account.blinkState.fetch(blinkState)).closeTime.toNumber();
+
+  before(async () => {
+    await setupGlobal(program);
+    creator = await newWallet(connection);
+    stranger = await newWallet(connection);
+    tokenMint = await createTokenMint(program);
+    await mintTokens(program, tokenMint, creator.publicKey, 10 ** 9);
+
+    // long enough to end early with the hour of notice
+    [openTime, closeTime] = await setWindow(program, 2, 3 * HOUR);
+    blink = await createBlink(program, creator, tokenMint, new BN(1000));
+    [, shortClose] = await setWindow(program, 2, 4);
+    short = await createBlink(program, creator, tokenMint, new BN(1000));
+  });
+
+  it("extends a running blink", async () => {
+    await extendDeadline(creator, blink.index, closeTime + 600);
+    closeTime += 600;
+    expect(await fetchClose(blink.blinkState)).to.equal(closeTime);
+  });
+
+  it("rejects an extension that does not push the close later", async () => {
+    await expectError(
+      extendDeadline(creator, blink.index, closeTime),
+      "InvalidCloseTime"
+    );
+  });
+
+  it("rejects an extension past the longest period", async () => {
+    await expectError(
+      extendDeadline(creator, blink.index, openTime + MAX_BLINK_PERIOD + 1),
+      "ExtensionTooLong"
+    );
+  });
+
+  it("rejects an extension by anyone but the creator", async () => {
+    await expectError(
+      extendDeadline(stranger, blink.index, closeTime + 600),
+      "ConstraintHasOne"
+    );
+  });
+
+  it("rejects ending early with less than an hour of notice", async () => {
+    const now = await chainTime(connection);
+    await expectError(
+      endEarly(creator, blink.index, Math.max(now, openTime) + 60),
+      "NoticeTooShort"
+    );
+  });
+
+  it("rejects ending before the blink opens", async () => {
+    await expectError(
+      endEarly(creator, blink.index, openTime - 1),
+      "InvalidCloseTime"
+    );
+  });
+
+  it("ends a blink early with notice", async () => {
+    const time = (await chainTime(connection)) + HOUR + 60;
+    await endEarly(creator, blink.index, time);
+    expect(await fetchClose(blink.blinkState)).to.equal(time);
+  });
+
+  it("rejects an extension once the close time has passed", async () => {
+    await waitUntil(connection, shortClose);
+    await expectError(
+      extendDeadline(creator, short.index, shortClose + 600),
+      "InvalidCloseTime"
+    );
+  });
+
+  it("rejects deadline changes on a closed blink", async () => {
+    await closeBlink(program, creator, short.index, 1).rpc();
+    await expectError(
+      extendDeadline(creator, short.index, shortClose + 600),
+      "CloseAlready"
+    );
+    await expectError(
+      endEarly(creator, short.index, shortClose - 1),
+      "CloseAlready"
+    );
+  });
+});
@@ -1,412 +1,212 @@
-import * as anchor from "@coral-xyz/anchor";
-import { Program } from "@coral-xyz/anchor";
-import { Blink } from "../target/types/blink";
-import { PublicKey, Keypair } from "@solana/web3.js";
-import {
-  getAuthAddress,
-  getTimeAddress,
-  getConfigAddress,
-  getBlinkAddress,
-  getSubmitAddress,
-  getSplTokenAddress,
-  getKeypair,
-} from "./utils";
-import { BN } from "@coral-xyz/anchor";
-import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
-
-describe("blink start", () => {
-  // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
-
-  const program = anchor.workspace.Blink as Program<Blink>;
-  const provider = program.provider as anchor.AnchorProvider;
-  const payer = provider.wallet as anchor.Wallet;
-
-  const index = 4016;
-  const answer = 3;
-  const amount = new BN(10 ** 9);
-  const openTime = new BN(new Date().getTime() / 1000 + 60);
-  const period = new BN(600);
-
-  const tokenMint = new PublicKey(
-    "W8LRujy76DASXHev9VUWdbAUyBZnXmS5MXHKNScPmwW"
-  );
-
-  let creator, user, owner: Keypair;
-  let auth, config, blink, submit, vault, creatorToken, userToken: PublicKey;
-  let timeConfig: PublicKey;
-
-  const confirm = async (signature: string): Promise<string> => {
-    const block = await provider.connection.getLatestBlockhash();
-    await provider.connection.confirmTransaction({
-      signature,
-      ...block,
-    });
-    return signature;
-  };
-
-  function sleep(mm: number): Promise<void> {
-    return new Promise((resolve) => setTimeout(resolve, mm * 1000));
-  }
-
-  beforeEach(() => {
-    let _;
-    [auth, _] = getAuthAddress(program.programId);
-    [timeConfig, _] = getTimeAddress(program.programId);
-    [config, _] = getConfigAddress(index, program.programId);
-
-    [blink, _] = getBlinkAddress(index, program.programId);
-    vault = getSplTokenAddress(tokenMint, auth, TOKEN_PROGRAM_ID);
-
-    owner = getKeypair("./owner.json");
-    creator = getKeypair("./creator.json");
-    user = getKeypair("./user.json");
-
-    creatorToken = getSplTokenAddress(
-      tokenMint,
-      creator.publicKey,
-      TOKEN_PROGRAM_ID
-    );
-    userToken = getSplTokenAddress(tokenMint, user.publicKey, TOKEN_PROGRAM_ID);
-    [submit, _] = getSubmitAddress(index, user.publicKey, program.programId);
-    /*
-    console.log(`TOKEN_PROGRAM      : ${TOKEN_PROGRAM_ID}`);
-    console.log(`program.programId  : ${program.programId}`);
-    console.log(`Creator Address    : ${creator.publicKey}`);
-    console.log(`CreatorToken       : ${creatorToken}`);
-    console.log(``);
-    console.log(`Payer Address      : ${payer.publicKey}`);
-    console.log(`Program Address    : ${program.programId}`);
-    console.log(`Auth Address       : ${auth}`);
-    console.log(`Blink Address      : ${blink}`);
-    console.log(`Submit Address     : ${submit}`);
-    console.log(`CreatorToken       : ${creatorToken}`);
-    console.log(`Vault Address      : ${vault}`);
-    console.log(`Config Address   ->: ${config}`);
-    */
-  });
-  const configInfo = async () => {
-    console.log(`config info`);
-    const configInfo = await program.account.blinkConfig.fetch(config);
-    console.log(`index     : ${configInfo.index}`);
-    console.log(`pic       : ${configInfo.pic}`);
-    console.log(`content   : ${configInfo.content}`);
-    console.log(`option1   : ${configInfo.option1}`);
-    console.log(`option2   : ${configInfo.option2}`);
-    console.log(`option3   : ${configInfo.option3}`);
-    console.log(`option4   : ${configInfo.option4}`);
-  };
-  const blinkInfo = async () => {
-    console.log(`blink info`);
-    const blinkInfo = await program.account.blinkState.fetch(blink);
-    console.log(`index      : ${blinkInfo.index}`);
-    console.log(`closed     : ${blinkInfo.closed}`);
-    console.log(`amount     : ${blinkInfo.amount}`);
-    console.log(`answer     : ${blinkInfo.answer}`);
-    console.log(`reward     : ${blinkInfo.reward}`);
-    console.log(`right1     : ${blinkInfo.right1}`);
-    console.log(`right2     : ${blinkInfo.right2}`);
-    console.log(`right3     : ${blinkInfo.right3}`);
-    console.log(`right4     : ${blinkInfo.right4}`);
-  };
-  const submitInfo = async () => {
-    console.log(`submit info`);
-    const submitInfo = await program.account.submitState.fetch(submit);
-    console.log(`index     : ${submitInfo.index}`);
-    console.log(`answer    : ${submitInfo.answer}`);
-    console.log(`claim     : ${submitInfo.claim}`);
-    console.log(`user      : ${submitInfo.user}`);
-  };
-
-  it("update func", async () => {
-    await program.methods
-      .updateTime(openTime, period)
-      .accounts({
-        owner: owner.publicKey,
-        timeConfig: timeConfig,
-      })
-      .signers([owner])
-      .rpc()
-      .then(confirm);
-  });
-
-  it("Errot Init Test", async () => {
-    try {
-      await program.methods
-        .initialize(index, amount, "pic", "content", "op1", "op2", "op3", "op4")
-        .accounts({
-          creator: creator.publicKey,
-          authority: auth,
-          timeConfig: timeConfig,
-          blinkConfig: config,
-          blinkState: blink,
-          tokenMint: tokenMint,
-          creatorToken: creatorToken,
-          vault: vault,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
-        .signers([user])
-        .rpc()
-        .then(confirm);
-    } catch (error) {
-      console.log("\nError user======");
-      console.log(error);
-    }
-  });
-
-  it("initialize func", async () => {
-    await program.methods
-      .initialize(index, amount, "pic", "content", "op1", "op2", "op3", "op4")
-      .accounts({
-        creator: creator.publicKey,
-        authority: auth,
-        timeConfig: timeConfig,
-        blinkConfig: config,
-        blinkState: blink,
-        tokenMint: tokenMint,
-        creatorToken: creatorToken,
-        vault: vault,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      })
-      .signers([creator])
-      .rpc()
-      .then(confirm);
-  });
-
-  it("Read config and blink data", async () => {
-    await configInfo();
-    await blinkInfo();
-  });
-
-  it("Errot Init Test", async () => {
-    try {
-      await program.methods
-        .initialize(index, amount, "pic", "content", "op1", "op2", "op3", "op4")
-        .accounts({
-          creator: creator.publicKey,
-          authority: auth,
-          timeConfig: timeConfig,
-          blinkConfig: config,
-          blinkState: blink,
-          tokenMint: tokenMint,
-          creatorToken: creatorToken,
-          vault: vault,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
-        .signers([creator])
-        .rpc()
-        .then(confirm);
-    } catch (error) {
-      console.log("\nError index======");
-      console.log(error);
-    }
-  });
-
-  it("Errot Submit Test", async () => {
-    try {
-      await program.methods
-        .submit(index, answer)
-        .accounts({
-          user: user.publicKey,
-          submitState: submit,
-          blinkState: blink,
-        })
-        .signers([creator])
-        .rpc()
-        .then(confirm);
-    } catch (error) {
-      console.log("\nError submit======");
-      console.log(error);
-    }
-  });
-
-  it("Errot submit func", async () => {
-    try {
-      await program.methods
-        .submit(index, answer)
-        .accounts({
-          user: user.publicKey,
-          submitState: submit,
-          blinkState: blink,
-        })
-        .signers([user])
-        .rpc()
-        .then(confirm);
-    } catch (error) {
-      console.log("\nError submit======");
-      console.log(error);
-    }
-  });
-
-  it("submit func", async () => {
-    await sleep(60);
-    await program.methods
-      .submit(index, answer)
-      .accounts({
-        user: user.publicKey,
-        submitState: submit,
-        blinkState: blink,
-      })
-      .signers([user])
-      .rpc()
-      .then(confirm);
-  });
-
-  it("Read submit data", async () => {
-    await submitInfo();
-  });
-
-  it("Errot Submit Test", async () => {
-    try {
-      await program.methods
-        .submit(index, answer)
-        .accounts({
-          user: user.publicKey,
-          submitState: submit,
-          blinkState: blink,
-        })
-        .signers([user])
-        .rpc()
-        .then(confirm);
-    } catch (error) {
-      console.log("\nError submit======");
-      console.log(error);
-    }
-  });
-
-  it("Errot Close Test", async () => {
-    try {
-      await program.methods
-        .close(index, answer)
-        .accounts({
-          owner: user.publicKey,
-          blinkState: blink,
-        })
-        .signers([user])
-        .rpc()
-        .then(confirm);
-    } catch (error) {
-      console.log("\nError close======");
-      console.log(error);
-    }
-  });
-
-  it("Errot close func", async () => {
-    try {
-      await program.methods
-        .close(index, answer)
-        .accounts({
-          owner: creator.publicKey,
-          blinkState: blink,
-        })
-        .signers([creator])
-        .rpc()
-        .then(confirm);
-    } catch (error) {
-      console.log("\nError close======");
-      console.log(error);
-    }
-  });
-
-  it("close func", async () => {
-    await sleep(600);
-    await program.methods
-      .close(index, answer)
-      .accounts({
-        payer: creator.publicKey,
-        blinkState: blink,
-      })
-      .signers([creator])
-      .rpc()
-      .then(confirm);
-  });
-
-  it("Read blink  data", async () => {
-    await blinkInfo();
-  });
-
-  it("Errot Close Test", async () => {
-    try {
-      await program.methods
-        .close(index, answer)
-        .accounts({
-          payer: creator.publicKey,
-          blinkState: blink,
-        })
-        .signers([creator])
-        .rpc()
-        .then(confirm);
-    } catch (error) {
-      console.log("\nError close======");
-      console.log(error);
-    }
-  });
-
-  it("Errot Claim Test", async () => {
-    try {
-      await program.methods
-        .claim(index)
-        .accounts({
-          user: user.publicKey,
-          submitState: submit,
-          blink_state: blink,
-          authority: auth,
-          user_account: userToken,
-          vault: vault,
-          tokenMint: tokenMint,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
-        .signers([creator])
-        .rpc()
-        .then(confirm);
-    } catch (error) {
-      console.log("\nError claim======");
-      console.log(error);
-    }
-  });
-
-  it("claim func", async () => {
-    await program.methods
-      .claim(index)
-      .accounts({
-        user: user.publicKey,
-        submitState: submit,
-        blink_state: blink,
-        authority: auth,
-        user_account: userToken,
-        vault: vault,
-        tokenMint: tokenMint,
-        tokenProgram: TOKEN_PROGRAM_ID,
-      })
-      .signers([user])
-      .rpc()
-      .then(confirm);
-  });
-
-  it("Read binkState and sumbit data", async () => {
-    await blinkInfo();
-    await submitInfo();
-  });
-  it("Errot Claim Test", async () => {
-    try {
-      await program.methods
-        .claim(index)
-        .accounts({
-          user: user.publicKey,
-          submitState: submit,
-          blink_state: blink,
-          authority: auth,
-          user_account: userToken,
-          vault: vault,
-          tokenMint: tokenMint,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
-        .signers([user])
-        .rpc()
-        .then(confirm);
-    } catch (error) {
-      console.log("\nError claim======");
-      console.log(error);
-    }
-  });
-});
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  getAuthAddress,
+  getBlinkAddress,
+  getConfigAddress,
+  getGlobalAddress,
+  getSplTokenAddress,
+  getSubmitAddress,
+  setupGlobal,
+  setWindow,
+  waitUntil,
+  newWallet,
+  createTokenMint,
+  mintTokens,
+  nextIndex,
+  initializeAccounts,
+  submitBlink,
+  closeBlink,
+  claimBlink,
+  tokenBalance,
+  expectError,
+  wallet,
+} from "./utils";
+import { BN } from "@coral-xyz/anchor";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+
+describe("blink start", () => {
+  // Configure the client to use the local cluster.
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const provider = program.provider as anchor.AnchorProvider;
+
+  let index: BN;
+  const answer = 3;
+  const amount = new BN(10 ** 9);
+
+  let tokenMint: PublicKey;
+  let creator: Keypair, user: Keypair, loser: Keypair;
+  let auth: PublicKey, config: PublicKey, blink: PublicKey, submit: PublicKey;
+  let vault: PublicKey, userToken: PublicKey;
+  let openTime: number, closeTime: number;
+
+  before(async () => {
+    await setupGlobal(program);
+    const admin = await program.account.globalConfig.fetch(
+      getGlobalAddress(program.programId)[0]
+    );
+    expect(admin.admin.toBase58()).to.equal(
+      wallet(program).publicKey.toBase58()
+    );
+
+    creator = await newWallet(provider.connection);
+    user = await newWallet(provider.connection);
+    loser = await newWallet(provider.connection);
+    tokenMint = await createTokenMint(program);
+    await mintTokens(program, tokenMint, creator.publicKey, 10 * 10 ** 9);
+
+    index = await nextIndex(program);
+    [auth] = getAuthAddress(program.programId);
+    [config] = getConfigAddress(index, program.programId);
+    [blink] = getBlinkAddress(index, program.programId);
+    [submit] = getSubmitAddress(index, user.publicKey, program.programId);
+    vault = getSplTokenAddress(tokenMint, auth, TOKEN_PROGRAM_ID);
+    userToken = getSplTokenAddress(
+      tokenMint,
+      user.publicKey,
+      TOKEN_PROGRAM_ID
+    );
+  });
+
+  it("update func", async () => {
+    [openTime, closeTime] = await setWindow(program, 8, 8);
+  });
+
+  it("rejects an initialize the creator did not sign", async () => {
+    try {
+      await program.methods
+        .initialize(amount, "pic", "content", "op1", "op2", "op3", "op4")
+        .accountsPartial(
+          initializeAccounts(program, creator.publicKey, index, tokenMint)
+        )
+        .signers([user])
+        .rpc();
+      expect.fail("initialize without the creator's signature");
+    } catch (error) {
+      expect(`${error}`).to.include("unknown signer");
+    }
+  });
+
+  it("initialize func", async () => {
+    await program.methods
+      .initialize(amount, "pic", "content", "op1", "op2", "op3", "op4")
+      .accountsPartial(
+        initializeAccounts(program, creator.publicKey, index, tokenMint)
+      )
+      .signers([creator])
+      .rpc();
+
+    const configInfo = await program.account.blinkConfig.fetch(config);
+    expect(configInfo.index.eq(index)).to.be.true;
+    expect(configInfo.content).to.equal("content");
+    const blinkInfo = await program.account.blinkState.fetch(blink);
+    expect(blinkInfo.amount.eq(amount)).to.be.true;
+    expect(blinkInfo.openTime.toNumber()).to.equal(openTime);
+    expect(blinkInfo.closeTime.toNumber()).to.equal(closeTime);
+    expect(await tokenBalance(provider.connection, vault)).to.equal(
+      amount.toNumber()
+    );
+  });
+
+  it("rejects initializing the same index again", async () => {
+    await expectError(
+      program.methods
+        .initialize(amount, "pic", "content", "op1", "op2", "op3", "op4")
+        .accountsPartial(
+          initializeAccounts(program, creator.publicKey, index, tokenMint)
+        )
+        .signers([creator])
+        .rpc(),
+      "ConstraintSeeds"
+    );
+  });
+
+  it("rejects a submit before the blink opens", async () => {
+    await expectError(
+      submitBlink(program, user, index, answer).rpc(),
+      "InvalidOpenTime"
+    );
+  });
+
+  it("submit func", async () => {
+    await waitUntil(provider.connection, openTime);
+    await submitBlink(program, user, index, answer).rpc();
+
+    const submitInfo = await program.account.submitState.fetch(submit);
+    expect(submitInfo.index.eq(index)).to.be.true;
+    expect(submitInfo.answer).to.equal(answer);
+    expect(submitInfo.claim).to.be.false;
+    expect(submitInfo.user.toBase58()).to.equal(user.publicKey.toBase58());
+    await submitBlink(program, loser, index, 1).rpc();
+    const blinkInfo = await program.account.blinkState.fetch(blink);
+    expect(blinkInfo.right1).to.equal(1);
+    expect(blinkInfo.right3).to.equal(1);
+  });
+
+  it("rejects a second submit", async () => {
+    await expectError(
+      submitBlink(program, user, index, answer).rpc(),
+      "already in use"
+    );
+  });
+
+  it("rejects a close before the close time", async () => {
+    await expectError(
+      closeBlink(program, creator, index, answer).rpc(),
+      "InvalidCloseTime"
+    );
+  });
+
+  it("rejects a close by anyone but the creator", async () => {
+    await waitUntil(provider.connection, closeTime);
+    await expectError(
+      closeBlink(program, user, index, answer).rpc(),
+      "InvalidResolver"
+    );
+  });
+
+  it("close func", async () => {
+    await closeBlink(program, creator, index, answer).rpc();
+
+    const blinkInfo = await program.account.blinkState.fetch(blink);
+    expect(blinkInfo.closed).to.be.true;
+    expect(blinkInfo.answer).to.equal(answer);
+    expect(blinkInfo.reward.eq(amount)).to.be.true;
+  });
+
+  it("rejects closing again", async () => {
+    await expectError(
+      closeBlink(program, creator, index, answer).rpc(),
+      "CloseAlready"
+    );
+  });
+
+  it("rejects a claim on a wrong answer", async () => {
+    await expectError(
+      claimBlink(program, loser, index, tokenMint).rpc(),
+      "InvalidClaim"
+    );
+  });
+
+  it("claim func", async () => {
+    await claimBlink(program, user, index, tokenMint).rpc();
+
+    expect(await tokenBalance(provider.connection, userToken)).to.equal(
+      amount.toNumber()
+    );
+    const submitInfo = await program.account.submitState.fetch(submit);
+    expect(submitInfo.claim).to.be.true;
+  });
+
+  it("rejects claiming again", async () => {
+    await expectError(
+      claimBlink(program, user, index, tokenMint).rpc(),
+      "ClaimAlready"
+    );
+  });
+});
@@ -0,0 +1,123 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTokenMint,
+  expectError,
+  initializeAccounts,
+  mintTokens,
+  newWallet,
+  nextIndex,
+  setupGlobal,
+  setWindow,
+} from "./utils";
+
+const URI = "https://blink.test/metadata/1.json";
+
+describe("metadata", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+  const hash = new Array(32).fill(0).map((_, i) => i);
+
+  let creator: Keypair;
+  let tokenMint: PublicKey;
+  let index: BN;
+  let accounts: ReturnType<typeof initializeAccounts>;
+
+  const initializeWithMetadata = (uri: string) =>
+    program.methods
+      .initializeWithMetadata(new BN(1000), uri, hash)
+      .accountsPartial(accounts)
+      .signers([creator])
+      .rpc();
+
+  before(async () => {
+    await setupGlobal(program);
+    creator = await newWallet(connection);
+    tokenMint = await createTokenMint(program);
+    await mintTokens(program, tokenMint, creator.publicKey, 10 ** 9);
+    await setWindow(program, 600, 60);
+
+    index = await nextIndex(program);
+    accounts = initializeAccounts(program, creator.publicKey, index, tokenMint);
+  });
+
+  it("rejects an empty uri", async () => {
+    await expectError(initializeWithMetadata(""), "InvalidUri");
+  });
+
+  it("rejects a uri over the length limit", async () => {
+    await expectError(
+      initializeWithMetadata("https://" + "x".repeat(200)),
+      "ContentTooLong"
+    );
+  });
+
+  it("creates a blink pointing at a metadata document", async () => {
+    await initializeWithMetadata(URI);
+
+    const config = await program.account.blinkConfig.fetch(
+      accounts.blinkConfig
+    );
+    expect(config.index.eq(index)).to.be.true;
+    expect(config.uri).to.equal(URI);
+    expect(config.metadataHash).to.deep.equal(hash);
+    expect(config.content).to.equal("");
+    expect(config.option1).to.equal("");
+    expect((await nextIndex(program)).eq(index.addn(1))).to.be.true;
+  });
+
+  it("switches inline content to a metadata document", async () => {
+    await program.methods
+      .updateConfig(index, "pic", "inline", "a", "b", "c", "d")
+      .accountsPartial({
+        creator: creator.publicKey,
+        globalConfig: accounts.globalConfig,
+        blinkConfig: accounts.blinkConfig,
+        blinkState: accounts.blinkState,
+      })
+      .signers([creator])
+      .rpc();
+
+    const updated = new Array(32).fill(9);
+    await program.methods
+      .updateMetadata(index, URI + "?v=2", updated)
+      .accountsPartial({
+        creator: creator.publicKey,
+        globalConfig: accounts.globalConfig,
+        blinkConfig: accounts.blinkConfig,
+        blinkState: accounts.blinkState,
+      })
+      .signers([creator])
+      .rpc();
+
+    const config = await program.account.blinkConfig.fetch(
+      accounts.blinkConfig
+    );
+    expect(config.uri).to.equal(URI + "?v=2");
+    expect(config.metadataHash).to.deep.equal(updated);
+    expect(config.pic).to.equal("");
+    expect(config.content).to.equal("");
+    expect(config.option4).to.equal("");
+  });
+
+  it("rejects an empty uri on update", async () => {
+    await expectError(
+      program.methods
+        .updateMetadata(index, "", hash)
+        .accountsPartial({
+          creator: creator.publicKey,
+          globalConfig: accounts.globalConfig,
+          blinkConfig: accounts.blinkConfig,
+          blinkState: accounts.blinkState,
+        })
+        .signers([creator])
+        .rpc(),
+      "InvalidUri"
+    );
+  });
+});
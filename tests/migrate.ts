@@ -0,0 +1,157 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  accountExist,
+  expectError,
+  getBlinkAddress,
+  getConfigAddress,
+  getGlobalAddress,
+  getLegacyBlinkAddress,
+  getLegacyConfigAddress,
+  getLegacySubmitAddress,
+  getSubmitAddress,
+  getTimeAddress,
+  newWallet,
+  setupGlobal,
+} from "./utils";
+
+// must match the accounts in tests/fixtures, loaded through Anchor.toml
+const LEGACY_INDEX = 7;
+const LEGACY_CREATOR = new PublicKey(Buffer.alloc(32, 1));
+const LEGACY_USER = new PublicKey(Buffer.alloc(32, 2));
+
+describe("migrate", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+  const { programId } = program;
+  const [globalConfig] = getGlobalAddress(programId);
+  const [blinkConfig] = getConfigAddress(LEGACY_INDEX, programId);
+  const [blinkState] = getBlinkAddress(LEGACY_INDEX, programId);
+  const [legacyConfig] = getLegacyConfigAddress(LEGACY_INDEX, programId);
+  const [legacyState] = getLegacyBlinkAddress(LEGACY_INDEX, programId);
+
+  let payer: Keypair;
+
+  const migrateBlink = () =>
+    program.methods
+      .migrateBlinkState(LEGACY_INDEX)
+      .accountsPartial({
+        payer: payer.publicKey,
+        globalConfig,
+        legacyBlinkConfig: legacyConfig,
+        legacyBlinkState: legacyState,
+        blinkConfig,
+        blinkState,
+      })
+      .signers([payer])
+      .rpc();
+
+  const migrateSubmit = (user: PublicKey) =>
+    program.methods
+      .migrateSubmitState(LEGACY_INDEX, user)
+      .accountsPartial({
+        payer: payer.publicKey,
+        globalConfig,
+        legacySubmitState: getLegacySubmitAddress(
+          LEGACY_INDEX,
+          user,
+          programId
+        )[0],
+        blinkState,
+        submitState: getSubmitAddress(LEGACY_INDEX, user, programId)[0],
+      })
+      .signers([payer])
+      .rpc();
+
+  before(async () => {
+    await setupGlobal(program);
+    payer = await newWallet(connection);
+    expect(await accountExist(connection, legacyState)).to.be.true;
+  });
+
+  it("rejects a time config already on the current layout", async () => {
+    await expectError(
+      program.methods
+        .migrateTimeConfig()
+        .accountsPartial({
+          payer: payer.publicKey,
+          globalConfig,
+          timeConfig: getTimeAddress(programId)[0],
+        })
+        .signers([payer])
+        .rpc(),
+      "InvalidVersion"
+    );
+  });
+
+  it("rejects migrating a submit before its blink", async () => {
+    await expectError(migrateSubmit(LEGACY_USER), "AccountNotInitialized");
+  });
+
+  it("moves a legacy blink to its u64 index", async () => {
+    const before = await connection.getBalance(payer.publicKey);
+    await migrateBlink();
+
+    const config = await program.account.blinkConfig.fetch(blinkConfig);
+    expect(config.index.toNumber()).to.equal(LEGACY_INDEX);
+    expect(config.creator.toBase58()).to.equal(LEGACY_CREATOR.toBase58());
+    expect(config.content).to.equal("legacy");
+    expect(config.option4).to.equal("op4");
+
+    const state = await program.account.blinkState.fetch(blinkState);
+    expect(state.index.toNumber()).to.equal(LEGACY_INDEX);
+    expect(state.blinkConfig.toBase58()).to.equal(blinkConfig.toBase58());
+    expect(state.closed).to.be.true;
+    expect(state.frozen).to.be.false;
+    expect(state.answer).to.equal(2);
+    expect(state.right2).to.equal(2);
+    expect(state.amount.toNumber()).to.equal(1000);
+    expect(state.reward.toNumber()).to.equal(500);
+    expect(state.openTime.toNumber()).to.equal(1_700_000_000);
+    expect(state.closeTime.toNumber()).to.equal(1_700_000_600);
+
+    // the legacy rent pays most of the new accounts
+    expect(await accountExist(connection, legacyConfig)).to.be.false;
+    expect(await accountExist(connection, legacyState)).to.be.false;
+    const after = await connection.getBalance(payer.publicKey);
+    expect(after).to.be.greaterThan(before);
+  });
+
+  it("rejects migrating the same blink twice", async () => {
+    await expectError(migrateBlink(), "InvalidOwner");
+  });
+
+  it("moves a legacy submit to its u64 index", async () => {
+    await migrateSubmit(LEGACY_USER);
+
+    const [submitState] = getSubmitAddress(
+      LEGACY_INDEX,
+      LEGACY_USER,
+      programId
+    );
+    const submit = await program.account.submitState.fetch(submitState);
+    expect(submit.index.toNumber()).to.equal(LEGACY_INDEX);
+    expect(submit.blinkState.toBase58()).to.equal(blinkState.toBase58());
+    expect(submit.user.toBase58()).to.equal(LEGACY_USER.toBase58());
+    expect(submit.answer).to.equal(2);
+    expect(submit.claim).to.be.false;
+    const [legacySubmit] = getLegacySubmitAddress(
+      LEGACY_INDEX,
+      LEGACY_USER,
+      programId
+    );
+    expect(await accountExist(connection, legacySubmit)).to.be.false;
+  });
+
+  it("rejects a user without a legacy submit", async () => {
+    await expectError(
+      migrateSubmit(Keypair.generate().publicKey),
+      "InvalidOwner"
+    );
+  });
+});
@@ -0,0 +1,116 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  BlinkAccounts,
+  createBlink,
+  createTokenMint,
+  expectError,
+  getGlobalAddress,
+  mintTokens,
+  newWallet,
+  setupGlobal,
+  setWindow,
+  submitBlink,
+  waitUntil,
+  wallet,
+} from "./utils";
+
+describe("pause", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+  const [globalConfig] = getGlobalAddress(program.programId);
+
+  let guardian: Keypair, creator: Keypair, user: Keypair, stranger: Keypair;
+  let tokenMint: PublicKey;
+  let blink: BlinkAccounts;
+
+  const setPaused = (signer: Keypair, paused: boolean) =>
+    (paused ? program.methods.pause() : program.methods.unpause())
+      .accountsPartial({ pauseGuardian: signer.publicKey, globalConfig })
+      .signers([signer])
+      .rpc();
+
+  const freeze = (signer: Keypair, frozen: boolean) =>
+    program.methods
+      .freezeBlink(blink.index, frozen)
+      .accountsPartial({
+        pauseGuardian: signer.publicKey,
+        globalConfig,
+        blinkState: blink.blinkState,
+      })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    await setupGlobal(program);
+    guardian = wallet(program);
+    creator = await newWallet(connection);
+    user = await newWallet(connection);
+    stranger = await newWallet(connection);
+    tokenMint = await createTokenMint(program);
+    await mintTokens(program, tokenMint, creator.publicKey, 10 ** 9);
+
+    const [openTime] = await setWindow(program, 2, 120);
+    blink = await createBlink(program, creator, tokenMint, new BN(1000));
+    await waitUntil(connection, openTime);
+  });
+
+  // leave the program running for the other suites whatever happens here
+  after(async () => {
+    if ((await program.account.globalConfig.fetch(globalConfig)).paused) {
+      await setPaused(guardian, false);
+    }
+  });
+
+  it("rejects a pause by anyone but the guardian", async () => {
+    await expectError(setPaused(stranger, true), "ConstraintHasOne");
+  });
+
+  it("stops user instructions while paused", async () => {
+    await setPaused(guardian, true);
+    const config = await program.account.globalConfig.fetch(globalConfig);
+    expect(config.paused).to.be.true;
+
+    await expectError(
+      submitBlink(program, user, blink.index, 1).rpc(),
+      "Paused"
+    );
+    await expectError(
+      createBlink(program, creator, tokenMint, new BN(1000)),
+      "Paused"
+    );
+    await expectError(setWindow(program, 60, 60), "Paused");
+  });
+
+  it("resumes once unpaused", async () => {
+    await setPaused(guardian, false);
+    await submitBlink(program, user, blink.index, 1).rpc();
+  });
+
+  it("rejects a freeze by anyone but the guardian", async () => {
+    await expectError(freeze(stranger, true), "ConstraintHasOne");
+  });
+
+  it("stops a frozen blink until it is thawed", async () => {
+    await freeze(guardian, true);
+    const state = await program.account.blinkState.fetch(blink.blinkState);
+    expect(state.frozen).to.be.true;
+
+    const other = await newWallet(connection);
+    await expectError(
+      submitBlink(program, other, blink.index, 2).rpc(),
+      "Paused"
+    );
+
+    await freeze(guardian, false);
+    await submitBlink(program, other, blink.index, 2).rpc();
+    const right2 = (await program.account.blinkState.fetch(blink.blinkState))
+      .right2;
+    expect(right2).to.equal(1);
+  });
+});
@@ -0,0 +1,115 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  BlinkAccounts,
+  claimBlink,
+  closeBlink,
+  createBlink,
+  createTokenMint,
+  expectError,
+  getGlobalAddress,
+  getProfileAddress,
+  mintTokens,
+  newWallet,
+  setupGlobal,
+  setWindow,
+  submitBlink,
+  waitUntil,
+} from "./utils";
+
+describe("profile", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+
+  let creator: Keypair, sponsor: Keypair, alice: Keypair, bob: Keypair;
+  let tokenMint: PublicKey;
+  let right: BlinkAccounts, wrong: BlinkAccounts;
+  let openTime: number, closeTime: number;
+
+  const createProfile = (user: Keypair, payer: Keypair) =>
+    program.methods
+      .createProfile()
+      .accountsPartial({
+        user: user.publicKey,
+        payer: payer.publicKey,
+        globalConfig: getGlobalAddress(program.programId)[0],
+        userProfile: getProfileAddress(user.publicKey, program.programId)[0],
+      })
+      .signers(user === payer ? [user] : [user, payer])
+      .rpc();
+
+  const fetchProfile = (user: Keypair) =>
+    program.account.userProfile.fetch(
+      getProfileAddress(user.publicKey, program.programId)[0]
+    );
+
+  before(async () => {
+    await setupGlobal(program);
+    creator = await newWallet(connection);
+    sponsor = await newWallet(connection);
+    alice = await newWallet(connection);
+    bob = await newWallet(connection);
+    tokenMint = await createTokenMint(program);
+    await mintTokens(program, tokenMint, creator.publicKey, 10 ** 9);
+
+    [openTime, closeTime] = await setWindow(program, 4, 4);
+    right = await createBlink(program, creator, tokenMint, new BN(1000));
+    wrong = await createBlink(program, creator, tokenMint, new BN(1000));
+  });
+
+  it("creates an empty profile paid by a sponsor", async () => {
+    await createProfile(alice, sponsor);
+    const profile = await fetchProfile(alice);
+    expect(profile.user.toBase58()).to.equal(alice.publicKey.toBase58());
+    expect(profile.submissions.toNumber()).to.equal(0);
+    expect(profile.correct.toNumber()).to.equal(0);
+    expect(profile.rewards.toNumber()).to.equal(0);
+  });
+
+  it("rejects a second profile for the same user", async () => {
+    await expectError(createProfile(alice, alice), "already in use");
+  });
+
+  it("counts submissions", async () => {
+    await waitUntil(connection, openTime);
+    await submitBlink(program, alice, right.index, 1, { profile: true }).rpc();
+    await submitBlink(program, alice, wrong.index, 1, { profile: true }).rpc();
+
+    const profile = await fetchProfile(alice);
+    expect(profile.submissions.toNumber()).to.equal(2);
+    expect(profile.lastIndex.eq(wrong.index)).to.be.true;
+  });
+
+  it("rejects someone else's profile", async () => {
+    await createProfile(bob, bob);
+    await expectError(
+      submitBlink(program, bob, right.index, 1)
+        .accountsPartial({
+          userProfile: getProfileAddress(alice.publicKey, program.programId)[0],
+        })
+        .rpc(),
+      "ConstraintSeeds"
+    );
+    expect((await fetchProfile(alice)).submissions.toNumber()).to.equal(2);
+  });
+
+  it("counts right answers and rewards on claim", async () => {
+    await waitUntil(connection, closeTime);
+    await closeBlink(program, creator, right.index, 1).rpc();
+    await closeBlink(program, creator, wrong.index, 2).rpc();
+    await claimBlink(program, alice, right.index, tokenMint, {
+      profile: true,
+    }).rpc();
+
+    const profile = await fetchProfile(alice);
+    expect(profile.submissions.toNumber()).to.equal(2);
+    expect(profile.correct.toNumber()).to.equal(1);
+    expect(profile.rewards.toNumber()).to.equal(1000);
+    expect(profile.lastIndex.eq(right.index)).to.be.true;
+  });
+});
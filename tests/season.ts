@@ -0,0 +1,221 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  BlinkAccounts,
+  chainTime,
+  closeBlink,
+  createBlink,
+  createTokenMint,
+  expectError,
+  getAuthAddress,
+  getBlinkAddress,
+  getGlobalAddress,
+  getScoreMarkerAddress,
+  getSeasonAddress,
+  getSeasonScoreAddress,
+  getSplTokenAddress,
+  getSubmitAddress,
+  mintTokens,
+  newWallet,
+  setupGlobal,
+  setWindow,
+  submitBlink,
+  tokenBalance,
+  waitUntil,
+} from "./utils";
+
+const PRIZE = 10_000;
+
+describe("season", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+  const { programId } = program;
+  const [globalConfig] = getGlobalAddress(programId);
+  const [authority] = getAuthAddress(programId);
+  const id = new BN(Date.now());
+  const [season] = getSeasonAddress(id, programId);
+
+  let owner: Keypair, alice: Keypair, bob: Keypair;
+  let tokenMint: PublicKey;
+  let blink: BlinkAccounts, outside: BlinkAccounts;
+  let endTime: number;
+
+  const createSeason = (seasonId: BN, end: number, topN: number) =>
+    program.methods
+      .createSeason(seasonId, new BN(end), topN, new BN(PRIZE))
+      .accountsPartial({
+        owner: owner.publicKey,
+        globalConfig,
+        season: getSeasonAddress(seasonId, programId)[0],
+        authority,
+        tokenMint,
+        ownerToken: getSplTokenAddress(
+          tokenMint,
+          owner.publicKey,
+          TOKEN_PROGRAM_ID
+        ),
+        vault: getSplTokenAddress(tokenMint, authority, TOKEN_PROGRAM_ID),
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+  const addSeasonBlink = (signer: Keypair, index: BN) =>
+    program.methods
+      .addSeasonBlink(id, index)
+      .accountsPartial({ owner: signer.publicKey, globalConfig, season })
+      .signers([signer])
+      .rpc();
+
+  const score = (index: BN, user: PublicKey) =>
+    program.methods
+      .score(id, index, user)
+      .accountsPartial({
+        payer: program.provider.publicKey,
+        globalConfig,
+        season,
+        blinkState: getBlinkAddress(index, programId)[0],
+        submitState: getSubmitAddress(index, user, programId)[0],
+        scoreMarker: getScoreMarkerAddress(id, index, user, programId)[0],
+        seasonScore: getSeasonScoreAddress(id, user, programId)[0],
+      })
+      .rpc();
+
+  const payout = (rank: number, winner: PublicKey) =>
+    program.methods
+      .payoutSeason(id, rank)
+      .accountsPartial({
+        payer: program.provider.publicKey,
+        globalConfig,
+        season,
+        authority,
+        winner,
+        winnerToken: getSplTokenAddress(tokenMint, winner, TOKEN_PROGRAM_ID),
+        vault: getSplTokenAddress(tokenMint, authority, TOKEN_PROGRAM_ID),
+        tokenMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+  before(async () => {
+    await setupGlobal(program);
+    owner = await newWallet(connection);
+    alice = await newWallet(connection);
+    bob = await newWallet(connection);
+    tokenMint = await createTokenMint(program);
+    await mintTokens(program, tokenMint, owner.publicKey, 10 ** 9);
+
+    const [openTime, closeTime] = await setWindow(program, 2, 4);
+    blink = await createBlink(program, owner, tokenMint, new BN(1000));
+    outside = await createBlink(program, owner, tokenMint, new BN(1000));
+    await waitUntil(connection, openTime);
+    for (const target of [blink, outside]) {
+      await submitBlink(program, alice, target.index, 1).rpc();
+      await submitBlink(program, bob, target.index, 2).rpc();
+    }
+    await waitUntil(connection, closeTime);
+    for (const target of [blink, outside]) {
+      await closeBlink(program, owner, target.index, 1).rpc();
+    }
+  });
+
+  it("rejects an empty or oversized leaderboard", async () => {
+    const end = (await chainTime(connection)) + 60;
+    await expectError(createSeason(id.addn(1), end, 0), "InvalidRank");
+    await expectError(createSeason(id.addn(1), end, 11), "InvalidRank");
+  });
+
+  it("rejects a season that has already ended", async () => {
+    const now = await chainTime(connection);
+    await expectError(createSeason(id.addn(1), now, 3), "InvalidCloseTime");
+  });
+
+  it("creates a season holding the prize", async () => {
+    endTime = (await chainTime(connection)) + 20;
+    await createSeason(id, endTime, 3);
+
+    const info = await program.account.season.fetch(season);
+    expect(info.id.eq(id)).to.be.true;
+    expect(info.owner.toBase58()).to.equal(owner.publicKey.toBase58());
+    expect(info.prize.toNumber()).to.equal(PRIZE);
+    expect(info.endTime.toNumber()).to.equal(endTime);
+    expect(info.topN).to.equal(3);
+    expect(info.leaderboard).to.be.empty;
+  });
+
+  it("rejects adding a blink by anyone but the owner", async () => {
+    await expectError(addSeasonBlink(alice, blink.index), "ConstraintHasOne");
+  });
+
+  it("adds a blink to the season", async () => {
+    await addSeasonBlink(owner, blink.index);
+    const info = await program.account.season.fetch(season);
+    expect(info.blinks.map((index) => index.toNumber())).to.deep.equal([
+      blink.index.toNumber(),
+    ]);
+  });
+
+  it("scores a right answer onto the leaderboard", async () => {
+    await score(blink.index, alice.publicKey);
+
+    const [seasonScore] = getSeasonScoreAddress(id, alice.publicKey, programId);
+    const points = await program.account.seasonScore.fetch(seasonScore);
+    expect(points.points.toNumber()).to.equal(1);
+    const info = await program.account.season.fetch(season);
+    expect(info.leaderboard).to.have.length(1);
+    expect(info.leaderboard[0].user.toBase58()).to.equal(
+      alice.publicKey.toBase58()
+    );
+    expect(info.leaderboard[0].points.toNumber()).to.equal(1);
+  });
+
+  it("rejects scoring the same blink twice", async () => {
+    await expectError(score(blink.index, alice.publicKey), "ScoredAlready");
+  });
+
+  it("rejects scoring a wrong answer", async () => {
+    await expectError(score(blink.index, bob.publicKey), "InvalidClaim");
+  });
+
+  it("rejects scoring a blink outside the season", async () => {
+    await expectError(score(outside.index, alice.publicKey), "NotInSeason");
+  });
+
+  it("rejects a payout before the season ends", async () => {
+    await expectError(payout(0, alice.publicKey), "InvalidCloseTime");
+  });
+
+  it("rejects a payout to anyone but the ranked winner", async () => {
+    await waitUntil(connection, endTime);
+    await expectError(payout(0, bob.publicKey), "InvalidRank");
+    await expectError(payout(1, bob.publicKey), "InvalidRank");
+  });
+
+  it("pays the winner their share once", async () => {
+    await payout(0, alice.publicKey);
+    const aliceToken = getSplTokenAddress(
+      tokenMint,
+      alice.publicKey,
+      TOKEN_PROGRAM_ID
+    );
+    // alone on the leaderboard, alice takes the whole prize
+    expect(await tokenBalance(connection, aliceToken)).to.equal(PRIZE);
+    expect((await program.account.season.fetch(season)).paid).to.equal(1);
+
+    await expectError(payout(0, alice.publicKey), "ClaimAlready");
+  });
+
+  it("rejects scoring after the season ends", async () => {
+    await expectError(score(outside.index, bob.publicKey), "InvalidCloseTime");
+  });
+});
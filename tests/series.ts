@@ -0,0 +1,291 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  BlinkAccounts,
+  chainTime,
+  claimBlink,
+  closeBlink,
+  createBlink,
+  createTokenMint,
+  expectError,
+  getAuthAddress,
+  getGlobalAddress,
+  getSeriesAddress,
+  getSplTokenAddress,
+  getStreakAddress,
+  mintTokens,
+  newWallet,
+  setupGlobal,
+  setWindow,
+  submitBlink,
+  tokenBalance,
+  waitUntil,
+} from "./utils";
+
+// each blink pays its two winners 500, a streak step adds half of that
+const BONUS_BPS = 5000;
+const MAX_STREAK = 3;
+const BONUS = 1000;
+
+describe("series", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+  const { programId } = program;
+  const [globalConfig] = getGlobalAddress(programId);
+  const [authority] = getAuthAddress(programId);
+  const id = new BN(Date.now());
+  const [series] = getSeriesAddress(id, programId);
+
+  let owner: Keypair, alice: Keypair, bob: Keypair;
+  let tokenMint: PublicKey;
+  let first: BlinkAccounts, second: BlinkAccounts;
+  let closeTime: number, endTime: number;
+
+  // the bonus is held by the series itself, apart from the shared vault
+  const seriesVault = (seriesId: BN) =>
+    getSplTokenAddress(
+      tokenMint,
+      getSeriesAddress(seriesId, programId)[0],
+      TOKEN_PROGRAM_ID
+    );
+  const sharedVault = () =>
+    getSplTokenAddress(tokenMint, authority, TOKEN_PROGRAM_ID);
+
+  const tokenAccounts = (seriesId: BN, signer: Keypair = owner) => ({
+    tokenMint,
+    ownerToken: getSplTokenAddress(
+      tokenMint,
+      signer.publicKey,
+      TOKEN_PROGRAM_ID
+    ),
+    vault: seriesVault(seriesId),
+    tokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  const createSeries = (
+    seriesId: BN,
+    end: number,
+    bonusBps: number,
+    maxStreak: number
+  ) =>
+    program.methods
+      .createSeries(seriesId, new BN(end), bonusBps, maxStreak, new BN(BONUS))
+      .accountsPartial({
+        owner: owner.publicKey,
+        globalConfig,
+        series: getSeriesAddress(seriesId, programId)[0],
+        ...tokenAccounts(seriesId),
+      })
+      .signers([owner])
+      .rpc();
+
+  const withdrawSeries = (signer: Keypair) =>
+    program.methods
+      .withdrawSeries(id)
+      .accountsPartial({
+        owner: signer.publicKey,
+        globalConfig,
+        series,
+        ...tokenAccounts(id, signer),
+      })
+      .signers([signer])
+      .rpc();
+
+  const addSeriesBlink = (index: BN) =>
+    program.methods
+      .addSeriesBlink(id, index)
+      .accountsPartial({ owner: owner.publicKey, globalConfig, series })
+      .signers([owner])
+      .rpc();
+
+  const userBalance = (user: Keypair) =>
+    tokenBalance(
+      connection,
+      getSplTokenAddress(tokenMint, user.publicKey, TOKEN_PROGRAM_ID)
+    );
+
+  before(async () => {
+    await setupGlobal(program);
+    owner = await newWallet(connection);
+    alice = await newWallet(connection);
+    bob = await newWallet(connection);
+    tokenMint = await createTokenMint(program);
+    await mintTokens(program, tokenMint, owner.publicKey, 10 ** 9);
+
+    let openTime: number;
+    [openTime, closeTime] = await setWindow(program, 2, 6);
+    first = await createBlink(program, owner, tokenMint, new BN(1000));
+    second = await createBlink(program, owner, tokenMint, new BN(1000));
+    await waitUntil(connection, openTime);
+    for (const blink of [first, second]) {
+      await submitBlink(program, alice, blink.index, 3).rpc();
+      await submitBlink(program, bob, blink.index, 3).rpc();
+    }
+  });
+
+  it("rejects a bonus over 100% or an empty streak", async () => {
+    const end = (await chainTime(connection)) + 60;
+    await expectError(
+      createSeries(id.addn(1), end, 10_001, MAX_STREAK),
+      "InvalidSeries"
+    );
+    await expectError(
+      createSeries(id.addn(1), end, BONUS_BPS, 0),
+      "InvalidSeries"
+    );
+  });
+
+  it("rejects a series that has already ended", async () => {
+    const now = await chainTime(connection);
+    await expectError(
+      createSeries(id.addn(1), now, BONUS_BPS, MAX_STREAK),
+      "InvalidCloseTime"
+    );
+  });
+
+  it("creates a series holding the bonus in its own vault", async () => {
+    // long enough for the claims below
+    endTime = (await chainTime(connection)) + 30;
+    await createSeries(id, endTime, BONUS_BPS, MAX_STREAK);
+    const info = await program.account.series.fetch(series);
+    expect(info.owner.toBase58()).to.equal(owner.publicKey.toBase58());
+    expect(info.vault.toBase58()).to.equal(seriesVault(id).toBase58());
+    expect(info.bonus.toNumber()).to.equal(BONUS);
+    expect(info.bonusBps).to.equal(BONUS_BPS);
+    expect(info.maxStreak).to.equal(MAX_STREAK);
+    expect(info.endTime.toNumber()).to.equal(endTime);
+    expect(await tokenBalance(connection, seriesVault(id))).to.equal(BONUS);
+  });
+
+  it("tops up the bonus", async () => {
+    await program.methods
+      .fundSeries(id, new BN(200))
+      .accountsPartial({
+        owner: owner.publicKey,
+        globalConfig,
+        series,
+        ...tokenAccounts(id),
+      })
+      .signers([owner])
+      .rpc();
+    const info = await program.account.series.fetch(series);
+    expect(info.bonus.toNumber()).to.equal(BONUS + 200);
+    expect(await tokenBalance(connection, seriesVault(id))).to.equal(
+      BONUS + 200
+    );
+  });
+
+  it("adds blinks in order and rejects duplicates", async () => {
+    await addSeriesBlink(first.index);
+    await addSeriesBlink(second.index);
+    await expectError(addSeriesBlink(first.index), "InvalidIndex");
+
+    const info = await program.account.series.fetch(series);
+    expect(info.blinks.map((index) => index.toNumber())).to.deep.equal([
+      first.index.toNumber(),
+      second.index.toNumber(),
+    ]);
+  });
+
+  it("creates streaks for the users", async () => {
+    for (const user of [alice, bob]) {
+      await program.methods
+        .createStreak(id)
+        .accountsPartial({
+          user: user.publicKey,
+          payer: user.publicKey,
+          globalConfig,
+          series,
+          streak: getStreakAddress(id, user.publicKey, programId)[0],
+        })
+        .signers([user])
+        .rpc();
+    }
+    const [streakAddress] = getStreakAddress(id, bob.publicKey, programId);
+    const streak = await program.account.streak.fetch(streakAddress);
+    expect(streak.user.toBase58()).to.equal(bob.publicKey.toBase58());
+    expect(streak.streak).to.equal(0);
+  });
+
+  it("starts the streak without a bonus", async () => {
+    await waitUntil(connection, closeTime);
+    await closeBlink(program, owner, first.index, 3).rpc();
+    await closeBlink(program, owner, second.index, 3).rpc();
+
+    await claimBlink(program, alice, first.index, tokenMint, {
+      series: id,
+    }).rpc();
+    const [streakAddress] = getStreakAddress(id, alice.publicKey, programId);
+    const streak = await program.account.streak.fetch(streakAddress);
+    expect(streak.streak).to.equal(1);
+    expect(await userBalance(alice)).to.equal(500);
+  });
+
+  it("pays the bonus on consecutive right answers", async () => {
+    const shared = await tokenBalance(connection, sharedVault());
+    await claimBlink(program, alice, second.index, tokenMint, {
+      series: id,
+      streakBlinks: [first.index],
+    }).rpc();
+
+    const [streakAddress] = getStreakAddress(id, alice.publicKey, programId);
+    const streak = await program.account.streak.fetch(streakAddress);
+    expect(streak.streak).to.equal(2);
+    // 500 + 500 * 1 step * 50%
+    expect(await userBalance(alice)).to.equal(500 + 750);
+    const info = await program.account.series.fetch(series);
+    expect(info.bonus.toNumber()).to.equal(BONUS + 200 - 250);
+    // the reward leaves the shared vault, the bonus the series vault
+    expect(await tokenBalance(connection, sharedVault())).to.equal(
+      shared - 500
+    );
+    expect(await tokenBalance(connection, seriesVault(id))).to.equal(
+      BONUS + 200 - 250
+    );
+  });
+
+  it("rejects a streak passed without its series", async () => {
+    await expectError(
+      claimBlink(program, bob, second.index, tokenMint, { series: id })
+        .accountsPartial({ series: null })
+        .rpc(),
+      "InvalidSeries"
+    );
+  });
+
+  it("pays no bonus outside the series", async () => {
+    await claimBlink(program, bob, second.index, tokenMint).rpc();
+    expect(await userBalance(bob)).to.equal(500);
+  });
+
+  it("rejects a withdrawal before the series ends", async () => {
+    await expectError(withdrawSeries(owner), "InvalidCloseTime");
+  });
+
+  it("rejects a withdrawal by anyone but the owner", async () => {
+    await waitUntil(connection, endTime);
+    await expectError(withdrawSeries(alice), "ConstraintHasOne");
+  });
+
+  it("returns the rest of the bonus to the owner", async () => {
+    const ownerToken = getSplTokenAddress(
+      tokenMint,
+      owner.publicKey,
+      TOKEN_PROGRAM_ID
+    );
+    const before = await tokenBalance(connection, ownerToken);
+    await withdrawSeries(owner);
+
+    const left = BONUS + 200 - 250;
+    expect(await tokenBalance(connection, ownerToken)).to.equal(before + left);
+    expect(await tokenBalance(connection, seriesVault(id))).to.equal(0);
+    const info = await program.account.series.fetch(series);
+    expect(info.bonus.toNumber()).to.equal(0);
+  });
+});
@@ -0,0 +1,201 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializeTransferHookInstruction,
+  getMintLen,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  claimBlink,
+  closeBlink,
+  createBlink,
+  getGlobalAddress,
+  getProfileAddress,
+  getSplTokenAddress,
+  mintTokens,
+  newWallet,
+  nextIndex,
+  setupGlobal,
+  setWindow,
+  submitBlink,
+  tokenBalance,
+  waitUntil,
+  wallet,
+} from "./utils";
+
+// 1% up to a fee no test reaches
+const FEE_BPS = 100;
+const MAX_FEE = BigInt(10 ** 12);
+
+describe("token-2022", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+
+  let creator: Keypair, user: Keypair;
+
+  // a Token-2022 mint with the extensions `init` sets up
+  const createMint2022 = async (
+    extensions: ExtensionType[],
+    init: (mint: PublicKey) => TransactionInstruction[]
+  ): Promise<PublicKey> => {
+    const payer = wallet(program);
+    const mint = Keypair.generate();
+    const space = getMintLen(extensions);
+    const tx = new Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: payer.publicKey,
+        newAccountPubkey: mint.publicKey,
+        space,
+        lamports: await connection.getMinimumBalanceForRentExemption(space),
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      ...init(mint.publicKey),
+      createInitializeMintInstruction(
+        mint.publicKey,
+        6,
+        payer.publicKey,
+        null,
+        TOKEN_2022_PROGRAM_ID
+      )
+    );
+    await sendAndConfirmTransaction(connection, tx, [payer, mint]);
+    await mintTokens(
+      program,
+      mint.publicKey,
+      creator.publicKey,
+      10 ** 9,
+      TOKEN_2022_PROGRAM_ID
+    );
+    return mint.publicKey;
+  };
+
+  const createHookMint = (hookProgram: PublicKey) =>
+    createMint2022([ExtensionType.TransferHook], (mint) => [
+      createInitializeTransferHookInstruction(
+        mint,
+        wallet(program).publicKey,
+        hookProgram,
+        TOKEN_2022_PROGRAM_ID
+      ),
+    ]);
+
+  before(async () => {
+    await setupGlobal(program);
+    creator = await newWallet(connection);
+    user = await newWallet(connection);
+  });
+
+  it("funds and pays out a transfer fee mint net of the fee", async () => {
+    const tokenMint = await createMint2022(
+      [ExtensionType.TransferFeeConfig],
+      (mint) => [
+        createInitializeTransferFeeConfigInstruction(
+          mint,
+          wallet(program).publicKey,
+          wallet(program).publicKey,
+          FEE_BPS,
+          MAX_FEE,
+          TOKEN_2022_PROGRAM_ID
+        ),
+      ]
+    );
+    const amount = 100_000;
+    const [openTime, closeTime] = await setWindow(program, 2, 4);
+    const blink = await createBlink(
+      program,
+      creator,
+      tokenMint,
+      new BN(amount),
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // the pot is what reached the vault
+    const pot = amount - (amount * FEE_BPS) / 10_000;
+    let state = await program.account.blinkState.fetch(blink.blinkState);
+    expect(state.amount.toNumber()).to.equal(pot);
+
+    const [userProfile] = getProfileAddress(user.publicKey, program.programId);
+    await program.methods
+      .createProfile()
+      .accountsPartial({
+        user: user.publicKey,
+        payer: user.publicKey,
+        globalConfig: getGlobalAddress(program.programId)[0],
+        userProfile,
+      })
+      .signers([user])
+      .rpc();
+
+    await waitUntil(connection, openTime);
+    await submitBlink(program, user, blink.index, 1, { profile: true }).rpc();
+    await waitUntil(connection, closeTime);
+    await closeBlink(program, creator, blink.index, 1).rpc();
+    state = await program.account.blinkState.fetch(blink.blinkState);
+    expect(state.reward.toNumber()).to.equal(pot);
+
+    await claimBlink(program, user, blink.index, tokenMint, {
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      profile: true,
+    }).rpc();
+    const userToken = getSplTokenAddress(
+      tokenMint,
+      user.publicKey,
+      TOKEN_2022_PROGRAM_ID
+    );
+    // the fee is withheld in the user's account, not paid out
+    const received = pot - (pot * FEE_BPS) / 10_000;
+    expect(await tokenBalance(connection, userToken)).to.equal(received);
+    // the profile counts what the user received
+    const profile = await program.account.userProfile.fetch(userProfile);
+    expect(profile.rewards.toNumber()).to.equal(received);
+  });
+
+  it("accepts a transfer hook mint without a hook program", async () => {
+    const tokenMint = await createHookMint(PublicKey.default);
+    await setWindow(program, 60, 60);
+    const blink = await createBlink(
+      program,
+      creator,
+      tokenMint,
+      new BN(1000),
+      TOKEN_2022_PROGRAM_ID
+    );
+    const state = await program.account.blinkState.fetch(blink.blinkState);
+    expect(state.amount.toNumber()).to.equal(1000);
+    expect(state.tokenMint.toBase58()).to.equal(tokenMint.toBase58());
+  });
+
+  it("rejects a hook mint without the hook's accounts", async () => {
+    const tokenMint = await createHookMint(Keypair.generate().publicKey);
+    const index = await nextIndex(program);
+    let failed = false;
+    try {
+      await createBlink(
+        program,
+        creator,
+        tokenMint,
+        new BN(1000),
+        TOKEN_2022_PROGRAM_ID
+      );
+    } catch (err) {
+      failed = true;
+    }
+    expect(failed).to.be.true;
+    expect((await nextIndex(program)).eq(index)).to.be.true;
+  });
+});
@@ -0,0 +1,134 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  BlinkAccounts,
+  createBlink,
+  createTokenMint,
+  expectError,
+  getGlobalAddress,
+  getTranslationAddress,
+  mintTokens,
+  newWallet,
+  setupGlobal,
+  setWindow,
+  waitUntil,
+} from "./utils";
+
+describe("translation", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+
+  let creator: Keypair, stranger: Keypair;
+  let tokenMint: PublicKey;
+  let blink: BlinkAccounts, opened: BlinkAccounts;
+  let openTime: number;
+
+  const addTranslation = (
+    signer: Keypair,
+    target: BlinkAccounts,
+    lang: string,
+    content = "contenido"
+  ) =>
+    program.methods
+      .addTranslation(target.index, lang, content, "uno", "dos", "tres", "")
+      .accountsPartial({
+        creator: signer.publicKey,
+        globalConfig: getGlobalAddress(program.programId)[0],
+        blinkConfig: target.blinkConfig,
+        blinkState: target.blinkState,
+        blinkTranslation: getTranslationAddress(
+          target.index,
+          lang,
+          program.programId
+        )[0],
+      })
+      .signers([signer])
+      .rpc();
+
+  before(async () => {
+    await setupGlobal(program);
+    creator = await newWallet(connection);
+    stranger = await newWallet(connection);
+    tokenMint = await createTokenMint(program);
+    await mintTokens(program, tokenMint, creator.publicKey, 10 ** 9);
+
+    [openTime] = await setWindow(program, 2, 60);
+    opened = await createBlink(program, creator, tokenMint, new BN(1000));
+    await setWindow(program, 600, 60);
+    blink = await createBlink(program, creator, tokenMint, new BN(1000));
+  });
+
+  it("adds a translation per language", async () => {
+    await addTranslation(creator, blink, "es");
+    await addTranslation(creator, blink, "pt-BR", "conteúdo");
+
+    const [es] = getTranslationAddress(blink.index, "es", program.programId);
+    const translation = await program.account.blinkTranslation.fetch(es);
+    expect(translation.index.eq(blink.index)).to.be.true;
+    expect(translation.lang).to.equal("es");
+    expect(translation.content).to.equal("contenido");
+    expect(translation.option1).to.equal("uno");
+    expect(translation.option4).to.equal("");
+
+    const [pt] = getTranslationAddress(blink.index, "pt-BR", program.programId);
+    expect(
+      (await program.account.blinkTranslation.fetch(pt)).content
+    ).to.equal("conteúdo");
+  });
+
+  it("rejects a language that is already translated", async () => {
+    await expectError(addTranslation(creator, blink, "es"), "already in use");
+  });
+
+  it("rejects malformed language codes", async () => {
+    await expectError(addTranslation(creator, blink, ""), "InvalidLang");
+    await expectError(addTranslation(creator, blink, "en_US"), "InvalidLang");
+    await expectError(
+      addTranslation(creator, blink, "toolonglang"),
+      "InvalidLang"
+    );
+  });
+
+  it("rejects a language code too long to derive an address", async () => {
+    const lang = "x".repeat(33);
+    await expectError(
+      program.methods
+        .addTranslation(blink.index, lang, "contenido", "uno", "dos", "", "")
+        .accountsPartial({
+          creator: creator.publicKey,
+          globalConfig: getGlobalAddress(program.programId)[0],
+          blinkConfig: blink.blinkConfig,
+          blinkState: blink.blinkState,
+          // no address exists for such a seed, any key stands in for it
+          blinkTranslation: Keypair.generate().publicKey,
+        })
+        .signers([creator])
+        .rpc(),
+      "InvalidLang"
+    );
+  });
+
+  it("rejects content over the length limit", async () => {
+    await expectError(
+      addTranslation(creator, blink, "fr", "x".repeat(201)),
+      "ContentTooLong"
+    );
+  });
+
+  it("rejects a translation by anyone but the creator", async () => {
+    await expectError(
+      addTranslation(stranger, blink, "de"),
+      "ConstraintHasOne"
+    );
+  });
+
+  it("rejects a translation once the blink is open", async () => {
+    await waitUntil(connection, openTime);
+    await expectError(addTranslation(creator, opened, "es"), "InvalidOpenTime");
+  });
+});
@@ -0,0 +1,130 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  BlinkAccounts,
+  createBlink,
+  createTokenMint,
+  expectError,
+  getGlobalAddress,
+  mintTokens,
+  newWallet,
+  setupGlobal,
+  setWindow,
+  waitUntil,
+} from "./utils";
+
+describe("update config", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+
+  let creator: Keypair, stranger: Keypair;
+  let tokenMint: PublicKey;
+  let blink: BlinkAccounts, opened: BlinkAccounts;
+  let openTime: number;
+
+  const updateConfig = (
+    signer: Keypair,
+    target: BlinkAccounts,
+    content: string,
+    option1 = "yes"
+  ) =>
+    program.methods
+      .updateConfig(target.index, "pic2", content, option1, "no", "", "")
+      .accountsPartial({
+        creator: signer.publicKey,
+        globalConfig: getGlobalAddress(program.programId)[0],
+        blinkConfig: target.blinkConfig,
+        blinkState: target.blinkState,
+      })
+      .signers([signer])
+      .rpc();
+
+  const configSize = async () =>
+    (await connection.getAccountInfo(blink.blinkConfig)).data.length;
+
+  before(async () => {
+    await setupGlobal(program);
+    creator = await newWallet(connection);
+    stranger = await newWallet(connection);
+    tokenMint = await createTokenMint(program);
+    await mintTokens(program, tokenMint, creator.publicKey, 10 ** 9);
+
+    [openTime] = await setWindow(program, 2, 60);
+    opened = await createBlink(program, creator, tokenMint, new BN(1000));
+    await setWindow(program, 600, 60);
+    blink = await createBlink(program, creator, tokenMint, new BN(1000));
+  });
+
+  it("replaces the content before the blink opens", async () => {
+    const size = await configSize();
+    await updateConfig(creator, blink, "a longer content");
+
+    const config = await program.account.blinkConfig.fetch(blink.blinkConfig);
+    expect(config.pic).to.equal("pic2");
+    expect(config.content).to.equal("a longer content");
+    expect(config.option1).to.equal("yes");
+    expect(config.option2).to.equal("no");
+    expect(config.option3).to.equal("");
+    expect(config.option4).to.equal("");
+    expect(config.uri).to.equal("");
+
+    // the account is resized to the strings: 25 bytes now, 22 before
+    expect(await configSize()).to.equal(size + 3);
+  });
+
+  it("drops the metadata uri", async () => {
+    await program.methods
+      .updateMetadata(
+        blink.index,
+        "https://blink.test/1.json",
+        new Array(32).fill(7)
+      )
+      .accountsPartial({
+        creator: creator.publicKey,
+        globalConfig: getGlobalAddress(program.programId)[0],
+        blinkConfig: blink.blinkConfig,
+        blinkState: blink.blinkState,
+      })
+      .signers([creator])
+      .rpc();
+
+    await updateConfig(creator, blink, "inline again");
+    const config = await program.account.blinkConfig.fetch(blink.blinkConfig);
+    expect(config.content).to.equal("inline again");
+    expect(config.uri).to.equal("");
+    expect(config.metadataHash).to.deep.equal(new Array(32).fill(0));
+  });
+
+  it("rejects content over the length limit", async () => {
+    await expectError(
+      updateConfig(creator, blink, "x".repeat(201)),
+      "ContentTooLong"
+    );
+    await expectError(
+      updateConfig(creator, blink, "content", "x".repeat(101)),
+      "ContentTooLong"
+    );
+  });
+
+  it("rejects an update by anyone but the creator", async () => {
+    await expectError(
+      updateConfig(stranger, blink, "hijacked"),
+      "ConstraintHasOne"
+    );
+  });
+
+  it("rejects an update once the blink is open", async () => {
+    await waitUntil(connection, openTime);
+    await expectError(
+      updateConfig(creator, opened, "too late"),
+      "InvalidOpenTime"
+    );
+    const config = await program.account.blinkConfig.fetch(opened.blinkConfig);
+    expect(config.content).to.equal("content");
+  });
+});
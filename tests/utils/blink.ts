@@ -0,0 +1,402 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import {
+  AccountMeta,
+  Connection,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SYSVAR_CLOCK_PUBKEY,
+} from "@solana/web3.js";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import { Blink } from "../../target/types/blink";
+import {
+  getAuthAddress,
+  getBlinkAddress,
+  getConfigAddress,
+  getCounterAddress,
+  getGlobalAddress,
+  getProfileAddress,
+  getProgramDataAddress,
+  getSeriesAddress,
+  getSplTokenAddress,
+  getStreakAddress,
+  getSubmitAddress,
+  getTimeAddress,
+} from "./pda";
+import { accountExist } from "./web3";
+
+export function wallet(program: Program<Blink>): Keypair {
+  return ((program.provider as anchor.AnchorProvider).wallet as anchor.Wallet)
+    .payer;
+}
+
+export function sleep(ms: number): Promise<void> {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+// unix timestamp of the cluster clock, which the program compares against
+export async function chainTime(connection: Connection): Promise<number> {
+  const clock = await connection.getAccountInfo(SYSVAR_CLOCK_PUBKEY);
+  // slot, epoch_start_timestamp, epoch, leader_schedule_epoch, unix_timestamp
+  return new BN(clock.data.subarray(32, 40), "le").toNumber();
+}
+
+export async function waitUntil(connection: Connection, time: number) {
+  while ((await chainTime(connection)) < time) {
+    await sleep(400);
+  }
+}
+
+export async function airdrop(
+  connection: Connection,
+  to: PublicKey,
+  sol = 10
+): Promise<void> {
+  const signature = await connection.requestAirdrop(
+    to,
+    sol * LAMPORTS_PER_SOL
+  );
+  const block = await connection.getLatestBlockhash();
+  await connection.confirmTransaction({ signature, ...block });
+}
+
+export async function newWallet(
+  connection: Connection,
+  sol = 10
+): Promise<Keypair> {
+  const keypair = Keypair.generate();
+  await airdrop(connection, keypair.publicKey, sol);
+  return keypair;
+}
+
+export async function createTokenMint(
+  program: Program<Blink>,
+  decimals = 6,
+  tokenProgram = TOKEN_PROGRAM_ID
+): Promise<PublicKey> {
+  const payer = wallet(program);
+  return createMint(
+    program.provider.connection,
+    payer,
+    payer.publicKey,
+    null,
+    decimals,
+    undefined,
+    undefined,
+    tokenProgram
+  );
+}
+
+/** Mint `amount` base units into `owner`'s associated account. */
+export async function mintTokens(
+  program: Program<Blink>,
+  tokenMint: PublicKey,
+  owner: PublicKey,
+  amount: number,
+  tokenProgram = TOKEN_PROGRAM_ID
+): Promise<PublicKey> {
+  const payer = wallet(program);
+  const account = await getOrCreateAssociatedTokenAccount(
+    program.provider.connection,
+    payer,
+    tokenMint,
+    owner,
+    true,
+    undefined,
+    undefined,
+    tokenProgram
+  );
+  await mintTo(
+    program.provider.connection,
+    payer,
+    tokenMint,
+    account.address,
+    payer,
+    amount,
+    [],
+    undefined,
+    tokenProgram
+  );
+  return account.address;
+}
+
+export async function tokenBalance(
+  connection: Connection,
+  account: PublicKey
+): Promise<number> {
+  if (!(await accountExist(connection, account))) {
+    return 0;
+  }
+  const balance = await connection.getTokenAccountBalance(account);
+  return Number(balance.value.amount);
+}
+
+/**
+ * Create the global config and the time config on a fresh validator. Under
+ * `anchor test` the provider wallet is the upgrade authority, so it becomes
+ * the admin and holds every role.
+ */
+export async function setupGlobal(program: Program<Blink>): Promise<void> {
+  const { connection } = program.provider;
+  const admin = wallet(program).publicKey;
+  const [globalConfig] = getGlobalAddress(program.programId);
+  if (!(await accountExist(connection, globalConfig))) {
+    await program.methods
+      .createGlobal()
+      .accountsPartial({
+        admin,
+        blinkProgram: program.programId,
+        programData: getProgramDataAddress(program.programId),
+        globalConfig,
+        blinkCounter: getCounterAddress(program.programId)[0],
+      })
+      .rpc();
+  }
+  const [timeConfig] = getTimeAddress(program.programId);
+  if (!(await accountExist(connection, timeConfig))) {
+    const openTime = (await chainTime(connection)) + 60;
+    await program.methods
+      .createTime(new BN(openTime), new BN(60))
+      .accountsPartial({ timeManager: admin, globalConfig, timeConfig })
+      .rpc();
+  }
+}
+
+/**
+ * Have the blinks created next open `openIn` seconds from now and stay open
+ * for `period` seconds. Returns the open and close times.
+ */
+export async function setWindow(
+  program: Program<Blink>,
+  openIn: number,
+  period: number
+): Promise<[number, number]> {
+  const openTime = (await chainTime(program.provider.connection)) + openIn;
+  await program.methods
+    .updateTime(new BN(openTime), new BN(period))
+    .accountsPartial({
+      timeManager: wallet(program).publicKey,
+      globalConfig: getGlobalAddress(program.programId)[0],
+      timeConfig: getTimeAddress(program.programId)[0],
+    })
+    .rpc();
+  return [openTime, openTime + period];
+}
+
+export type BlinkAccounts = {
+  index: BN;
+  blinkConfig: PublicKey;
+  blinkState: PublicKey;
+  vault: PublicKey;
+};
+
+/** Accounts of `initialize` and `initialize_with_metadata` for `index`. */
+export function initializeAccounts(
+  program: Program<Blink>,
+  creator: PublicKey,
+  index: BN,
+  tokenMint: PublicKey,
+  tokenProgram = TOKEN_PROGRAM_ID
+) {
+  const { programId } = program;
+  const [authority] = getAuthAddress(programId);
+  return {
+    creator,
+    authority,
+    timeConfig: getTimeAddress(programId)[0],
+    blinkCounter: getCounterAddress(programId)[0],
+    globalConfig: getGlobalAddress(programId)[0],
+    blinkConfig: getConfigAddress(index, programId)[0],
+    blinkState: getBlinkAddress(index, programId)[0],
+    tokenMint,
+    creatorToken: getSplTokenAddress(tokenMint, creator, tokenProgram),
+    vault: getSplTokenAddress(tokenMint, authority, tokenProgram),
+    tokenProgram,
+  };
+}
+
+export async function nextIndex(program: Program<Blink>): Promise<BN> {
+  const [blinkCounter] = getCounterAddress(program.programId);
+  return (await program.account.blinkCounter.fetch(blinkCounter)).nextIndex;
+}
+
+/** Create a blink with inline content, funded with `amount` base units. */
+export async function createBlink(
+  program: Program<Blink>,
+  creator: Keypair,
+  tokenMint: PublicKey,
+  amount: BN,
+  tokenProgram = TOKEN_PROGRAM_ID,
+  remainingAccounts: AccountMeta[] = []
+): Promise<BlinkAccounts> {
+  const index = await nextIndex(program);
+  const accounts = initializeAccounts(
+    program,
+    creator.publicKey,
+    index,
+    tokenMint,
+    tokenProgram
+  );
+  await program.methods
+    .initialize(amount, "pic", "content", "op1", "op2", "op3", "op4")
+    .accountsPartial(accounts)
+    .remainingAccounts(remainingAccounts)
+    .signers([creator])
+    .rpc();
+  return {
+    index,
+    blinkConfig: accounts.blinkConfig,
+    blinkState: accounts.blinkState,
+    vault: accounts.vault,
+  };
+}
+
+export function submitBlink(
+  program: Program<Blink>,
+  user: Keypair,
+  index: BN,
+  answer: number,
+  {
+    payer = user,
+    profile = false,
+  }: { payer?: Keypair; profile?: boolean } = {}
+) {
+  const { programId } = program;
+  return program.methods
+    .submit(index, answer)
+    .accountsPartial({
+      user: user.publicKey,
+      payer: payer.publicKey,
+      globalConfig: getGlobalAddress(programId)[0],
+      submitState: getSubmitAddress(index, user.publicKey, programId)[0],
+      blinkState: getBlinkAddress(index, programId)[0],
+      userProfile: profile
+        ? getProfileAddress(user.publicKey, programId)[0]
+        : null,
+    })
+    .signers(payer === user ? [user] : [user, payer]);
+}
+
+export function closeBlink(
+  program: Program<Blink>,
+  resolver: Keypair,
+  index: BN,
+  answer: number
+) {
+  const { programId } = program;
+  return program.methods
+    .close(index, answer)
+    .accountsPartial({
+      payer: resolver.publicKey,
+      globalConfig: getGlobalAddress(programId)[0],
+      blinkState: getBlinkAddress(index, programId)[0],
+    })
+    .signers([resolver]);
+}
+
+/**
+ * With `series` the claim counts towards the user's streak; `streakBlinks`
+ * are the blinks right before `index` in the series, newest first.
+ */
+export function claimBlink(
+  program: Program<Blink>,
+  user: Keypair,
+  index: BN,
+  tokenMint: PublicKey,
+  {
+    payer = user,
+    tokenProgram = TOKEN_PROGRAM_ID,
+    profile = false,
+    series = null,
+    streakBlinks = [],
+    remainingAccounts = [],
+  }: {
+    payer?: Keypair;
+    tokenProgram?: PublicKey;
+    profile?: boolean;
+    series?: BN | null;
+    streakBlinks?: BN[];
+    remainingAccounts?: AccountMeta[];
+  } = {}
+) {
+  const { programId } = program;
+  const [authority] = getAuthAddress(programId);
+  const streakAccounts: AccountMeta[] = [];
+  for (const blink of streakBlinks) {
+    streakAccounts.push(
+      {
+        pubkey: getBlinkAddress(blink, programId)[0],
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: getSubmitAddress(blink, user.publicKey, programId)[0],
+        isSigner: false,
+        isWritable: false,
+      }
+    );
+  }
+  return program.methods
+    .claim(index)
+    .accountsPartial({
+      user: user.publicKey,
+      payer: payer.publicKey,
+      globalConfig: getGlobalAddress(programId)[0],
+      submitState: getSubmitAddress(index, user.publicKey, programId)[0],
+      blinkState: getBlinkAddress(index, programId)[0],
+      authority,
+      userAccount: getSplTokenAddress(tokenMint, user.publicKey, tokenProgram),
+      vault: getSplTokenAddress(tokenMint, authority, tokenProgram),
+      tokenMint,
+      tokenProgram,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      userProfile: profile
+        ? getProfileAddress(user.publicKey, programId)[0]
+        : null,
+      series: series === null ? null : getSeriesAddress(series, programId)[0],
+      streak:
+        series === null
+          ? null
+          : getStreakAddress(series, user.publicKey, programId)[0],
+      seriesVault:
+        series === null
+          ? null
+          : getSplTokenAddress(
+              tokenMint,
+              getSeriesAddress(series, programId)[0],
+              tokenProgram
+            ),
+    })
+    .remainingAccounts([...streakAccounts, ...remainingAccounts])
+    .signers(payer === user ? [user] : [user, payer]);
+}
+
+/**
+ * Expect `promise` to fail with the program error `code`, or any failure
+ * whose message or logs mention it, such as `already in use`.
+ */
+export async function expectError(
+  promise: Promise<unknown>,
+  code: string
+): Promise<void> {
+  try {
+    await promise;
+  } catch (err) {
+    if (err instanceof anchor.AnchorError) {
+      expect(err.error.errorCode.code).to.equal(code);
+      return;
+    }
+    const logs: string[] = err.logs ?? [];
+    expect(`${err}\n${logs.join("\n")}`).to.include(code);
+    return;
+  }
+  expect.fail(`expected ${code}`);
+}
@@ -2,3 +2,4 @@ export * from "./pda";
 export * from "./util";
 export * from "./web3";
 export * from "./token";
+export * from "./blink";
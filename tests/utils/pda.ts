@@ -1,88 +1,278 @@
-import * as anchor from "@coral-xyz/anchor";
-import { BN } from "@coral-xyz/anchor";
-import { PublicKey } from "@solana/web3.js";
-
-import { getAssociatedTokenAddressSync } from "@solana/spl-token";
-
-export const AUTH_SEED = Buffer.from(
-  anchor.utils.bytes.utf8.encode("auth_seed")
-);
-
-export const TIME_SEED = Buffer.from(
-  anchor.utils.bytes.utf8.encode("time_seed")
-);
-
-export const CONFIG_SEED = Buffer.from(
-  anchor.utils.bytes.utf8.encode("config_seed")
-);
-
-export const BLINK_SEED = Buffer.from(
-  anchor.utils.bytes.utf8.encode("blink_seed")
-);
-
-export const SUBMIT_SEED = Buffer.from(
-  anchor.utils.bytes.utf8.encode("submit_seed")
-);
-
-export function numberToBytes(index: number) {
-  const num = new BN(index);
-  return num.toArrayLike(Buffer, "le", 2);
-}
-
-export function getTimeAddress(programId: PublicKey): [PublicKey, number] {
-  const [address, bump] = PublicKey.findProgramAddressSync(
-    [TIME_SEED],
-    programId
-  );
-  return [address, bump];
-}
-
-export function getAuthAddress(programId: PublicKey): [PublicKey, number] {
-  const [address, bump] = PublicKey.findProgramAddressSync(
-    [AUTH_SEED],
-    programId
-  );
-  return [address, bump];
-}
-
-export function getConfigAddress(
-  index: number,
-  programId: PublicKey
-): [PublicKey, number] {
-  const [address, bump] = PublicKey.findProgramAddressSync(
-    [CONFIG_SEED, numberToBytes(index)],
-    programId
-  );
-  return [address, bump];
-}
-
-export function getBlinkAddress(
-  index: number,
-  programId: PublicKey
-): [PublicKey, number] {
-  const [address, bump] = PublicKey.findProgramAddressSync(
-    [BLINK_SEED, numberToBytes(index)],
-    programId
-  );
-  return [address, bump];
-}
-
-export function getSplTokenAddress(
-  tokenMint: PublicKey,
-  authority: PublicKey,
-  programId: PublicKey
-): PublicKey {
-  return getAssociatedTokenAddressSync(tokenMint, authority, true, programId);
-}
-
-export function getSubmitAddress(
-  index: number,
-  user: PublicKey,
-  programId: PublicKey
-): [PublicKey, number] {
-  const [address, bump] = PublicKey.findProgramAddressSync(
-    [SUBMIT_SEED, numberToBytes(index), user.toBuffer()],
-    programId
-  );
-  return [address, bump];
-}
+import * as anchor from "@coral-xyz/anchor";
+import { BN } from "@coral-xyz/anchor";
+import { PublicKey } from "@solana/web3.js";
+
+import { getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+export const AUTH_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("auth_seed")
+);
+
+export const COUNTER_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("counter_seed")
+);
+
+export const TIME_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("time_seed")
+);
+
+export const CONFIG_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("config_seed")
+);
+
+export const BLINK_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("blink_seed")
+);
+
+export const SUBMIT_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("submit_seed")
+);
+
+export function numberToBytes(index: number | BN) {
+  const num = new BN(index);
+  return num.toArrayLike(Buffer, "le", 8);
+}
+
+export function getCounterAddress(programId: PublicKey): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [COUNTER_SEED],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getTimeAddress(programId: PublicKey): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [TIME_SEED],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getAuthAddress(programId: PublicKey): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [AUTH_SEED],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getConfigAddress(
+  index: number | BN,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [CONFIG_SEED, numberToBytes(index)],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getBlinkAddress(
+  index: number | BN,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [BLINK_SEED, numberToBytes(index)],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getSplTokenAddress(
+  tokenMint: PublicKey,
+  authority: PublicKey,
+  programId: PublicKey
+): PublicKey {
+  return getAssociatedTokenAddressSync(tokenMint, authority, true, programId);
+}
+
+export function getSubmitAddress(
+  index: number | BN,
+  user: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [SUBMIT_SEED, numberToBytes(index), user.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
+export const GLOBAL_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("global_seed")
+);
+
+export const TRANSLATION_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("translation_seed")
+);
+
+export const PROFILE_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("profile_seed")
+);
+
+export const SEASON_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("season_seed")
+);
+
+export const SEASON_SCORE_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("season_score_seed")
+);
+
+export const SCORE_MARKER_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("score_marker_seed")
+);
+
+export const SERIES_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("series_seed")
+);
+
+export const STREAK_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("streak_seed")
+);
+
+export const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+
+export function getGlobalAddress(programId: PublicKey): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [GLOBAL_SEED],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getProgramDataAddress(programId: PublicKey): PublicKey {
+  const [address] = PublicKey.findProgramAddressSync(
+    [programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_ID
+  );
+  return address;
+}
+
+export function getTranslationAddress(
+  index: number | BN,
+  lang: string,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [TRANSLATION_SEED, numberToBytes(index), Buffer.from(lang)],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getProfileAddress(
+  user: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [PROFILE_SEED, user.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getSeasonAddress(
+  id: number | BN,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [SEASON_SEED, numberToBytes(id)],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getSeasonScoreAddress(
+  id: number | BN,
+  user: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [SEASON_SCORE_SEED, numberToBytes(id), user.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getScoreMarkerAddress(
+  id: number | BN,
+  index: number | BN,
+  user: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [
+      SCORE_MARKER_SEED,
+      numberToBytes(id),
+      numberToBytes(index),
+      user.toBuffer(),
+    ],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getSeriesAddress(
+  id: number | BN,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [SERIES_SEED, numberToBytes(id)],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getStreakAddress(
+  id: number | BN,
+  user: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [STREAK_SEED, numberToBytes(id), user.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
+// accounts created before the u64 index, seeded with the u16 index
+export function legacyIndexBytes(index: number) {
+  return new BN(index).toArrayLike(Buffer, "le", 2);
+}
+
+export function getLegacyConfigAddress(
+  index: number,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [CONFIG_SEED, legacyIndexBytes(index)],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getLegacyBlinkAddress(
+  index: number,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [BLINK_SEED, legacyIndexBytes(index)],
+    programId
+  );
+  return [address, bump];
+}
+
+export function getLegacySubmitAddress(
+  index: number,
+  user: PublicKey,
+  programId: PublicKey
+): [PublicKey, number] {
+  const [address, bump] = PublicKey.findProgramAddressSync(
+    [SUBMIT_SEED, legacyIndexBytes(index), user.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
@@ -0,0 +1,88 @@
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { Blink } from "../target/types/blink";
+import { Keypair, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import {
+  NATIVE_MINT,
+  TOKEN_PROGRAM_ID,
+  closeAccount,
+  createWrappedNativeAccount,
+} from "@solana/spl-token";
+import { expect } from "chai";
+import {
+  BlinkAccounts,
+  claimBlink,
+  closeBlink,
+  createBlink,
+  expectError,
+  getSplTokenAddress,
+  newWallet,
+  setupGlobal,
+  setWindow,
+  submitBlink,
+  tokenBalance,
+  waitUntil,
+} from "./utils";
+
+const POT = LAMPORTS_PER_SOL / 2;
+
+describe("wsol", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+
+  const program = anchor.workspace.Blink as Program<Blink>;
+  const { connection } = program.provider;
+
+  let creator: Keypair, payer: Keypair, user: Keypair;
+  let blink: BlinkAccounts;
+  let openTime: number, closeTime: number;
+
+  before(async () => {
+    await setupGlobal(program);
+    creator = await newWallet(connection);
+    payer = await newWallet(connection);
+    // holds no SOL, the payer covers its rent and fees
+    user = Keypair.generate();
+    await createWrappedNativeAccount(
+      connection,
+      creator,
+      creator.publicKey,
+      POT
+    );
+
+    [openTime, closeTime] = await setWindow(program, 2, 4);
+    blink = await createBlink(program, creator, NATIVE_MINT, new BN(POT));
+  });
+
+  it("holds the wrapped SOL in the vault", async () => {
+    const state = await program.account.blinkState.fetch(blink.blinkState);
+    expect(state.tokenMint.toBase58()).to.equal(NATIVE_MINT.toBase58());
+    expect(state.amount.toNumber()).to.equal(POT);
+    expect(await tokenBalance(connection, blink.vault)).to.be.at.least(POT);
+  });
+
+  it("pays out SOL to a user without any", async () => {
+    await waitUntil(connection, openTime);
+    await submitBlink(program, user, blink.index, 4, { payer }).rpc();
+    await waitUntil(connection, closeTime);
+    await closeBlink(program, creator, blink.index, 4).rpc();
+    await claimBlink(program, user, blink.index, NATIVE_MINT, { payer }).rpc();
+
+    const userToken = getSplTokenAddress(
+      NATIVE_MINT,
+      user.publicKey,
+      TOKEN_PROGRAM_ID
+    );
+    expect(await tokenBalance(connection, userToken)).to.equal(POT);
+
+    // closing the wrapped account unwraps the reward, rent included
+    await closeAccount(connection, payer, userToken, user.publicKey, user);
+    expect(await connection.getBalance(user.publicKey)).to.be.at.least(POT);
+  });
+
+  it("rejects claiming again", async () => {
+    await expectError(
+      claimBlink(program, user, blink.index, NATIVE_MINT, { payer }).rpc(),
+      "ClaimAlready"
+    );
+  });
+});